@@ -49,6 +49,67 @@ impl AtomicF32 {
     pub fn set(&self, value: f32) {
         self.atomic.store(value.to_bits(), Ordering::Relaxed)
     }
+
+    /// Set the value of the atomic float to `value`, but only if it differs from the
+    /// current value. Returns `true` if a write happened.
+    ///
+    /// This avoids redundant stores (and the resulting cache-line contention with readers)
+    /// when the same value is set repeatedly.
+    #[inline]
+    pub fn set_if_changed(&self, value: f32) -> bool {
+        let bits = value.to_bits();
+        if self.atomic.load(Ordering::Relaxed) == bits {
+            false
+        } else {
+            self.atomic.store(bits, Ordering::Relaxed);
+            true
+        }
+    }
+
+    /// Set the value of the atomic float to `new` if the current value is `current`.
+    ///
+    /// Returns `Ok` with the previous value if the value was updated, or `Err` with the
+    /// actual current value otherwise.
+    ///
+    /// Note that the values are compared by their bit patterns, not with `==`. This means
+    /// that `0.0` and `-0.0` are *NOT* considered equal, while a `NaN` *is* considered equal
+    /// to a `NaN` with the same bits (i.e. `f32::NAN` matches a stored `f32::NAN`).
+    #[inline]
+    pub fn compare_exchange(&self, current: f32, new: f32) -> Result<f32, f32> {
+        self.atomic
+            .compare_exchange(
+                current.to_bits(),
+                new.to_bits(),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .map(f32::from_bits)
+            .map_err(f32::from_bits)
+    }
+
+    /// Atomically update the value of the atomic float with the function `f`, retrying if
+    /// the value was changed by another thread in the meantime.
+    ///
+    /// The function may be called multiple times, and can return `None` to leave the value
+    /// unchanged. Returns `Ok` with the previous value if `f` returned `Some`, or `Err` with
+    /// the current value otherwise.
+    ///
+    /// Since this compares the bit patterns of the values (see
+    /// `AtomicF32::compare_exchange()`), it never spins forever on a stored `NaN`.
+    ///
+    /// * `f` - The function that computes the new value from the current value.
+    #[inline]
+    pub fn fetch_update<F>(&self, mut f: F) -> Result<f32, f32>
+    where
+        F: FnMut(f32) -> Option<f32>,
+    {
+        self.atomic
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                f(f32::from_bits(bits)).map(f32::to_bits)
+            })
+            .map(f32::from_bits)
+            .map_err(f32::from_bits)
+    }
 }
 
 impl Default for AtomicF32 {
@@ -108,6 +169,51 @@ impl AtomicF64 {
     pub fn set(&self, value: f64) {
         self.atomic.store(value.to_bits(), Ordering::Relaxed)
     }
+
+    /// Set the value of the atomic float to `new` if the current value is `current`.
+    ///
+    /// Returns `Ok` with the previous value if the value was updated, or `Err` with the
+    /// actual current value otherwise.
+    ///
+    /// Note that the values are compared by their bit patterns, not with `==`. This means
+    /// that `0.0` and `-0.0` are *NOT* considered equal, while a `NaN` *is* considered equal
+    /// to a `NaN` with the same bits (i.e. `f64::NAN` matches a stored `f64::NAN`).
+    #[inline]
+    pub fn compare_exchange(&self, current: f64, new: f64) -> Result<f64, f64> {
+        self.atomic
+            .compare_exchange(
+                current.to_bits(),
+                new.to_bits(),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .map(f64::from_bits)
+            .map_err(f64::from_bits)
+    }
+
+    /// Atomically update the value of the atomic float with the function `f`, retrying if
+    /// the value was changed by another thread in the meantime.
+    ///
+    /// The function may be called multiple times, and can return `None` to leave the value
+    /// unchanged. Returns `Ok` with the previous value if `f` returned `Some`, or `Err` with
+    /// the current value otherwise.
+    ///
+    /// Since this compares the bit patterns of the values (see
+    /// `AtomicF64::compare_exchange()`), it never spins forever on a stored `NaN`.
+    ///
+    /// * `f` - The function that computes the new value from the current value.
+    #[inline]
+    pub fn fetch_update<F>(&self, mut f: F) -> Result<f64, f64>
+    where
+        F: FnMut(f64) -> Option<f64>,
+    {
+        self.atomic
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                f(f64::from_bits(bits)).map(f64::to_bits)
+            })
+            .map(f64::from_bits)
+            .map_err(f64::from_bits)
+    }
 }
 
 impl Default for AtomicF64 {
@@ -139,3 +245,71 @@ impl From<AtomicF64> for f64 {
         value.get()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_if_changed() {
+        let a = AtomicF32::new(0.0);
+
+        assert!(a.set_if_changed(0.5));
+        assert!(!a.set_if_changed(0.5));
+        assert_eq!(a.get(), 0.5);
+
+        assert!(a.set_if_changed(0.25));
+        assert_eq!(a.get(), 0.25);
+    }
+
+    #[test]
+    fn test_compare_exchange() {
+        let a = AtomicF32::new(0.5);
+
+        assert_eq!(a.compare_exchange(0.25, 1.0), Err(0.5));
+        assert_eq!(a.compare_exchange(0.5, 1.0), Ok(0.5));
+        assert_eq!(a.get(), 1.0);
+
+        // Values are compared by their bits.
+        let a = AtomicF64::new(0.0);
+        assert_eq!(a.compare_exchange(-0.0, 1.0), Err(0.0));
+
+        a.set(f64::NAN);
+        assert!(a.compare_exchange(0.0, 1.0).unwrap_err().is_nan());
+        assert!(a.compare_exchange(f64::NAN, 1.0).unwrap().is_nan());
+        assert_eq!(a.get(), 1.0);
+
+        a.set(f64::NAN);
+        assert!(a
+            .fetch_update(|v| if v.is_nan() { Some(0.0) } else { None })
+            .unwrap()
+            .is_nan());
+        assert_eq!(a.get(), 0.0);
+        assert_eq!(a.fetch_update(|_| None), Err(0.0));
+    }
+
+    #[test]
+    fn test_fetch_update_max() {
+        use std::sync::Arc;
+        use std::thread;
+
+        let peak = Arc::new(AtomicF32::new(0.0));
+
+        let threads: Vec<_> = (0..2)
+            .map(|t| {
+                let peak = Arc::clone(&peak);
+                thread::spawn(move || {
+                    for i in 0..10_000 {
+                        let value = (i * 2 + t) as f32;
+                        let _ = peak.fetch_update(|v| if value > v { Some(value) } else { None });
+                    }
+                })
+            })
+            .collect();
+        for t in threads {
+            t.join().unwrap();
+        }
+
+        assert_eq!(peak.get(), 19_999.0);
+    }
+}
@@ -0,0 +1,90 @@
+//! Direct-form convolution for short impulse responses.
+
+/// Convolve a block of `frames` frames of `input` with the impulse response `ir`, writing the
+/// result into `out`.
+///
+/// This is a direct-form FIR filter, so it is only suitable for short impulse responses.
+/// This does not allocate.
+///
+/// * `input` - The input frames. This must contain at least `frames` frames.
+/// * `ir` - The impulse response. This must not be empty.
+/// * `state` - The filter state, which holds the last `ir.len() - 1` input frames from the
+///   previous block. This must have a length of exactly `ir.len() - 1`, and it should be
+///   zeroed before processing the first block.
+/// * `out` - The output frames. This must contain at least `frames` frames.
+/// * `frames` - The number of frames to process.
+pub fn convolve(input: &[f32], ir: &[f32], state: &mut [f32], out: &mut [f32], frames: usize) {
+    assert!(!ir.is_empty());
+    assert_eq!(state.len(), ir.len() - 1);
+
+    let input = &input[..frames];
+    let out = &mut out[..frames];
+
+    for (n, out) in out.iter_mut().enumerate() {
+        let mut acc = 0.0;
+        for (k, coeff) in ir.iter().enumerate() {
+            let x = if k <= n {
+                input[n - k]
+            } else {
+                state[state.len() - (k - n)]
+            };
+
+            acc += coeff * x;
+        }
+
+        *out = acc;
+    }
+
+    // Keep the last `ir.len() - 1` input frames for the next block.
+    let history = state.len();
+    if frames >= history {
+        state.copy_from_slice(&input[frames - history..]);
+    } else {
+        state.copy_within(frames.., 0);
+        state[history - frames..].copy_from_slice(input);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_impulse() {
+        let input = [1.0, 2.0, 3.0, 4.0];
+        let mut out = [0.0; 4];
+        let mut state = [];
+
+        convolve(&input, &[1.0], &mut state, &mut out, 4);
+        assert_eq!(out, input);
+    }
+
+    #[test]
+    fn test_two_tap_ir_across_blocks() {
+        let ir = [0.5, 0.25];
+        let mut state = [0.0];
+        let mut out = [0.0; 3];
+
+        convolve(&[1.0, 2.0, 3.0], &ir, &mut state, &mut out, 3);
+        assert_eq!(out, [0.5, 1.25, 2.0]);
+
+        // The last input frame of the previous block carries over.
+        convolve(&[4.0, 5.0, 6.0], &ir, &mut state, &mut out, 3);
+        assert_eq!(out, [2.75, 3.5, 4.25]);
+    }
+
+    #[test]
+    fn test_block_shorter_than_ir() {
+        let ir = [1.0, 1.0, 1.0, 1.0];
+        let mut state = [0.0; 3];
+        let mut out = [0.0; 1];
+
+        // A running sum of the last 4 input frames.
+        let mut results = Vec::new();
+        for x in [1.0, 2.0, 3.0, 4.0, 5.0] {
+            convolve(&[x], &ir, &mut state, &mut out, 1);
+            results.push(out[0]);
+        }
+        assert_eq!(results, vec![1.0, 3.0, 6.0, 10.0, 14.0]);
+    }
+}
@@ -10,6 +10,21 @@ pub fn coeff_to_db_f32(coeff: f32) -> f32 {
     20.0 * coeff.log(10.0)
 }
 
+/// Returns the decibel value from the raw amplitude (coefficient), or `None` if the
+/// coefficient is invalid (negative or `NaN`).
+///
+/// A coefficient of `0.0` is valid and will return negative infinity dB.
+///
+/// This is useful for validating gain values coming from untrusted sources.
+#[inline]
+pub fn coeff_to_db_checked_f32(coeff: f32) -> Option<f32> {
+    if coeff.is_nan() || coeff < 0.0 {
+        None
+    } else {
+        Some(coeff_to_db_f32(coeff))
+    }
+}
+
 /// Returns the raw amplitude (coefficient) from the given decibel value.
 ///
 /// If `db <= -90.0`, then 0.0 will be returned instead (negative infinity gain).
@@ -36,6 +51,57 @@ pub fn coeff_to_db_clamped_neg_90_db_f32(coeff: f32) -> f32 {
     }
 }
 
+/// Returns the raw amplitude (coefficient) from the given decibel value.
+///
+/// If `db <= floor_db`, then 0.0 will be returned instead (negative infinity gain).
+#[inline]
+pub fn db_to_coeff_clamped_f32(db: f32, floor_db: f32) -> f32 {
+    if db <= floor_db {
+        0.0
+    } else {
+        db_to_coeff_f32(db)
+    }
+}
+
+/// Returns the decibel value from the raw amplitude (coefficient).
+///
+/// If the coefficient is less than or equal to the coefficient of `floor_db`, then
+/// `floor_db` will be returned instead (representing negative infinity gain when paired
+/// with `db_to_coeff_clamped_f32`).
+#[inline]
+pub fn coeff_to_db_clamped_f32(coeff: f32, floor_db: f32) -> f32 {
+    if coeff <= db_to_coeff_f32(floor_db) {
+        floor_db
+    } else {
+        coeff_to_db_f32(coeff)
+    }
+}
+
+/// Returns the gain (coefficient) that compensates for the change in perceived loudness
+/// caused by applying a gain of `db` decibels.
+///
+/// * `db` - The gain that was applied, in decibels.
+/// * `slope` - How much of the change to compensate for, where `1.0` fully cancels it
+///   out and `0.0` applies no compensation. Values around `0.5` to `0.7` tend to sound
+///   natural, since the ear doesn't perceive loudness linearly with gain.
+///
+/// This is useful for auto-gain, so that turning up a drive or gain parameter doesn't make
+/// the result sound "better" just because it is louder.
+#[inline]
+pub fn equal_loudness_gain_f32(db: f32, slope: f32) -> f32 {
+    db_to_coeff_f32(-db * slope)
+}
+
+/// Returns the adjustment in decibels that needs to be applied to a signal with the
+/// amplitude `measured_coeff` in order to match the amplitude `reference_coeff` (i.e. from
+/// an RMS or peak measurement).
+///
+/// If `measured_coeff` is `0.0`, then positive infinity will be returned.
+#[inline]
+pub fn gain_match_f32(reference_coeff: f32, measured_coeff: f32) -> f32 {
+    coeff_to_db_f32(reference_coeff) - coeff_to_db_f32(measured_coeff)
+}
+
 /// Returns the raw amplitude (coefficient) from the given decibel value.
 #[inline]
 pub fn db_to_coeff_f64(db: f64) -> f64 {
@@ -48,6 +114,21 @@ pub fn coeff_to_db_f64(coeff: f64) -> f64 {
     20.0 * coeff.log(10.0)
 }
 
+/// Returns the decibel value from the raw amplitude (coefficient), or `None` if the
+/// coefficient is invalid (negative or `NaN`).
+///
+/// A coefficient of `0.0` is valid and will return negative infinity dB.
+///
+/// This is useful for validating gain values coming from untrusted sources.
+#[inline]
+pub fn coeff_to_db_checked_f64(coeff: f64) -> Option<f64> {
+    if coeff.is_nan() || coeff < 0.0 {
+        None
+    } else {
+        Some(coeff_to_db_f64(coeff))
+    }
+}
+
 /// Returns the raw amplitude (coefficient) from the given decibel value.
 ///
 /// If `db <= -90.0`, then 0.0 will be returned instead (negative infinity gain).
@@ -73,3 +154,90 @@ pub fn coeff_to_db_clamped_neg_90_db_f64(coeff: f64) -> f64 {
         coeff_to_db_f64(coeff)
     }
 }
+
+/// Returns the raw amplitude (coefficient) from the given decibel value.
+///
+/// If `db <= floor_db`, then 0.0 will be returned instead (negative infinity gain).
+#[inline]
+pub fn db_to_coeff_clamped_f64(db: f64, floor_db: f64) -> f64 {
+    if db <= floor_db {
+        0.0
+    } else {
+        db_to_coeff_f64(db)
+    }
+}
+
+/// Returns the decibel value from the raw amplitude (coefficient).
+///
+/// If the coefficient is less than or equal to the coefficient of `floor_db`, then
+/// `floor_db` will be returned instead (representing negative infinity gain when paired
+/// with `db_to_coeff_clamped_f64`).
+#[inline]
+pub fn coeff_to_db_clamped_f64(coeff: f64, floor_db: f64) -> f64 {
+    if coeff <= db_to_coeff_f64(floor_db) {
+        floor_db
+    } else {
+        coeff_to_db_f64(coeff)
+    }
+}
+
+/// Returns the gain (coefficient) that compensates for the change in perceived loudness
+/// caused by applying a gain of `db` decibels.
+///
+/// * `db` - The gain that was applied, in decibels.
+/// * `slope` - How much of the change to compensate for, where `1.0` fully cancels it
+///   out and `0.0` applies no compensation. Values around `0.5` to `0.7` tend to sound
+///   natural, since the ear doesn't perceive loudness linearly with gain.
+///
+/// This is useful for auto-gain, so that turning up a drive or gain parameter doesn't make
+/// the result sound "better" just because it is louder.
+#[inline]
+pub fn equal_loudness_gain_f64(db: f64, slope: f64) -> f64 {
+    db_to_coeff_f64(-db * slope)
+}
+
+/// Returns the adjustment in decibels that needs to be applied to a signal with the
+/// amplitude `measured_coeff` in order to match the amplitude `reference_coeff` (i.e. from
+/// an RMS or peak measurement).
+///
+/// If `measured_coeff` is `0.0`, then positive infinity will be returned.
+#[inline]
+pub fn gain_match_f64(reference_coeff: f64, measured_coeff: f64) -> f64 {
+    coeff_to_db_f64(reference_coeff) - coeff_to_db_f64(measured_coeff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coeff_to_db_checked() {
+        assert_eq!(coeff_to_db_checked_f32(-0.5), None);
+        assert_eq!(coeff_to_db_checked_f32(f32::NAN), None);
+        assert_eq!(coeff_to_db_checked_f32(0.0), Some(f32::NEG_INFINITY));
+        assert!((coeff_to_db_checked_f32(0.5).unwrap() - -6.0206).abs() < 0.0001);
+
+        assert_eq!(coeff_to_db_checked_f64(-0.5), None);
+        assert_eq!(coeff_to_db_checked_f64(f64::NAN), None);
+        assert_eq!(coeff_to_db_checked_f64(0.0), Some(f64::NEG_INFINITY));
+        assert!((coeff_to_db_checked_f64(0.5).unwrap() - -6.0206).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_gain_match() {
+        assert_eq!(gain_match_f32(0.5, 0.5), 0.0);
+        assert_eq!(gain_match_f32(1.0, 1.0), 0.0);
+        assert_eq!(gain_match_f64(0.25, 0.25), 0.0);
+        assert!((gain_match_f32(1.0, 0.5) - 6.0206).abs() < 0.0001);
+        assert!((gain_match_f64(0.5, 1.0) - -6.0206).abs() < 0.0001);
+        assert_eq!(gain_match_f32(1.0, 0.0), f32::INFINITY);
+    }
+
+    #[test]
+    fn test_equal_loudness_gain() {
+        assert_eq!(equal_loudness_gain_f32(0.0, 0.5), 1.0);
+        assert_eq!(equal_loudness_gain_f64(12.0, 0.0), 1.0);
+        assert!((equal_loudness_gain_f32(6.0, 1.0) - db_to_coeff_f32(-6.0)).abs() < 1.0e-6);
+        assert!((equal_loudness_gain_f64(-12.0, 0.5) - db_to_coeff_f64(6.0)).abs() < 1.0e-9);
+    }
+}
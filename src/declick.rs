@@ -69,7 +69,7 @@ where
         self.fade.set_speed(sample_rate, seconds);
     }
 
-    pub fn output(&self) -> DeclickOutput<T> {
+    pub fn output(&self) -> DeclickOutput<'_, T> {
         let fade = self.fade.output();
 
         DeclickOutput {
@@ -90,7 +90,7 @@ where
     pub fn dest(&self) -> &T {
         self.staged
             .as_ref()
-            .or_else(|| self.next.as_ref())
+            .or(self.next.as_ref())
             .unwrap_or(&self.current)
     }
 
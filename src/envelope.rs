@@ -0,0 +1,249 @@
+//! Envelope generators.
+
+use crate::smooth::SmoothF32;
+use crate::time::{SampleRate, SecondsF64};
+
+/// The current stage of an [`Adsr`] envelope.
+///
+/// [`Adsr`]: struct.Adsr.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdsrStage {
+    /// The envelope is silent.
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+}
+
+/// An ADSR (attack, decay, sustain, release) envelope generator.
+///
+/// Each of the attack, decay, and release stages is a linear ramp that lasts exactly the
+/// given time (rounded to the nearest frame).
+#[derive(Debug)]
+pub struct Adsr {
+    attack: SecondsF64,
+    decay: SecondsF64,
+    sustain: f32,
+    release: SecondsF64,
+    sample_rate: SampleRate,
+
+    stage: AdsrStage,
+    frames_left_in_stage: u64,
+    smooth: SmoothF32,
+}
+
+impl Adsr {
+    /// * `attack` - The time it takes to rise from the current level to `1.0`.
+    /// * `decay` - The time it takes to fall from `1.0` to the `sustain` level.
+    /// * `sustain` - The level held while the note is held, in the range `[0.0, 1.0]`.
+    /// * `release` - The time it takes to fall from the current level to `0.0` after the note
+    ///   is released.
+    /// * `sample_rate` - The sample rate of the stream.
+    /// * `max_blocksize` - The maximum number of frames that will be processed at a time.
+    pub fn new(
+        attack: SecondsF64,
+        decay: SecondsF64,
+        sustain: f32,
+        release: SecondsF64,
+        sample_rate: SampleRate,
+        max_blocksize: usize,
+    ) -> Self {
+        Self {
+            attack,
+            decay,
+            sustain: sustain.clamp(0.0, 1.0),
+            release,
+            sample_rate,
+
+            stage: AdsrStage::Idle,
+            frames_left_in_stage: 0,
+            smooth: SmoothF32::new(0.0, max_blocksize),
+        }
+    }
+
+    /// Start the attack stage from the current level.
+    pub fn note_on(&mut self) {
+        self.enter_stage(AdsrStage::Attack);
+    }
+
+    /// Start the release stage from the current level.
+    pub fn note_off(&mut self) {
+        if self.stage != AdsrStage::Idle {
+            self.enter_stage(AdsrStage::Release);
+        }
+    }
+
+    /// Immediately silence the envelope.
+    pub fn reset(&mut self) {
+        self.stage = AdsrStage::Idle;
+        self.frames_left_in_stage = 0;
+        self.smooth.reset(0.0);
+    }
+
+    /// The current stage of the envelope.
+    pub fn stage(&self) -> AdsrStage {
+        self.stage
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: SampleRate) {
+        self.sample_rate = sample_rate;
+    }
+
+    /// Set the attack, decay, sustain, and release. This will take effect the next time each
+    /// stage is entered.
+    pub fn set_params(
+        &mut self,
+        attack: SecondsF64,
+        decay: SecondsF64,
+        sustain: f32,
+        release: SecondsF64,
+    ) {
+        self.attack = attack;
+        self.decay = decay;
+        self.sustain = sustain.clamp(0.0, 1.0);
+        self.release = release;
+    }
+
+    /// Write the next `frames` frames of the envelope into `out`.
+    pub fn process(&mut self, out: &mut [f32], frames: usize) {
+        let out = &mut out[..frames];
+
+        let mut done = 0;
+        while done < frames {
+            match self.stage {
+                AdsrStage::Idle => {
+                    out[done..].fill(0.0);
+                    return;
+                }
+                AdsrStage::Sustain => {
+                    out[done..].fill(self.sustain);
+                    return;
+                }
+                AdsrStage::Attack | AdsrStage::Decay | AdsrStage::Release => {
+                    let n = (frames - done)
+                        .min(self.smooth.max_blocksize())
+                        .min(self.frames_left_in_stage as usize);
+
+                    self.smooth.process(n);
+                    out[done..done + n].copy_from_slice(&self.smooth.output().values[..n]);
+
+                    done += n;
+                    self.frames_left_in_stage -= n as u64;
+
+                    if self.frames_left_in_stage == 0 {
+                        self.next_stage();
+                    }
+                }
+            }
+        }
+    }
+
+    fn next_stage(&mut self) {
+        match self.stage {
+            AdsrStage::Attack => self.enter_stage(AdsrStage::Decay),
+            AdsrStage::Decay => self.enter_stage(AdsrStage::Sustain),
+            AdsrStage::Release => self.enter_stage(AdsrStage::Idle),
+            _ => (),
+        }
+    }
+
+    fn enter_stage(&mut self, stage: AdsrStage) {
+        self.stage = stage;
+
+        let (target, time) = match stage {
+            AdsrStage::Attack => (1.0, self.attack),
+            AdsrStage::Decay => (self.sustain, self.decay),
+            AdsrStage::Release => (0.0, self.release),
+            AdsrStage::Sustain => {
+                self.smooth.reset(self.sustain);
+                return;
+            }
+            AdsrStage::Idle => {
+                self.smooth.reset(0.0);
+                return;
+            }
+        };
+
+        self.frames_left_in_stage = time.to_nearest_frame_round(self.sample_rate).0;
+        if self.frames_left_in_stage == 0 {
+            self.smooth.reset(target);
+            self.next_stage();
+        } else {
+            self.smooth.ramp_to(target, self.frames_left_in_stage);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_adsr() -> Adsr {
+        // At 1 kHz, 1 ms is one frame.
+        Adsr::new(
+            SecondsF64(0.010),
+            SecondsF64(0.010),
+            0.5,
+            SecondsF64(0.020),
+            SampleRate(1_000.0),
+            8,
+        )
+    }
+
+    #[test]
+    fn test_attack_reaches_peak_in_attack_time() {
+        let mut adsr = test_adsr();
+        let mut out = [0.0; 32];
+
+        adsr.note_on();
+        adsr.process(&mut out, 32);
+
+        assert!(out[8] < 1.0);
+        assert_eq!(out[9], 1.0);
+        assert!(out[..10].windows(2).all(|w| w[0] < w[1]));
+
+        // Decay to the sustain level, then hold.
+        assert_eq!(out[19], 0.5);
+        assert!(out[20..].iter().all(|v| *v == 0.5));
+        assert_eq!(adsr.stage(), AdsrStage::Sustain);
+    }
+
+    #[test]
+    fn test_release_decays_to_zero_in_release_time() {
+        let mut adsr = test_adsr();
+        let mut out = [0.0; 32];
+
+        adsr.note_on();
+        adsr.process(&mut out, 32);
+
+        adsr.note_off();
+        adsr.process(&mut out, 32);
+
+        assert!(out[18] > 0.0);
+        assert_eq!(out[19], 0.0);
+        assert!(out[20..].iter().all(|v| *v == 0.0));
+        assert_eq!(adsr.stage(), AdsrStage::Idle);
+    }
+
+    #[test]
+    fn test_zero_length_stages() {
+        let mut adsr = Adsr::new(
+            SecondsF64(0.0),
+            SecondsF64(0.0),
+            0.75,
+            SecondsF64(0.0),
+            SampleRate(1_000.0),
+            8,
+        );
+        let mut out = [0.0; 4];
+
+        adsr.note_on();
+        adsr.process(&mut out, 4);
+        assert_eq!(out, [0.75; 4]);
+
+        adsr.note_off();
+        adsr.process(&mut out, 4);
+        assert_eq!(out, [0.0; 4]);
+    }
+}
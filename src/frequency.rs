@@ -0,0 +1,81 @@
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+
+use std::ops::{Mul, MulAssign};
+
+/// A frequency in Hz.
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Frequency(pub f32);
+
+impl Frequency {
+    /// The frequency of MIDI note `69` (A4), in Hz.
+    pub const A4_HZ: f32 = 440.0;
+
+    pub fn new(hz: f32) -> Self {
+        Self(hz)
+    }
+
+    /// The frequency of the given (fractional) MIDI note, tuned so that note `69` (A4) is
+    /// `440 Hz`.
+    pub fn from_midi_note(note: f32) -> Self {
+        Self(Self::A4_HZ * 2.0f32.powf((note - 69.0) / 12.0))
+    }
+
+    /// The (fractional) MIDI note of this frequency, tuned so that note `69` (A4) is `440 Hz`.
+    pub fn to_midi_note(&self) -> f32 {
+        69.0 + (12.0 * (self.0 / Self::A4_HZ).log2())
+    }
+
+    /// Transpose this frequency by the given number of (fractional) semitones.
+    pub fn transpose_semitones(&self, n: f32) -> Self {
+        Self(self.0 * 2.0f32.powf(n / 12.0))
+    }
+
+    /// Transpose this frequency by the given number of cents.
+    pub fn transpose_cents(&self, c: f32) -> Self {
+        Self(self.0 * 2.0f32.powf(c / 1_200.0))
+    }
+}
+
+impl From<f32> for Frequency {
+    fn from(hz: f32) -> Self {
+        Frequency(hz)
+    }
+}
+
+impl Mul<f32> for Frequency {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self::Output {
+        Self(self.0 * rhs)
+    }
+}
+impl MulAssign<f32> for Frequency {
+    fn mul_assign(&mut self, other: f32) {
+        self.0 *= other;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transpose() {
+        let a3 = Frequency(220.0);
+
+        assert!((a3.transpose_semitones(12.0).0 - 440.0).abs() < 1e-3);
+        assert!((a3.transpose_semitones(-12.0).0 - 110.0).abs() < 1e-3);
+        assert!((a3.transpose_cents(1_200.0).0 - 440.0).abs() < 1e-3);
+        assert!((a3.transpose_cents(100.0).0 - a3.transpose_semitones(1.0).0).abs() < 1e-3);
+
+        assert_eq!(a3 * 2.0, Frequency(440.0));
+    }
+
+    #[test]
+    fn test_midi_note() {
+        assert!((Frequency(440.0).to_midi_note() - 69.0).abs() < 1e-5);
+        assert!((Frequency(261.6256).to_midi_note() - 60.0).abs() < 1e-3);
+        assert!((Frequency::from_midi_note(81.0).0 - 880.0).abs() < 1e-3);
+    }
+}
@@ -1,6 +1,13 @@
 pub mod atomic;
+pub mod convolve;
 pub mod decibel;
 pub mod declick;
+pub mod envelope;
+pub mod frequency;
 pub mod parameter;
+pub mod resample;
 pub mod smooth;
 pub mod time;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
@@ -6,18 +6,25 @@
 //
 //  Thanks wrl! :)
 
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+
+use std::convert::TryFrom;
 use std::sync::{
-    atomic::{AtomicBool, AtomicI32, Ordering},
-    Arc,
+    atomic::{AtomicBool, AtomicI32, AtomicU64, AtomicUsize, Ordering},
+    Arc, Weak,
 };
 
 use crate::atomic::{AtomicF32, AtomicF64};
 use crate::decibel::{
-    coeff_to_db_clamped_neg_90_db_f32, coeff_to_db_clamped_neg_90_db_f64,
+    coeff_to_db_clamped_f32, coeff_to_db_clamped_f64, coeff_to_db_clamped_neg_90_db_f32,
+    coeff_to_db_clamped_neg_90_db_f64, db_to_coeff_clamped_f32, db_to_coeff_clamped_f64,
     db_to_coeff_clamped_neg_90_db_f32, db_to_coeff_clamped_neg_90_db_f64,
 };
-use crate::smooth::{SmoothF32, SmoothF64, SmoothOutputF32, SmoothOutputF64};
-use crate::time::{SampleRate, SecondsF64};
+use crate::smooth::{
+    SmoothF32, SmoothF64, SmoothMode, SmoothOutputF32, SmoothOutputF64, SmoothStateF32,
+};
+use crate::time::{FrameTime, SampleRate, SecondsF64};
 
 /// A good default value to use as `smooth_secs` parameter when creating a [`ParamF32`]/[`ParamF64`].
 ///
@@ -27,10 +34,46 @@ use crate::time::{SampleRate, SecondsF64};
 /// [`ParamF64`]: struct.ParamF64.html
 pub const DEFAULT_SMOOTH_SECS: SecondsF64 = SecondsF64(5.0 / 1_000.0);
 
+/// The distance (in normalized units) within which a value set from a [`ParamF32Handle`] is
+/// pulled toward a snap value. See `ParamF32::set_snap_values()`.
+///
+/// [`ParamF32Handle`]: struct.ParamF32Handle.html
+pub const SNAP_RADIUS_NORMALIZED: f32 = 0.02;
+
+/// The default distance (in normalized units) a new value of a [`ParamF32`] must differ from
+/// the current value by to be applied. See `ParamF32::set_change_deadband()`.
+///
+/// [`ParamF32`]: struct.ParamF32.html
+pub const DEFAULT_CHANGE_DEADBAND_NORMALIZED: f32 = 1.0e-6;
+
+/// The distance (in normalized units) within which a controller value engages a parameter
+/// in `PickupMode::Catch`, even if it has not crossed the current value. See
+/// `ParamF32Handle::set_from_controller_with_pickup()`.
+pub const PICKUP_RADIUS_NORMALIZED: f32 = 0.01;
+
+/// How a [`ParamF32Handle`] reacts to values from an external controller (i.e. a hardware
+/// knob mapped with MIDI learn) whose physical position differs from the current value of
+/// the parameter.
+///
+/// [`ParamF32Handle`]: struct.ParamF32Handle.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickupMode {
+    /// Every controller value is applied immediately, which may cause the value to jump.
+    Jump,
+    /// Controller values are ignored until they cross (or come within
+    /// `PICKUP_RADIUS_NORMALIZED` of) the current value, and then take over.
+    Catch,
+}
+
 /// A good default value to use as `gradient` parameter when creating a [`ParamF32`]/[`ParamF64`] that
 /// deals with decibels.
 pub const DEFAULT_DB_GRADIENT: Gradient = Gradient::Power(0.15);
 
+/// The smallest bound used by the `Gradient::Exponential` mapping for `f32` values.
+pub const EXPONENTIAL_MIN_F32: f32 = 1.0e-6;
+/// The smallest bound used by the `Gradient::Exponential` mapping for `f64` values.
+pub const EXPONENTIAL_MIN_F64: f64 = 1.0e-9;
+
 /// The gradient used when mapping the normalized value in the range `[0.0, 1.0]` to the
 /// desired value.
 ///
@@ -38,6 +81,7 @@ pub const DEFAULT_DB_GRADIENT: Gradient = Gradient::Power(0.15);
 /// gradient around `Power(0.15)`. This is so one tick near the top of the slider/knob
 /// controlling this parameter causes a small change in dB around `0.0 dB` and one tick
 /// on the other end causes a large change in dB around `-90.0 dB`.
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Gradient {
     /// Linear mapping
@@ -52,11 +96,36 @@ pub enum Gradient {
     /// Exponential (logarithmic) mapping
     ///
     /// This is useful for parameters dealing with frequency in Hz.
+    ///
+    /// This mapping is only defined for a positive range, so `min` and `max` are internally
+    /// clamped to be at least `EXPONENTIAL_MIN_F32` (or `EXPONENTIAL_MIN_F64`). This means a
+    /// range starting at `0.0` (i.e. DC) still gives finite values, with a normalized value of
+    /// `0.0` mapping exactly to `min`.
     Exponential,
+    /// S-curve mapping, where a normalized value of `0.5` always maps to the midpoint of the
+    /// range.
+    ///
+    /// With a positive steepness, the center of the range is fine and both extremes are
+    /// coarse (i.e. a bipolar pan or pitch knob). With a negative steepness, the center is
+    /// coarse and both extremes are fine. A steepness of `0.0` is the same as a linear
+    /// mapping. Steepness values in the range `[-4.0, 4.0]` work well. Larger magnitudes
+    /// give more extreme curves at the cost of round-trip precision near the ends.
+    SCurve(f32),
+    /// Cubic bezier mapping through `(0, 0)` and `(1, 1)` with the control points `(x1, y1)`
+    /// and `(x2, y2)`, where `x` is the normalized value and `y` is the (unscaled) position
+    /// in the range. This works the same way as the `cubic-bezier()` easing function in
+    /// CSS, so curves can be designed with any of the visual tools made for it (i.e.
+    /// `x1: 0.25, y1: 0.1, x2: 0.25, y2: 1.0` is the CSS `ease` curve).
+    ///
+    /// `x1` and `x2` are clamped to the range `[0.0, 1.0]`. `y1` and `y2` should also lie in
+    /// the range `[0.0, 1.0]`, otherwise the mapping may not be monotonic (and therefore not
+    /// invertible).
+    Bezier { x1: f32, y1: f32, x2: f32, y2: f32 },
 }
 
 /// The unit of this parameter. This signifies how the value displayed to the end user should
 /// differ from the actual value used in DSP.
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Unit {
     /// Any kind of unit where the value displayed to the end user is the same value used
@@ -69,16 +138,45 @@ pub enum Unit {
     /// resulting raw DSP ampilitude value will be clamped to `0.0` (essentially equaling
     /// `-infinity dB`).
     Decibels,
+    /// Same as `Unit::Decibels`, except that the raw DSP amplitude value is clamped to `0.0`
+    /// whenever the dB value is less than or equal to the given floor (in dB) instead of
+    /// `-90.0 dB` (i.e. `-120.0` for mastering).
+    DecibelsWithFloor(f32),
+    /// Signifies that the value displayed to the end user should be in percent (i.e.
+    /// `0.0..=100.0`) and the value used in the DSP should be a fraction (i.e. `0.0..=1.0`).
+    Percent,
+    /// Signifies that the value displayed to the end user should be in semitones and the
+    /// value used in the DSP should be a pitch (frequency) ratio, where
+    /// `semitones = 12 * log2(ratio)`.
+    Semitones,
+    /// Signifies that the value is a frequency in Hz. The value displayed to the end user is
+    /// the same value used in the DSP.
+    Hz,
 }
 
 impl Unit {
+    /// The suffix to append to the unit value when displaying it to the end user (i.e.
+    /// `"dB"` or `"%"`).
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Unit::Generic => "",
+            Unit::Decibels | Unit::DecibelsWithFloor(_) => "dB",
+            Unit::Percent => "%",
+            Unit::Semitones => "st",
+            Unit::Hz => "Hz",
+        }
+    }
+
     /// Convert the given unit value to the corresponding raw value used in DSP.
     ///
     /// This is only effective when this unit is not of type `Unit::Generic`.
     pub fn unit_to_dsp_f32(&self, value: f32) -> f32 {
         match self {
             Unit::Decibels => db_to_coeff_clamped_neg_90_db_f32(value),
-            _ => value,
+            Unit::DecibelsWithFloor(floor_db) => db_to_coeff_clamped_f32(value, *floor_db),
+            Unit::Percent => value / 100.0,
+            Unit::Semitones => 2.0f32.powf(value / 12.0),
+            Unit::Generic | Unit::Hz => value,
         }
     }
 
@@ -88,7 +186,10 @@ impl Unit {
     pub fn dsp_to_unit_f32(&self, dsp_value: f32) -> f32 {
         match self {
             Unit::Decibels => coeff_to_db_clamped_neg_90_db_f32(dsp_value),
-            _ => dsp_value,
+            Unit::DecibelsWithFloor(floor_db) => coeff_to_db_clamped_f32(dsp_value, *floor_db),
+            Unit::Percent => dsp_value * 100.0,
+            Unit::Semitones => 12.0 * dsp_value.log2(),
+            Unit::Generic | Unit::Hz => dsp_value,
         }
     }
 
@@ -98,7 +199,12 @@ impl Unit {
     pub fn unit_to_dsp_f64(&self, value: f64) -> f64 {
         match self {
             Unit::Decibels => db_to_coeff_clamped_neg_90_db_f64(value),
-            _ => value,
+            Unit::DecibelsWithFloor(floor_db) => {
+                db_to_coeff_clamped_f64(value, f64::from(*floor_db))
+            }
+            Unit::Percent => value / 100.0,
+            Unit::Semitones => 2.0f64.powf(value / 12.0),
+            Unit::Generic | Unit::Hz => value,
         }
     }
 
@@ -108,9 +214,274 @@ impl Unit {
     pub fn dsp_to_unit_f64(&self, dsp_value: f64) -> f64 {
         match self {
             Unit::Decibels => coeff_to_db_clamped_neg_90_db_f64(dsp_value),
-            _ => dsp_value,
+            Unit::DecibelsWithFloor(floor_db) => {
+                coeff_to_db_clamped_f64(dsp_value, f64::from(*floor_db))
+            }
+            Unit::Percent => dsp_value * 100.0,
+            Unit::Semitones => 12.0 * dsp_value.log2(),
+            Unit::Generic | Unit::Hz => dsp_value,
+        }
+    }
+
+    /// Convert the given raw DSP value to the corresponding unit value, split into its
+    /// magnitude and sign.
+    ///
+    /// This is useful for displaying bipolar parameters (i.e. stereo width or phase) as a
+    /// magnitude with a separate sign indicator. The returned `bool` is `true` if the unit
+    /// value is negative.
+    pub fn display_value_f32(&self, dsp_value: f32) -> (f32, bool) {
+        let value = self.dsp_to_unit_f32(dsp_value);
+        (value.abs(), value < 0.0)
+    }
+
+    /// Convert the given raw DSP value to the corresponding unit value, split into its
+    /// magnitude and sign.
+    ///
+    /// This is useful for displaying bipolar parameters (i.e. stereo width or phase) as a
+    /// magnitude with a separate sign indicator. The returned `bool` is `true` if the unit
+    /// value is negative.
+    pub fn display_value_f64(&self, dsp_value: f64) -> (f64, bool) {
+        let value = self.dsp_to_unit_f64(dsp_value);
+        (value.abs(), value < 0.0)
+    }
+
+    /// Format the given unit value with `precision` decimal places, followed by a space and
+    /// the suffix of this unit (if it has one), i.e. `"-6.0 dB"`.
+    pub fn format_value_f32(&self, value: f32, precision: usize) -> String {
+        let suffix = self.suffix();
+        if suffix.is_empty() {
+            format!("{:.*}", precision, value)
+        } else {
+            format!("{:.*} {}", precision, value, suffix)
+        }
+    }
+
+    /// Parse a unit value from text (i.e. typed in by the user), the inverse of
+    /// `Unit::format_value_f32()`.
+    ///
+    /// Surrounding whitespace, a leading `+`, and the suffix of this unit (in any case) are
+    /// all optional, and scientific notation (i.e. `"1.5e3"`) is accepted. This returns
+    /// `None` if the text is not a finite number.
+    pub fn parse_value_f32(&self, text: &str) -> Option<f32> {
+        let text = text.trim();
+        let suffix = self.suffix();
+
+        let split = text.len().saturating_sub(suffix.len());
+        let text = if !suffix.is_empty()
+            && text.is_char_boundary(split)
+            && text[split..].eq_ignore_ascii_case(suffix)
+        {
+            text[..split].trim_end()
+        } else {
+            text
+        };
+
+        text.parse::<f32>().ok().filter(|v| v.is_finite())
+    }
+}
+
+/// The static configuration of a [`ParamF32`]. This is all of the metadata needed to render
+/// a generic control for the parameter.
+///
+/// [`ParamF32`]: struct.ParamF32.html
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParamF32Config {
+    /// The minimum (de-normalized) value of the parameter.
+    pub min: f32,
+    /// The maximum (de-normalized) value of the parameter.
+    pub max: f32,
+    /// The [`Gradient`] mapping used when converting from the normalized value in the range
+    /// `[0.0, 1.0]` to the desired value.
+    ///
+    /// [`Gradient`]: enum.Gradient.html
+    pub gradient: Gradient,
+    /// The [`Unit`] that signifies how the value displayed to the end user should differ from
+    /// the actual value used in DSP.
+    ///
+    /// [`Unit`]: enum.Unit.html
+    pub unit: Unit,
+    /// The period of the low-pass parameter smoothing filter.
+    pub smooth_secs: SecondsF64,
+    /// The default (de-normalized) value of the parameter.
+    pub default_value: f32,
+}
+
+/// The persistent state of a [`ParamF32`] (i.e. for saving it to a project file). Restore it
+/// with `ParamF32::from_state()`.
+///
+/// [`ParamF32`]: struct.ParamF32.html
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParamF32State {
+    /// The normalized value in the range `[0.0, 1.0]`.
+    pub normalized: f32,
+    /// The default (de-normalized) value of the parameter.
+    pub default_value: f32,
+    /// The minimum (de-normalized) value of the parameter.
+    pub min: f32,
+    /// The maximum (de-normalized) value of the parameter.
+    pub max: f32,
+    /// The [`Gradient`] mapping used when converting from the normalized value in the range
+    /// `[0.0, 1.0]` to the desired value.
+    ///
+    /// [`Gradient`]: enum.Gradient.html
+    pub gradient: Gradient,
+    /// The [`Unit`] that signifies how the value displayed to the end user should differ from
+    /// the actual value used in DSP.
+    ///
+    /// [`Unit`]: enum.Unit.html
+    pub unit: Unit,
+}
+
+/// A snapshot of the internal state of a [`ParamF32`], including the state of its smoothing
+/// filter.
+///
+/// [`ParamF32`]: struct.ParamF32.html
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParamF32SmoothState {
+    normalized: f32,
+    value: f32,
+    smooth: SmoothStateF32,
+}
+
+impl ParamF32SmoothState {
+    /// The normalized value in the range `[0.0, 1.0]` at the time this state was captured.
+    pub fn normalized(&self) -> f32 {
+        self.normalized
+    }
+
+    /// The state of the smoothing filter at the time this state was captured.
+    pub fn smooth(&self) -> SmoothStateF32 {
+        self.smooth
+    }
+}
+
+/// A builder for a [`ParamF32`]/[`ParamF32Handle`] pair, as an alternative to the
+/// positional arguments of `ParamF32::from_value()` and `ParamF32::from_normalized()`.
+///
+/// By default the range is `[0.0, 1.0]`, with `Gradient::Linear`, `Unit::Generic`,
+/// `DEFAULT_SMOOTH_SECS`, and the default [`SampleRate`]. If no default value is set, then
+/// the initial value of the parameter is used as its default value.
+///
+/// [`ParamF32`]: struct.ParamF32.html
+/// [`ParamF32Handle`]: struct.ParamF32Handle.html
+/// [`SampleRate`]: ../time/struct.SampleRate.html
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParamF32Builder {
+    min: f32,
+    max: f32,
+    default_value: Option<f32>,
+    gradient: Gradient,
+    unit: Unit,
+    smooth_secs: SecondsF64,
+    sample_rate: SampleRate,
+    max_blocksize: usize,
+}
+
+impl ParamF32Builder {
+    /// * max_blocksize - The maximum number of frames that will be processed in a single
+    ///   call to `ParamF32::smoothed()`.
+    pub fn new(max_blocksize: usize) -> Self {
+        Self {
+            min: 0.0,
+            max: 1.0,
+            default_value: None,
+            gradient: Gradient::Linear,
+            unit: Unit::Generic,
+            smooth_secs: DEFAULT_SMOOTH_SECS,
+            sample_rate: SampleRate::default(),
+            max_blocksize,
         }
     }
+
+    /// The minimum (de-normalized) value of the parameter (at a normalized value of `0.0`).
+    pub fn min(mut self, min: f32) -> Self {
+        self.min = min;
+        self
+    }
+
+    /// The maximum (de-normalized) value of the parameter (at a normalized value of `1.0`).
+    pub fn max(mut self, max: f32) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// The default (de-normalized) value of the parameter.
+    pub fn default_value(mut self, default_value: f32) -> Self {
+        self.default_value = Some(default_value);
+        self
+    }
+
+    /// The [`Gradient`] mapping used when converting from the normalized value in the range
+    /// `[0.0, 1.0]` to the desired value.
+    ///
+    /// [`Gradient`]: enum.Gradient.html
+    pub fn gradient(mut self, gradient: Gradient) -> Self {
+        self.gradient = gradient;
+        self
+    }
+
+    /// The [`Unit`] that signifies how the value displayed to the end user should differ
+    /// from the actual value used in DSP.
+    ///
+    /// [`Unit`]: enum.Unit.html
+    pub fn unit(mut self, unit: Unit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// The period of the low-pass parameter smoothing filter (for declicking).
+    pub fn smooth_secs(mut self, smooth_secs: SecondsF64) -> Self {
+        self.smooth_secs = smooth_secs;
+        self
+    }
+
+    /// The sample rate of this process. This is used for the low-pass parameter smoothing
+    /// filter.
+    pub fn sample_rate(mut self, sample_rate: SampleRate) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Create the Parameter/Handle pair from its initial (de-normalized) value.
+    pub fn build_from_value(self, value: f32) -> (ParamF32, ParamF32Handle) {
+        ParamF32::from_value(
+            value,
+            self.default_value.unwrap_or(value),
+            self.min,
+            self.max,
+            self.gradient,
+            self.unit,
+            self.smooth_secs,
+            self.sample_rate,
+            self.max_blocksize,
+        )
+    }
+
+    /// Create the Parameter/Handle pair from its initial normalized value in the range
+    /// `[0.0, 1.0]`.
+    pub fn build_from_normalized(self, normalized: f32) -> (ParamF32, ParamF32Handle) {
+        let default_value = self.default_value.unwrap_or_else(|| {
+            normalized_to_value_f32(
+                normalized.clamp(0.0, 1.0),
+                self.min,
+                self.max,
+                self.gradient,
+            )
+        });
+
+        ParamF32::from_normalized(
+            normalized,
+            default_value,
+            self.min,
+            self.max,
+            self.gradient,
+            self.unit,
+            self.smooth_secs,
+            self.sample_rate,
+            self.max_blocksize,
+        )
+    }
 }
 
 /// An auto-smoothed parameter with an `f32` value.
@@ -121,13 +492,41 @@ pub struct ParamF32 {
     unit: Unit,
 
     shared_normalized: Arc<AtomicF32>,
+    shared_changes: Arc<AtomicU64>,
+    shared_trim: Arc<AtomicF32>,
+    shared_normalized_limit: Arc<AtomicF32>,
+    seen_changes: u64,
+    // The value of the shared change counter the last time `poll_shared()` read the shared
+    // state.
+    polled_changes: u64,
+    skip_unchanged_polls: bool,
+    // Only this parameter holds a strong reference to this token, so handles can detect when
+    // it has been dropped.
+    _alive: Arc<()>,
+    // The shared normalized value the last time it was read or written by this parameter,
+    // used to detect new values from a handle (or from direct writes to the atomic).
+    last_shared_normalized: f32,
     normalized: f32,
+    trim_offset_normalized: f32,
+    modulation_normalized: f32,
+    allow_overshoot: bool,
+    overshoot_limit: f32,
 
     value: f32,
     default_value: f32,
 
+    snap_normalized: Vec<f32>,
+    snap_strength: f32,
+    steps: Option<u32>,
+
+    min_seen: f32,
+    max_seen: f32,
+
     smoothed: SmoothF32,
     smooth_secs: SecondsF64,
+    fast_smooth_secs: SecondsF64,
+    change_deadband: f32,
+    sample_rate: SampleRate,
 }
 
 impl ParamF32 {
@@ -135,20 +534,22 @@ impl ParamF32 {
     ///
     /// * value - The initial (de-normalized) value of the parameter.
     /// * default_value - The default (de-normalized) value of the parameter.
-    /// * min - The minimum (de-normalized) value of the parameter.
+    /// * min - The minimum (de-normalized) value of the parameter (at a normalized value of
+    ///   `0.0`). This may be greater than `max` for an inverted range.
     /// * max - The maximum (de-normalized) value of the parameter.
     /// * gradient - The [`Gradient`] mapping used when converting from the normalized value
-    /// in the range `[0.0, 1.0]` to the desired value. If this parameter deals with decibels,
-    /// you may use `ParamF32::DEFAULT_SMOOTH_SECS` as a good default.
+    ///   in the range `[0.0, 1.0]` to the desired value. If this parameter deals with decibels,
+    ///   you may use `ParamF32::DEFAULT_SMOOTH_SECS` as a good default.
     /// * unit - The [`Unit`] that signifies how the value displayed to the end user should
-    /// differ from the actual value used in DSP.
+    ///   differ from the actual value used in DSP.
     /// * smooth_secs: The period of the low-pass parameter smoothing filter (for declicking). You
-    /// may use `ParamF32::DEFAULT_SMOOTH_SECS` as a good default.
+    ///   may use `ParamF32::DEFAULT_SMOOTH_SECS` as a good default.
     /// * sample_rate: The sample rate of this process. This is used for the low-pass parameter
-    /// smoothing filter.
+    ///   smoothing filter.
     ///
     /// [`Gradient`]: enum.Gradient.html
     /// [`Unit`]: enum.Unit.html
+    #[allow(clippy::too_many_arguments)]
     pub fn from_value(
         value: f32,
         default_value: f32,
@@ -163,15 +564,17 @@ impl ParamF32 {
         let normalized = value_to_normalized_f32(value, min, max, gradient);
 
         let handle_value = normalized_to_value_f32(normalized, min, max, gradient);
-        let rt_value = match unit {
-            Unit::Decibels => db_to_coeff_clamped_neg_90_db_f32(handle_value),
-            _ => handle_value,
-        };
+        let rt_value = unit.unit_to_dsp_f32(handle_value);
 
         let shared_normalized = Arc::new(AtomicF32::new(normalized));
+        let alive = Arc::new(());
+        let shared_changes = Arc::new(AtomicU64::new(0));
+        let shared_trim = Arc::new(AtomicF32::new(0.0));
+        let shared_normalized_limit = Arc::new(AtomicF32::new(1.0));
 
         let mut smoothed = SmoothF32::new(rt_value, max_blocksize);
         smoothed.set_speed(sample_rate, smooth_secs);
+        smoothed.set_fast_speed(sample_rate, smooth_secs);
 
         (
             Self {
@@ -180,11 +583,31 @@ impl ParamF32 {
                 gradient,
                 unit,
                 shared_normalized: Arc::clone(&shared_normalized),
+                shared_changes: Arc::clone(&shared_changes),
+                shared_trim: Arc::clone(&shared_trim),
+                shared_normalized_limit: Arc::clone(&shared_normalized_limit),
+                seen_changes: 0,
+                polled_changes: 0,
+                skip_unchanged_polls: false,
+                _alive: Arc::clone(&alive),
+                last_shared_normalized: normalized,
                 normalized,
+                trim_offset_normalized: 0.0,
+                modulation_normalized: 0.0,
+                allow_overshoot: false,
+                overshoot_limit: 1.0,
                 value: rt_value,
                 default_value,
+                snap_normalized: Vec::new(),
+                snap_strength: 0.0,
+                steps: None,
+                min_seen: rt_value,
+                max_seen: rt_value,
                 smoothed,
                 smooth_secs,
+                fast_smooth_secs: smooth_secs,
+                change_deadband: DEFAULT_CHANGE_DEADBAND_NORMALIZED,
+                sample_rate,
             },
             ParamF32Handle {
                 min,
@@ -192,7 +615,14 @@ impl ParamF32 {
                 gradient,
                 unit,
                 default_value,
+                smooth_secs,
                 shared_normalized,
+                shared_changes,
+                shared_trim,
+                shared_normalized_limit,
+                param_alive: Arc::downgrade(&alive),
+                seen_changes: AtomicU64::new(0),
+                last_controller_normalized: AtomicF32::new(f32::NAN),
             },
         )
     }
@@ -201,20 +631,22 @@ impl ParamF32 {
     ///
     /// * normalized - The initial normalized value of the parameter in the range `[0.0, 1.0]`.
     /// * default_value - The default (de-normalized) value of the parameter.
-    /// * min - The minimum (de-normalized) value of the parameter.
+    /// * min - The minimum (de-normalized) value of the parameter (at a normalized value of
+    ///   `0.0`). This may be greater than `max` for an inverted range.
     /// * max - The maximum (de-normalized) value of the parameter.
     /// * gradient - The [`Gradient`] mapping used when converting from the normalized value
-    /// in the range `[0.0, 1.0]` to the desired value. If this parameter deals with decibels,
-    /// you may use `ParamF32::DEFAULT_SMOOTH_SECS` as a good default.
+    ///   in the range `[0.0, 1.0]` to the desired value. If this parameter deals with decibels,
+    ///   you may use `ParamF32::DEFAULT_SMOOTH_SECS` as a good default.
     /// * unit - The [`Unit`] that signifies how the value displayed to the end user should
-    /// differ from the actual value used in DSP.
+    ///   differ from the actual value used in DSP.
     /// * smooth_secs: The period of the low-pass parameter smoothing filter (for declicking). You
-    /// may use `ParamF32::DEFAULT_SMOOTH_SECS` as a good default.
+    ///   may use `ParamF32::DEFAULT_SMOOTH_SECS` as a good default.
     /// * sample_rate: The sample rate of this process. This is used for the low-pass parameter
-    /// smoothing filter.
+    ///   smoothing filter.
     ///
     /// [`Gradient`]: enum.Gradient.html
     /// [`Unit`]: enum.Unit.html
+    #[allow(clippy::too_many_arguments)]
     pub fn from_normalized(
         normalized: f32,
         default_value: f32,
@@ -229,15 +661,17 @@ impl ParamF32 {
         let normalized = normalized.clamp(0.0, 1.0);
 
         let shared_normalized = Arc::new(AtomicF32::new(normalized));
+        let alive = Arc::new(());
+        let shared_changes = Arc::new(AtomicU64::new(0));
+        let shared_trim = Arc::new(AtomicF32::new(0.0));
+        let shared_normalized_limit = Arc::new(AtomicF32::new(1.0));
 
         let handle_value = normalized_to_value_f32(normalized, min_value, max_value, gradient);
-        let rt_value = match unit {
-            Unit::Decibels => db_to_coeff_clamped_neg_90_db_f32(handle_value),
-            _ => handle_value,
-        };
+        let rt_value = unit.unit_to_dsp_f32(handle_value);
 
         let mut smoothed = SmoothF32::new(rt_value, max_blocksize);
         smoothed.set_speed(sample_rate, smooth_secs);
+        smoothed.set_fast_speed(sample_rate, smooth_secs);
 
         (
             Self {
@@ -246,11 +680,31 @@ impl ParamF32 {
                 gradient,
                 unit,
                 shared_normalized: Arc::clone(&shared_normalized),
+                shared_changes: Arc::clone(&shared_changes),
+                shared_trim: Arc::clone(&shared_trim),
+                shared_normalized_limit: Arc::clone(&shared_normalized_limit),
+                seen_changes: 0,
+                polled_changes: 0,
+                skip_unchanged_polls: false,
+                _alive: Arc::clone(&alive),
+                last_shared_normalized: normalized,
                 normalized,
+                trim_offset_normalized: 0.0,
+                modulation_normalized: 0.0,
+                allow_overshoot: false,
+                overshoot_limit: 1.0,
                 value: rt_value,
                 default_value,
+                snap_normalized: Vec::new(),
+                snap_strength: 0.0,
+                steps: None,
+                min_seen: rt_value,
+                max_seen: rt_value,
                 smoothed,
                 smooth_secs,
+                fast_smooth_secs: smooth_secs,
+                change_deadband: DEFAULT_CHANGE_DEADBAND_NORMALIZED,
+                sample_rate,
             },
             ParamF32Handle {
                 min: min_value,
@@ -258,22 +712,88 @@ impl ParamF32 {
                 gradient,
                 unit,
                 default_value,
+                smooth_secs,
                 shared_normalized,
+                shared_changes,
+                shared_trim,
+                shared_normalized_limit,
+                param_alive: Arc::downgrade(&alive),
+                seen_changes: AtomicU64::new(0),
+                last_controller_normalized: AtomicF32::new(f32::NAN),
             },
         )
     }
 
+    /// Create a Parameter/Handle pair from a [`ParamF32State`] (i.e. one loaded from a project
+    /// file). The smoothing filter starts settled at the saved value.
+    ///
+    /// * state - The saved state of the parameter.
+    /// * smooth_secs: The period of the low-pass parameter smoothing filter (for declicking). You
+    ///   may use `ParamF32::DEFAULT_SMOOTH_SECS` as a good default.
+    /// * sample_rate: The sample rate of this process. This is used for the low-pass parameter
+    ///   smoothing filter.
+    ///
+    /// [`ParamF32State`]: struct.ParamF32State.html
+    pub fn from_state(
+        state: ParamF32State,
+        smooth_secs: SecondsF64,
+        sample_rate: SampleRate,
+        max_blocksize: usize,
+    ) -> (Self, ParamF32Handle) {
+        Self::from_normalized(
+            state.normalized,
+            state.default_value,
+            state.min,
+            state.max,
+            state.gradient,
+            state.unit,
+            smooth_secs,
+            sample_rate,
+            max_blocksize,
+        )
+    }
+
+    /// Create a Parameter/Handle pair with the same configuration (min, max, gradient, unit,
+    /// and smoothing period) as the given [`ParamF64`], initialized at its current value.
+    ///
+    /// * param - The parameter to copy the configuration from.
+    /// * sample_rate: The sample rate of this process. This is used for the low-pass parameter
+    ///   smoothing filter.
+    ///
+    /// [`ParamF64`]: struct.ParamF64.html
+    pub fn from_f64_config(param: &ParamF64, sample_rate: SampleRate) -> (Self, ParamF32Handle) {
+        Self::from_normalized(
+            param.normalized as f32,
+            param.default_value as f32,
+            param.min as f32,
+            param.max as f32,
+            param.gradient,
+            param.unit,
+            param.smooth_secs,
+            sample_rate,
+            param.smoothed.max_blocksize(),
+        )
+    }
+
     /// Set the (de-normalized) value of this parameter.
     pub fn set_value(&mut self, value: f32) {
-        if self.value != value {
-            self.normalized = value_to_normalized_f32(value, self.min, self.max, self.gradient);
-            self.shared_normalized.set(self.normalized);
-
-            let v = normalized_to_value_f32(self.normalized, self.min, self.max, self.gradient);
-            self.value = match self.unit {
-                Unit::Decibels => db_to_coeff_clamped_neg_90_db_f32(v),
-                _ => v,
-            };
+        let normalized = self.quantize(value_to_normalized_f32(
+            value,
+            self.min,
+            self.max,
+            self.gradient,
+        ));
+        if (normalized - self.normalized).abs() > self.change_deadband {
+            self.normalized = normalized;
+            self.store_shared_normalized();
+
+            let v = normalized_to_value_unclamped_f32(
+                self.effective_normalized(),
+                self.min,
+                self.max,
+                self.gradient,
+            );
+            self.value = self.unit.unit_to_dsp_f32(v);
 
             self.smoothed.set(self.value);
         }
@@ -281,15 +801,18 @@ impl ParamF32 {
 
     /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
     pub fn set_normalized(&mut self, normalized: f32) {
-        if self.normalized != normalized {
-            self.normalized = normalized.clamp(0.0, 1.0);
-            self.shared_normalized.set(self.normalized);
-
-            let v = normalized_to_value_f32(self.normalized, self.min, self.max, self.gradient);
-            self.value = match self.unit {
-                Unit::Decibels => db_to_coeff_clamped_neg_90_db_f32(v),
-                _ => v,
-            };
+        let normalized = self.quantize(normalized.clamp(0.0, self.normalized_limit()));
+        if (normalized - self.normalized).abs() > self.change_deadband {
+            self.normalized = normalized;
+            self.store_shared_normalized();
+
+            let v = normalized_to_value_unclamped_f32(
+                self.effective_normalized(),
+                self.min,
+                self.max,
+                self.gradient,
+            );
+            self.value = self.unit.unit_to_dsp_f32(v);
 
             self.smoothed.set(self.value);
         }
@@ -297,206 +820,462 @@ impl ParamF32 {
 
     /// Reset this parameter (without any smoothing) to the given (de-normalized) value.
     pub fn reset_from_value(&mut self, value: f32) {
-        self.normalized = value_to_normalized_f32(value, self.min, self.max, self.gradient);
-        self.shared_normalized.set(self.normalized);
+        self.normalized = self.quantize(value_to_normalized_f32(
+            value,
+            self.min,
+            self.max,
+            self.gradient,
+        ));
+        self.store_shared_normalized();
 
-        let v = normalized_to_value_f32(self.normalized, self.min, self.max, self.gradient);
-        self.value = match self.unit {
-            Unit::Decibels => db_to_coeff_clamped_neg_90_db_f32(v),
-            _ => v,
-        };
+        let v = normalized_to_value_unclamped_f32(
+            self.effective_normalized(),
+            self.min,
+            self.max,
+            self.gradient,
+        );
+        self.value = self.unit.unit_to_dsp_f32(v);
 
         self.smoothed.reset(self.value);
     }
 
     /// Reset this parameter (without any smoothing) to the given normalized value in the range `[0.0, 1.0]`.
     pub fn reset_from_normalized(&mut self, normalized: f32) {
-        self.normalized = normalized.clamp(0.0, 1.0);
-        self.shared_normalized.set(self.normalized);
+        self.normalized = self.quantize(normalized.clamp(0.0, self.normalized_limit()));
+        self.store_shared_normalized();
 
-        let v = normalized_to_value_f32(self.normalized, self.min, self.max, self.gradient);
-        self.value = match self.unit {
-            Unit::Decibels => db_to_coeff_clamped_neg_90_db_f32(v),
-            _ => v,
-        };
+        let v = normalized_to_value_unclamped_f32(
+            self.effective_normalized(),
+            self.min,
+            self.max,
+            self.gradient,
+        );
+        self.value = self.unit.unit_to_dsp_f32(v);
 
         self.smoothed.reset(self.value);
     }
 
-    /// Reset the internal smoothing buffer.
-    pub fn reset(&mut self) {
-        self.smoothed.reset(self.value);
+    /// Set values that this parameter softly snaps to (magnetic detents).
+    ///
+    /// Whenever a value set from the [`ParamF32Handle`] lies within `SNAP_RADIUS_NORMALIZED`
+    /// (in normalized units) of a snap value, it is pulled toward that snap value by
+    /// `strength`, where `0.0` means no pull and `1.0` snaps exactly onto the snap value.
+    ///
+    /// Only the value used by this parameter is snapped. The value of the handle stays
+    /// exactly as it was set from the UI.
+    ///
+    /// * `values` - The (de-normalized) snap values. Pass an empty slice to disable snapping.
+    /// * `strength` - The strength of the pull in the range `[0.0, 1.0]`.
+    ///
+    /// Note this allocates, so this should not be called on the audio thread.
+    ///
+    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
+    pub fn set_snap_values(&mut self, values: &[f32], strength: f32) {
+        let (min, max, gradient) = (self.min, self.max, self.gradient);
+
+        self.snap_normalized.clear();
+        self.snap_normalized.extend(
+            values
+                .iter()
+                .map(|v| value_to_normalized_f32(*v, min, max, gradient)),
+        );
+        self.snap_strength = strength.clamp(0.0, 1.0);
     }
 
-    /// Get the smoothed buffer of values for use in DSP.
-    pub fn smoothed(&mut self, frames: usize) -> SmoothOutputF32 {
-        let new_normalized = self.shared_normalized.get();
-        if self.normalized != new_normalized {
-            self.normalized = new_normalized;
+    /// Returns the normalized value pulled toward the nearest snap value, or `None` if it lies
+    /// outside the radius of every snap value.
+    fn snap(&self, normalized: f32) -> Option<f32> {
+        let nearest = self
+            .snap_normalized
+            .iter()
+            .copied()
+            .filter(|s| (s - normalized).abs() <= SNAP_RADIUS_NORMALIZED)
+            .min_by(|a, b| (a - normalized).abs().total_cmp(&(b - normalized).abs()))?;
+
+        Some(normalized + ((nearest - normalized) * self.snap_strength))
+    }
+
+    /// Quantize this parameter to a fixed number of evenly-spaced steps in normalized space
+    /// (i.e. a 24-step drive control), or `None` to make it continuous again.
+    ///
+    /// When set, every new value (from this parameter or from the [`ParamF32Handle`]) is
+    /// moved to the nearest step. The DSP value is still smoothed when moving between steps.
+    ///
+    /// Values set through the [`ParamF32Handle`] are only quantized on this side, so the
+    /// handle keeps the value exactly as it was set from the UI.
+    ///
+    /// * `steps` - The number of steps, including both ends of the range (i.e. `Some(4)`
+    ///   gives the normalized values `0.0`, `1/3`, `2/3`, and `1.0`). Values less than `2`
+    ///   are treated as `None`.
+    ///
+    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
+    pub fn set_steps(&mut self, steps: Option<u32>) {
+        self.steps = steps.filter(|s| *s >= 2);
+        self.set_normalized(self.normalized);
+    }
 
-            let v = normalized_to_value_f32(self.normalized, self.min, self.max, self.gradient);
-            self.value = match self.unit {
-                Unit::Decibels => db_to_coeff_clamped_neg_90_db_f32(v),
-                _ => v,
-            };
+    /// The number of steps this parameter is quantized to, or `None` if it is continuous.
+    pub fn steps(&self) -> Option<u32> {
+        self.steps
+    }
 
-            self.smoothed.set(self.value);
+    /// Returns the normalized value moved to the nearest step, if this parameter is stepped.
+    ///
+    /// Values past `1.0` (overshoot) are left as they are.
+    fn quantize(&self, normalized: f32) -> f32 {
+        match self.steps {
+            Some(steps) if normalized <= 1.0 => {
+                let intervals = (steps - 1) as f32;
+                (normalized * intervals).round() / intervals
+            }
+            _ => normalized,
         }
+    }
 
-        self.smoothed.process(frames);
-        self.smoothed.update_status();
+    /// Move this parameter to the given (de-normalized) value with a one-shot linear ramp
+    /// lasting `ramp`, instead of jumping like `ParamF32::reset_from_value()`.
+    ///
+    /// This is useful for moving smoothly to a new state without a click (i.e. after loading
+    /// a preset). Any later change to the value cancels the ramp and uses the regular
+    /// smoothing filter instead.
+    pub fn soft_reset_to_value(&mut self, value: f32, ramp: SecondsF64) {
+        self.normalized = value_to_normalized_f32(value, self.min, self.max, self.gradient);
+        self.store_shared_normalized();
 
-        self.smoothed.output()
-    }
+        let v = normalized_to_value_unclamped_f32(
+            self.effective_normalized(),
+            self.min,
+            self.max,
+            self.gradient,
+        );
+        self.value = self.unit.unit_to_dsp_f32(v);
 
-    /// Update the sample rate (used for the parameter smoothing LPF).
-    pub fn set_sample_rate(&mut self, sample_rate: SampleRate) {
-        self.smoothed.set_speed(sample_rate, self.smooth_secs);
+        self.smoothed
+            .ramp_to(self.value, ramp.to_nearest_frame_round(self.sample_rate).0);
     }
 
-    /// The minimum value of this parameter.
-    pub fn min(&self) -> f32 {
-        self.min
+    /// Reset the internal smoothing buffer.
+    pub fn reset(&mut self) {
+        self.smoothed.reset(self.value);
     }
 
-    /// The maximum value of this parameter.
-    pub fn max(&self) -> f32 {
-        self.max
+    /// Returns `true` if the smoothing filter has not yet settled to its target value (the
+    /// same as `SmoothOutputF32::is_smoothing()`).
+    ///
+    /// If this returns `false`, then every value in the buffer returned by
+    /// `ParamF32::smoothed()` is the same, so per-frame reads can be skipped.
+    ///
+    /// Note that this only reflects the state after the most recent call to
+    /// `ParamF32::smoothed()`. A new value set from the handle is only picked up on the next
+    /// call.
+    pub fn is_smoothing(&self) -> bool {
+        self.smoothed.is_active()
     }
 
-    /// The [`Gradient`] mapping used when converting from the normalized value
-    /// in the range `[0.0, 1.0]` to the desired value.
+    /// If the smoothing filter has reached its target value, reset it to that value so that
+    /// `ParamF32::is_smoothing()` returns `false` right away (instead of after the next
+    /// block). Returns `true` if the filter is settled afterwards.
     ///
-    /// [`Gradient`]: enum.Gradient.html
-    pub fn gradient(&self) -> Gradient {
-        self.gradient
+    /// Note that this only reflects the state after the most recent call to
+    /// `ParamF32::smoothed()`.
+    pub fn reset_if_settled(&mut self) -> bool {
+        self.smoothed.reset_if_settled()
     }
 
-    /// The [`Unit`] that signifies how the value displayed to the end user should
-    /// differ from the actual value used in DSP.
+    /// Get the smoothed buffer of values for use in DSP.
     ///
-    /// [`Unit`]: enum.Unit.html
-    pub fn unit(&self) -> Unit {
-        self.unit
-    }
+    /// Note that the smoothing filter assumes that this is called for every contiguous block
+    /// of frames. If processing was suspended for a while (i.e. a suspended track), use
+    /// `ParamF32::smoothed_with_elapsed()` instead when resuming.
+    pub fn smoothed(&mut self, frames: usize) -> SmoothOutputF32<'_> {
+        self.poll_shared();
 
-    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
-    /// of this parameter.
-    pub fn value_to_normalized(&self, value: f32) -> f32 {
-        value_to_normalized_f32(value, self.min, self.max, self.gradient)
+        self.smoothed.process(frames);
+        self.smoothed.update_status();
+
+        self.track_seen(frames);
+
+        self.smoothed.output()
     }
 
-    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
-    /// corresponding value of this parameter.
-    pub fn normalized_to_value(&self, normalized: f32) -> f32 {
-        normalized_to_value_f32(normalized, self.min, self.max, self.gradient)
+    /// Same as `ParamF32::smoothed()`, except that the smoothed values are written into `out`
+    /// instead of being returned from internal storage. Returns the number of frames
+    /// written, which is `out.len()` clamped to the maximum block size.
+    pub fn smoothed_into(&mut self, out: &mut [f32]) -> usize {
+        let frames = out.len().min(self.smoothed.max_blocksize());
+        out[..frames].copy_from_slice(&self.smoothed(frames).values[..frames]);
+
+        frames
     }
 
-    /// The current normalized value in the range `[0.0, 1.0]`. This is only meant for
-    /// communicating with the host. This is not meant to be used to retrieve the latest
-    /// value for DSP. To get the latest value for DSP please use `ParamF32::smoothed()`
-    /// instead.
+    /// Get the smoothed buffer of values for use in DSP, while applying a list of automation
+    /// events at sample-accurate positions within the block.
     ///
-    /// Please note that this should be called *after* calling `ParamF32::smoothed()`
-    /// if you need the latest value from the corresponding [`ParamF32Handle`],
-    /// otherwise this may not return the latest value.
+    /// * `events` - The automation events as `(frame_offset, normalized_value)` pairs, where
+    ///   `frame_offset` is relative to the start of this block. These must be sorted by
+    ///   `frame_offset`. Offsets past the end of the block are clamped to the last frame.
+    /// * `frames` - The number of frames in this block.
     ///
-    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
-    pub fn host_get_normalized(&self) -> f32 {
-        self.normalized
-    }
+    /// Each event is applied as if `ParamF32::set_normalized()` was called at that frame, so
+    /// the output is smoothed between events in the same way as it would be between blocks.
+    pub fn process_automation(
+        &mut self,
+        events: &[(usize, f32)],
+        frames: usize,
+    ) -> SmoothOutputF32<'_> {
+        let frames = frames.min(self.smoothed.max_blocksize());
+
+        self.poll_shared();
+
+        let mut offset = 0;
+        for &(event_offset, normalized) in events.iter() {
+            let event_offset = event_offset.min(frames.saturating_sub(1));
+            if event_offset > offset {
+                self.smoothed.process_at(offset, event_offset - offset);
+                offset = event_offset;
+            }
 
-    /// The (un-normalized) default value of the parameter.
-    pub fn default_value(&self) -> f32 {
-        self.default_value
+            self.set_normalized(normalized);
+        }
+        self.smoothed.process_at(offset, frames - offset);
+        self.smoothed.update_status();
+
+        self.track_seen(frames);
+
+        self.smoothed.output()
     }
 
-    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
-    pub fn default_normalized(&self) -> f32 {
-        self.value_to_normalized(self.default_value)
+    /// Get the smoothed buffer of values for use in DSP, after advancing the smoothing filter
+    /// by `elapsed_since_last` frames that were not processed.
+    ///
+    /// This is useful when resuming processing after it was suspended for a while (i.e. a
+    /// suspended track), so that the smoothing filter doesn't resume a ramp that should have
+    /// already finished.
+    pub fn smoothed_with_elapsed(
+        &mut self,
+        frames: usize,
+        elapsed_since_last: FrameTime,
+    ) -> SmoothOutputF32<'_> {
+        self.poll_shared();
+
+        self.smoothed.skip(elapsed_since_last.0);
+        self.smoothed.update_status();
+
+        self.smoothed.process(frames);
+        self.smoothed.update_status();
+
+        self.track_seen(frames);
+
+        self.smoothed.output()
     }
 
-    /// The current (un-normalized) value of the parameter. This is only meant for
-    /// communicating with the host. This is not meant to be used to retrieve the latest
-    /// value for DSP. To get the latest value for DSP please use `ParamF32::smoothed()`
-    /// instead.
+    fn track_seen(&mut self, frames: usize) {
+        let out = self.smoothed.output();
+        let frames = frames.min(out.values.len());
+
+        let values = if out.is_smoothing() {
+            &out.values[..frames]
+        } else {
+            // The output is constant, so only one value needs to be checked.
+            &out.values[..frames.min(1)]
+        };
+
+        for v in values.iter() {
+            self.min_seen = self.min_seen.min(*v);
+            self.max_seen = self.max_seen.max(*v);
+        }
+    }
+
+    /// The minimum smoothed (DSP) value this parameter has output since it was created or
+    /// since the last call to `ParamF32::reset_seen()`.
+    pub fn min_seen(&self) -> f32 {
+        self.min_seen
+    }
+
+    /// The maximum smoothed (DSP) value this parameter has output since it was created or
+    /// since the last call to `ParamF32::reset_seen()`.
+    pub fn max_seen(&self) -> f32 {
+        self.max_seen
+    }
+
+    /// Reset the range returned by `ParamF32::min_seen()` and `ParamF32::max_seen()` to the
+    /// current smoothed (DSP) value.
+    pub fn reset_seen(&mut self) {
+        let (current, _) = self.smoothed.current_value();
+        self.min_seen = current;
+        self.max_seen = current;
+    }
+
+    /// Pick up any change to the normalized value made through the [`ParamF32Handle`] and
+    /// re-target the smoothing filter, without processing any frames. Returns `true` if the
+    /// target changed.
     ///
-    /// Please note that this should be called *after* calling `ParamF32::smoothed()`
-    /// if you need the latest value from the corresponding [`ParamF32Handle`],
-    /// otherwise this may not return the latest value.
+    /// This is useful when no output is needed (i.e. while the transport is paused). Note that
+    /// `ParamF32::smoothed()` already does this, so there is no need to call this before it.
     ///
     /// [`ParamF32Handle`]: struct.ParamF32Handle.html
-    pub fn host_get_value(&self) -> f32 {
-        self.value
+    pub fn poll_shared(&mut self) -> bool {
+        let changes = self.shared_changes.load(Ordering::Acquire);
+        if self.skip_unchanged_polls && changes == self.polled_changes {
+            return false;
+        }
+        self.polled_changes = changes;
+
+        let shared_normalized = self.shared_normalized.get();
+        let new_trim = self.shared_trim.get();
+        if shared_normalized == self.last_shared_normalized
+            && new_trim == self.trim_offset_normalized
+        {
+            return false;
+        }
+        self.last_shared_normalized = shared_normalized;
+
+        // Snapping and quantizing only apply to the value used by this parameter. The shared
+        // value is owned by the handles, so writing the result back could overwrite a newer
+        // value from the UI.
+        let new_normalized =
+            self.quantize(self.snap(shared_normalized).unwrap_or(shared_normalized));
+
+        if (new_normalized - self.normalized).abs() > self.change_deadband
+            || self.trim_offset_normalized != new_trim
+        {
+            self.normalized = new_normalized;
+            self.trim_offset_normalized = new_trim;
+
+            let v = normalized_to_value_unclamped_f32(
+                self.effective_normalized(),
+                self.min,
+                self.max,
+                self.gradient,
+            );
+            self.value = self.unit.unit_to_dsp_f32(v);
+
+            self.smoothed.set(self.value);
+
+            true
+        } else {
+            false
+        }
     }
 
-    /// Get the shared normalized float value.
+    /// Returns `true` if the value or trim offset of this parameter was written through a
+    /// [`ParamF32Handle`] (or by this parameter) since the last time the shared state was
+    /// read by `ParamF32::poll_shared()` or `ParamF32::smoothed()`.
     ///
-    /// This can be useful to integrate with various plugin APIs.
-    pub fn shared_normalized(&self) -> Arc<AtomicF32> {
-        Arc::clone(&self.shared_normalized)
+    /// Values written directly to the atomic returned by `ParamF32::shared_normalized()` are
+    /// not counted.
+    ///
+    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
+    pub fn has_pending_changes(&self) -> bool {
+        self.shared_changes.load(Ordering::Acquire) != self.polled_changes
     }
-}
 
-/// A handle to get and update the value of an auto-smoothed [`ParamF32`] from a UI.
-///
-/// [`ParamF32`]: struct.ParamF32.html
-pub struct ParamF32Handle {
-    min: f32,
-    max: f32,
-    gradient: Gradient,
-    unit: Unit,
-    default_value: f32,
+    /// Returns `true` if the value of this parameter was changed (either by this parameter
+    /// or through any [`ParamF32Handle`]) since the last time this was called.
+    ///
+    /// This also picks up any change made through the [`ParamF32Handle`] like
+    /// `ParamF32::poll_shared()`, so anything derived from the value of this parameter only
+    /// needs to be re-computed when this returns `true`.
+    ///
+    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
+    pub fn poll_changed(&mut self) -> bool {
+        // Values written directly to the shared atomic don't update the change counter, but
+        // they are still picked up here.
+        let picked_up = self.poll_shared();
 
-    shared_normalized: Arc<AtomicF32>,
-}
+        let changes = self.shared_changes.load(Ordering::Acquire);
+        let counted = changes != self.seen_changes;
+        self.seen_changes = changes;
 
-impl ParamF32Handle {
-    /// The normalized value in the range `[0.0, 1.0]`.
-    pub fn normalized(&self) -> f32 {
-        self.shared_normalized.get()
+        picked_up || counted
     }
 
-    /// The (un-normalized) value of this parameter.
+    /// Write the normalized value to the shared state and notify any observers of the change.
+    fn store_shared_normalized(&mut self) {
+        self.shared_normalized.set(self.normalized);
+        self.last_shared_normalized = self.normalized;
+        self.shared_changes.fetch_add(1, Ordering::Release);
+    }
+
+    /// Capture the exact internal state of this parameter, including the current output,
+    /// target, and status of the smoothing filter.
     ///
-    /// Please note that this is calculated from the shared normalized value every time, so
-    /// avoid calling this every frame if you can.
-    pub fn value(&self) -> f32 {
-        normalized_to_value_f32(
-            self.shared_normalized.get(),
-            self.min,
-            self.max,
-            self.gradient,
-        )
+    /// This can be used to resume rendering bit-identically with `ParamF32::restore_state()`
+    /// (i.e. when bouncing in chunks).
+    pub fn save_state(&self) -> ParamF32SmoothState {
+        ParamF32SmoothState {
+            normalized: self.normalized,
+            value: self.value,
+            smooth: self.smoothed.state(),
+        }
     }
 
-    /// The (un-normalized) default value of the parameter.
-    pub fn default_value(&self) -> f32 {
-        self.default_value
+    /// Restore the internal state of this parameter from a state captured with
+    /// `ParamF32::save_state()`.
+    ///
+    /// This will also update the shared normalized value.
+    pub fn restore_state(&mut self, state: ParamF32SmoothState) {
+        self.normalized = state.normalized;
+        self.store_shared_normalized();
+        self.value = state.value;
+
+        self.smoothed.set_state(state.smooth);
     }
 
-    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
-    pub fn default_normalized(&self) -> f32 {
-        self.value_to_normalized(self.default_value)
+    /// Update the sample rate (used for the parameter smoothing LPF).
+    pub fn set_sample_rate(&mut self, sample_rate: SampleRate) {
+        self.sample_rate = sample_rate;
+        self.smoothed.set_speed(sample_rate, self.smooth_secs);
+        self.smoothed
+            .set_fast_speed(sample_rate, self.fast_smooth_secs);
     }
 
-    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
+    /// Change the period of the low-pass parameter smoothing filter (i.e. to momentarily
+    /// lengthen it during a big preset change).
     ///
-    /// Please note that this will ***NOT*** automatically notify the host of the value change
-    /// if you are using this inside a plugin spec such as VST. It is intended for you use your
-    /// own method for achieving this.
-    pub fn set_normalized(&self, normalized: f32) {
-        self.shared_normalized.set(normalized.clamp(0.0, 1.0));
+    /// This can be called while the filter is smoothing. The current output is kept as-is,
+    /// and only the speed at which it moves towards the target from there changes. With
+    /// `SmoothMode::Linear`, a ramp in progress is restarted from the current output so that
+    /// it reaches the target in the new `smooth_secs`.
+    ///
+    /// Note that this does not change the `smooth_secs` reported by an existing
+    /// [`ParamF32Handle`].
+    ///
+    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
+    pub fn set_smooth_secs(&mut self, smooth_secs: SecondsF64, sample_rate: SampleRate) {
+        self.smooth_secs = smooth_secs;
+        self.sample_rate = sample_rate;
+        self.smoothed.set_speed(sample_rate, smooth_secs);
+
+        if self.smoothed.mode() == SmoothMode::Linear && self.smoothed.is_active() {
+            let target = self.smoothed.dest();
+            self.smoothed.set(target);
+        }
     }
 
-    /// Set the (un-normalized) value of this parameter.
+    /// Set how the smoothing filter moves towards a new value.
     ///
-    /// Please note that this will ***NOT*** automatically notify the host of the value change
-    /// if you are using this inside a plugin spec such as VST. It is intended for you use your
-    /// own method for achieving this.
-    pub fn set_value(&self, value: f32) {
-        let normalized = value_to_normalized_f32(value, self.min, self.max, self.gradient);
-        self.set_normalized(normalized);
+    /// The default is `SmoothMode::Exponential`, which is best suited for declicking. Use
+    /// `SmoothMode::PerBlockLinear` for sample-accurate automation playback, or
+    /// `SmoothMode::Linear` for fades that reach the target in exactly `smooth_secs`.
+    ///
+    /// This is meant to be called right after constructing the parameter.
+    pub fn set_smooth_mode(&mut self, mode: SmoothMode) {
+        self.smoothed.set_mode(mode);
+    }
+
+    /// Use two-stage smoothing (`SmoothMode::TwoStage`), where the value responds quickly
+    /// to a change and then settles slowly. This gives a more musical (analog-like) response
+    /// for knobs.
+    ///
+    /// The slow stage uses the `smooth_secs` this parameter was created with.
+    ///
+    /// * `fast_secs` - The period of the fast stage. This should be shorter than
+    ///   `smooth_secs`.
+    pub fn set_two_stage_smoothing(&mut self, fast_secs: SecondsF64) {
+        self.fast_smooth_secs = fast_secs;
+        self.smoothed.set_fast_speed(self.sample_rate, fast_secs);
+        self.smoothed.set_mode(SmoothMode::TwoStage);
     }
 
     /// The minimum value of this parameter.
@@ -525,178 +1304,1848 @@ impl ParamF32Handle {
         self.unit
     }
 
-    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
-    /// of this parameter.
-    pub fn value_to_normalized(&self, value: f32) -> f32 {
-        value_to_normalized_f32(value, self.min, self.max, self.gradient)
-    }
-
-    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
-    /// corresponding value of this parameter.
-    pub fn normalized_to_value(&self, normalized: f32) -> f32 {
-        normalized_to_value_f32(normalized, self.min, self.max, self.gradient)
+    /// The current (un-normalized) value of this parameter formatted with `precision`
+    /// decimal places and the unit suffix, i.e. `"-6.0 dB"`. See `Unit::format_value_f32()`.
+    pub fn value_to_string(&self, precision: usize) -> String {
+        let value = normalized_to_value_unclamped_f32(
+            self.effective_normalized(),
+            self.min,
+            self.max,
+            self.gradient,
+        );
+        self.unit.format_value_f32(value, precision)
     }
 
-    /// Get the shared normalized float value.
+    /// Parse an (un-normalized) value of this parameter from text (i.e. typed in by the
+    /// user), the inverse of `ParamF32::value_to_string()`. See `Unit::parse_value_f32()`.
     ///
-    /// This can be useful to integrate with various plugin APIs.
-    pub fn shared_normalized(&self) -> Arc<AtomicF32> {
-        Arc::clone(&self.shared_normalized)
+    /// Note that the returned value is not clamped to the range of this parameter.
+    pub fn string_to_value(&self, text: &str) -> Option<f32> {
+        self.unit.parse_value_f32(text)
     }
-}
 
-impl Clone for ParamF32Handle {
-    fn clone(&self) -> Self {
-        Self {
+    /// The static configuration of this parameter.
+    pub fn config(&self) -> ParamF32Config {
+        ParamF32Config {
             min: self.min,
             max: self.max,
             gradient: self.gradient,
             unit: self.unit,
+            smooth_secs: self.smooth_secs,
             default_value: self.default_value,
+        }
+    }
 
-            shared_normalized: Arc::clone(&self.shared_normalized),
+    /// The persistent state of this parameter (i.e. for saving it to a project file).
+    pub fn state(&self) -> ParamF32State {
+        ParamF32State {
+            normalized: self.normalized,
+            default_value: self.default_value,
+            min: self.min,
+            max: self.max,
+            gradient: self.gradient,
+            unit: self.unit,
         }
     }
-}
 
-pub fn normalized_to_value_f32(normalized: f32, min: f32, max: f32, gradient: Gradient) -> f32 {
-    let normalized = normalized.clamp(0.0, 1.0);
+    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
+    /// of this parameter.
+    pub fn value_to_normalized(&self, value: f32) -> f32 {
+        value_to_normalized_f32(value, self.min, self.max, self.gradient)
+    }
 
-    let map = |x: f32| -> f32 {
-        let range = max - min;
-        (x * range) + min
-    };
+    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
+    /// corresponding value of this parameter.
+    pub fn normalized_to_value(&self, normalized: f32) -> f32 {
+        normalized_to_value_f32(normalized, self.min, self.max, self.gradient)
+    }
 
-    match gradient {
-        Gradient::Linear => map(normalized),
+    /// The slope `d(value) / d(normalized)` of the gradient mapping at the given normalized
+    /// value in the range `[0.0, 1.0]`.
+    ///
+    /// This can be used by UIs to adapt the drag sensitivity of a control to the mapping.
+    pub fn value_slope_at(&self, normalized: f32) -> f32 {
+        let normalized = normalized.clamp(0.0, 1.0);
+        let range = self.max - self.min;
 
-        Gradient::Power(exponent) => map(normalized.powf(exponent)),
+        match self.gradient {
+            Gradient::Linear => range,
 
-        Gradient::Exponential => {
-            if normalized == 0.0 {
-                return min;
+            Gradient::Power(exponent) => range * exponent * normalized.powf(exponent - 1.0),
+
+            Gradient::SCurve(steepness) => {
+                range * s_curve_slope(f64::from(normalized), f64::from(steepness)) as f32
             }
 
-            if normalized == 1.0 {
-                return max;
+            Gradient::Bezier { x1, y1, x2, y2 } => {
+                range * bezier_slope(f64::from(normalized), x1, y1, x2, y2) as f32
             }
 
-            let minl = min.log2();
-            let range = max.log2() - minl;
-            2.0f32.powf((normalized * range) + minl)
+            Gradient::Exponential => {
+                let min = self.min.max(EXPONENTIAL_MIN_F32);
+                let max = self.max.max(EXPONENTIAL_MIN_F32);
+
+                let log_range = (max / min).ln();
+                min * (normalized * log_range).exp() * log_range
+            }
         }
     }
-}
 
-pub fn value_to_normalized_f32(value: f32, min: f32, max: f32, gradient: Gradient) -> f32 {
-    if value <= min {
-        return 0.0;
+    /// Convert a list of (de-normalized) value changes into a list of normalized breakpoints
+    /// in the range `[0.0, 1.0]`, (i.e. for exporting automation to a host).
+    ///
+    /// Values outside the range of this parameter are clamped.
+    pub fn export_normalized_breakpoints(
+        &self,
+        changes: &[(FrameTime, f32)],
+    ) -> Vec<(FrameTime, f32)> {
+        changes
+            .iter()
+            .map(|(t, value)| (*t, self.value_to_normalized(*value)))
+            .collect()
     }
 
-    if value >= max {
-        return 1.0;
+    /// Convert a delta in (de-normalized) value units (i.e. a modulation depth of `±200 Hz`)
+    /// into the corresponding delta in normalized units, starting from `base_value`.
+    ///
+    /// For non-linear gradients the same value delta corresponds to a different normalized
+    /// delta depending on where in the range `base_value` lies.
+    ///
+    /// Note that the result is limited by the range of this parameter, so a delta that would
+    /// go past `min` or `max` only covers the distance up to that bound.
+    pub fn value_delta_to_normalized_at(&self, base_value: f32, delta_value: f32) -> f32 {
+        self.value_to_normalized(base_value + delta_value) - self.value_to_normalized(base_value)
     }
 
-    let unmap = |x: f32| -> f32 {
-        let range = max - min;
-        (x - min) / range
-    };
+    /// The current normalized value in the range `[0.0, 1.0]`. This is only meant for
+    /// communicating with the host. This is not meant to be used to retrieve the latest
+    /// value for DSP. To get the latest value for DSP please use `ParamF32::smoothed()`
+    /// instead.
+    ///
+    /// Please note that this should be called *after* calling `ParamF32::smoothed()`
+    /// if you need the latest value from the corresponding [`ParamF32Handle`],
+    /// otherwise this may not return the latest value.
+    ///
+    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
+    pub fn host_get_normalized(&self) -> f32 {
+        self.normalized.min(1.0)
+    }
 
-    match gradient {
-        Gradient::Linear => unmap(value),
+    /// The trim offset (in normalized units) that is added on top of the normalized
+    /// (automation) value before it is mapped to the DSP value.
+    pub fn trim_offset_normalized(&self) -> f32 {
+        self.trim_offset_normalized
+    }
 
-        Gradient::Power(exponent) => unmap(value).powf(1.0 / exponent),
+    /// Set the trim offset (in normalized units) that is added on top of the normalized
+    /// (automation) value before it is mapped to the DSP value. This is used for a
+    /// "trim/relative" automation mode, where live tweaks ride on top of the recorded
+    /// automation without changing it.
+    ///
+    /// The sum of the normalized value and the trim offset is clamped to the range
+    /// `[0.0, 1.0]`.
+    ///
+    /// * `offset` - The trim offset in the range `[-1.0, 1.0]`.
+    pub fn set_trim_offset_normalized(&mut self, offset: f32) {
+        let offset = offset.clamp(-1.0, 1.0);
+        if self.trim_offset_normalized != offset {
+            self.trim_offset_normalized = offset;
+            self.shared_trim.set(offset);
+            self.shared_changes.fetch_add(1, Ordering::Release);
+
+            let v = normalized_to_value_unclamped_f32(
+                self.effective_normalized(),
+                self.min,
+                self.max,
+                self.gradient,
+            );
+            self.value = self.unit.unit_to_dsp_f32(v);
 
-        Gradient::Exponential => {
-            let minl = min.log2();
-            let range = max.log2() - minl;
-            (value.log2() - minl) / range
+            self.smoothed.set(self.value);
         }
     }
-}
-
-// ------  F64  -------------------------------------------------------------------------
 
-/// An auto-smoothed parameter with an `f64` value.
-pub struct ParamF64 {
-    min: f64,
-    max: f64,
-    gradient: Gradient,
-    unit: Unit,
+    /// The amount of modulation (in normalized units) that is added on top of the normalized
+    /// value before it is mapped to the DSP value.
+    pub fn modulation(&self) -> f32 {
+        self.modulation_normalized
+    }
 
-    shared_normalized: Arc<AtomicF64>,
-    normalized: f64,
+    /// Set the amount of modulation (in normalized units) that is added on top of the
+    /// normalized value before it is mapped to the DSP value (i.e. from an LFO or an
+    /// envelope).
+    ///
+    /// Unlike setting the value directly, this leaves the base value untouched, so
+    /// `ParamF32::host_get_normalized()` and the [`ParamF32Handle`] still report the user's
+    /// knob position. Set this to `0.0` to clear the modulation.
+    ///
+    /// The sum of the normalized value, the trim offset, and the modulation is clamped to
+    /// the range `[0.0, 1.0]`.
+    ///
+    /// * `amount_normalized` - The modulation amount in the range `[-1.0, 1.0]`.
+    ///
+    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
+    pub fn set_modulation(&mut self, amount_normalized: f32) {
+        let amount_normalized = amount_normalized.clamp(-1.0, 1.0);
+        if self.modulation_normalized != amount_normalized {
+            self.modulation_normalized = amount_normalized;
+
+            let v = normalized_to_value_unclamped_f32(
+                self.effective_normalized(),
+                self.min,
+                self.max,
+                self.gradient,
+            );
+            self.value = self.unit.unit_to_dsp_f32(v);
 
-    value: f64,
-    default_value: f64,
+            self.smoothed.set(self.value);
+        }
+    }
 
-    smoothed: SmoothF64,
-    smooth_secs: SecondsF64,
-}
+    /// The normalized value with the trim offset and modulation applied, clamped to the
+    /// range `[0.0, 1.0]` (or to the overshoot limit if overshoot is allowed).
+    pub fn effective_normalized(&self) -> f32 {
+        (self.normalized + self.trim_offset_normalized + self.modulation_normalized)
+            .clamp(0.0, self.normalized_limit())
+    }
 
-impl ParamF64 {
-    /// Create a Parameter/Handle pair from its (de-normalized) value.
+    /// Allow the normalized value to exceed `1.0` (up to the limit set with
+    /// `ParamF32::set_overshoot_limit()`), mapping to values beyond `max`. This is useful for
+    /// parameters like a drive knob where the user may push slightly past the nominal range
+    /// for extra effect.
     ///
-    /// * value - The initial (de-normalized) value of the parameter.
-    /// * default_value - The default (de-normalized) value of the parameter.
-    /// * min - The minimum (de-normalized) value of the parameter.
-    /// * max - The maximum (de-normalized) value of the parameter.
-    /// * gradient - The [`Gradient`] mapping used when converting from the normalized value
-    /// in the range `[0.0, 1.0]` to the desired value. If this parameter deals with decibels,
-    /// you may use `ParamF64::DEFAULT_SMOOTH_SECS` as a good default.
-    /// * unit - The [`Unit`] that signifies how the value displayed to the end user should
-    /// differ from the actual value used in DSP.
-    /// * smooth_secs: The period of the low-pass parameter smoothing filter (for declicking). You
-    /// may use `ParamF64::DEFAULT_SMOOTH_SECS` as a good default.
-    /// * sample_rate: The sample rate of this process. This is used for the low-pass parameter
-    /// smoothing filter.
+    /// The mapping of in-range values is unchanged. Hosts should still be told that the range
+    /// of this parameter is `[0.0, 1.0]`, so `ParamF32::host_get_normalized()` is capped at
+    /// `1.0`. Any overshoot can only be set through this parameter or its
+    /// [`ParamF32Handle`] (i.e. from the plugin's own UI), and any value set by the host will
+    /// lie within the nominal range.
+    ///
+    /// If overshoot is disallowed while the value lies past `1.0`, then the value is brought
+    /// back to `1.0`.
+    ///
+    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
+    pub fn set_allow_overshoot(&mut self, allow_overshoot: bool) {
+        self.allow_overshoot = allow_overshoot;
+        self.apply_normalized_limit();
+    }
+
+    /// Set the maximum normalized value when overshoot is allowed (see
+    /// `ParamF32::set_allow_overshoot()`). This is clamped to be at least `1.0`. By default
+    /// this is `1.0` (no overshoot).
+    pub fn set_overshoot_limit(&mut self, limit_normalized: f32) {
+        self.overshoot_limit = limit_normalized.max(1.0);
+        self.apply_normalized_limit();
+    }
+
+    /// Whether the normalized value is allowed to exceed `1.0`.
+    pub fn allow_overshoot(&self) -> bool {
+        self.allow_overshoot
+    }
+
+    /// The maximum normalized value when overshoot is allowed.
+    pub fn overshoot_limit(&self) -> f32 {
+        self.overshoot_limit
+    }
+
+    /// Set the distance (in normalized units) a new value must differ from the current value
+    /// by to be applied. Smaller changes (i.e. from noisy automation) are ignored, so they
+    /// don't restart the smoothing filter. Set this to `0.0` to apply every change.
+    ///
+    /// By default this is `DEFAULT_CHANGE_DEADBAND_NORMALIZED`.
+    pub fn set_change_deadband(&mut self, deadband_normalized: f32) {
+        self.change_deadband = deadband_normalized.max(0.0);
+    }
+
+    /// The distance (in normalized units) a new value must differ from the current value by
+    /// to be applied. See `ParamF32::set_change_deadband()`.
+    pub fn change_deadband(&self) -> f32 {
+        self.change_deadband
+    }
+
+    /// Only read the shared state in `ParamF32::poll_shared()` (and so in
+    /// `ParamF32::smoothed()`) when a [`ParamF32Handle`] has written to it since the last
+    /// poll (see `ParamF32::has_pending_changes()`). This saves a few atomic loads and
+    /// comparisons per block in sessions with many static parameters.
+    ///
+    /// Values written directly to the atomic returned by `ParamF32::shared_normalized()` are
+    /// then only picked up after the next write through a handle, so only enable this if
+    /// all writes go through a [`ParamF32Handle`]. By default this is disabled.
+    ///
+    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
+    pub fn set_skip_unchanged_polls(&mut self, skip: bool) {
+        self.skip_unchanged_polls = skip;
+    }
+
+    /// Whether the shared state is only read when a handle has written to it. See
+    /// `ParamF32::set_skip_unchanged_polls()`.
+    pub fn skip_unchanged_polls(&self) -> bool {
+        self.skip_unchanged_polls
+    }
+
+    /// The maximum normalized value this parameter can currently be set to.
+    fn normalized_limit(&self) -> f32 {
+        if self.allow_overshoot {
+            self.overshoot_limit
+        } else {
+            1.0
+        }
+    }
+
+    fn apply_normalized_limit(&mut self) {
+        let limit = self.normalized_limit();
+        self.shared_normalized_limit.set(limit);
+
+        if self.normalized > limit {
+            self.set_normalized(limit);
+        }
+    }
+
+    /// The (un-normalized) default value of the parameter.
+    pub fn default_value(&self) -> f32 {
+        self.default_value
+    }
+
+    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
+    pub fn default_normalized(&self) -> f32 {
+        self.value_to_normalized(self.default_value)
+    }
+
+    /// The current (un-normalized) value of the parameter. This is only meant for
+    /// communicating with the host. This is not meant to be used to retrieve the latest
+    /// value for DSP. To get the latest value for DSP please use `ParamF32::smoothed()`
+    /// instead.
+    ///
+    /// Please note that this should be called *after* calling `ParamF32::smoothed()`
+    /// if you need the latest value from the corresponding [`ParamF32Handle`],
+    /// otherwise this may not return the latest value.
+    ///
+    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
+    pub fn host_get_value(&self) -> f32 {
+        self.value
+    }
+
+    /// The current target DSP value of the parameter, without advancing the smoothing
+    /// filter.
+    ///
+    /// Note that this is the value the smoothing filter is moving towards, *not* the
+    /// in-flight smoothed value. Use `ParamF32::current_smoothed_value()` for that instead.
+    ///
+    /// Please note that this does not pick up any new value from the corresponding
+    /// [`ParamF32Handle`] until the next call to `ParamF32::smoothed()`.
+    ///
+    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
+    pub fn peek_value(&self) -> f32 {
+        self.value
+    }
+
+    /// The most recent output of the smoothing filter (the last value of the buffer from the
+    /// most recent call to `ParamF32::smoothed()`), without advancing the smoothing filter.
+    ///
+    /// This is useful for initializing a value that depends on this parameter (i.e. a filter
+    /// coefficient) before processing the next block.
+    pub fn current_smoothed_value(&self) -> f32 {
+        self.smoothed.current_value().0
+    }
+
+    /// Get the shared normalized float value.
+    ///
+    /// This can be useful to integrate with various plugin APIs.
+    pub fn shared_normalized(&self) -> Arc<AtomicF32> {
+        Arc::clone(&self.shared_normalized)
+    }
+}
+
+/// A handle to get and update the value of an auto-smoothed [`ParamF32`] from a UI.
+///
+/// [`ParamF32`]: struct.ParamF32.html
+pub struct ParamF32Handle {
+    min: f32,
+    max: f32,
+    gradient: Gradient,
+    unit: Unit,
+    default_value: f32,
+    smooth_secs: SecondsF64,
+
+    shared_normalized: Arc<AtomicF32>,
+    shared_changes: Arc<AtomicU64>,
+    shared_trim: Arc<AtomicF32>,
+    shared_normalized_limit: Arc<AtomicF32>,
+    param_alive: Weak<()>,
+
+    // The value of the shared change counter the last time `take_dirty()` was called.
+    seen_changes: AtomicU64,
+
+    // The last normalized value received from a controller, used to detect when it crosses
+    // the current value in `PickupMode::Catch`. This is `NaN` if no value was received yet.
+    last_controller_normalized: AtomicF32,
+}
+
+impl ParamF32Handle {
+    /// Create a [`WeakParamF32Handle`] to this parameter, which can be used to detect when
+    /// the corresponding [`ParamF32`] has been dropped.
+    ///
+    /// [`ParamF32`]: struct.ParamF32.html
+    /// [`WeakParamF32Handle`]: struct.WeakParamF32Handle.html
+    pub fn downgrade(&self) -> WeakParamF32Handle {
+        WeakParamF32Handle {
+            min: self.min,
+            max: self.max,
+            gradient: self.gradient,
+            unit: self.unit,
+            default_value: self.default_value,
+            smooth_secs: self.smooth_secs,
+
+            shared_normalized: Arc::downgrade(&self.shared_normalized),
+            shared_changes: Arc::downgrade(&self.shared_changes),
+            shared_trim: Arc::downgrade(&self.shared_trim),
+            shared_normalized_limit: Arc::downgrade(&self.shared_normalized_limit),
+            param_alive: Weak::clone(&self.param_alive),
+        }
+    }
+
+    /// The normalized value in the range `[0.0, 1.0]`.
+    pub fn normalized(&self) -> f32 {
+        self.shared_normalized.get()
+    }
+
+    /// The (un-normalized) value of this parameter.
+    ///
+    /// Please note that this is calculated from the shared normalized value every time, so
+    /// avoid calling this every frame if you can.
+    pub fn value(&self) -> f32 {
+        normalized_to_value_unclamped_f32(
+            self.shared_normalized.get(),
+            self.min,
+            self.max,
+            self.gradient,
+        )
+    }
+
+    /// The normalized value in the range `[0.0, 1.0]` and the corresponding (un-normalized)
+    /// value of this parameter, in that order.
+    ///
+    /// Both values are derived from a single load of the shared normalized value, so they
+    /// are always consistent with each other (unlike calling `normalized()` and `value()`
+    /// separately while the audio thread may be writing in between).
+    pub fn snapshot(&self) -> (f32, f32) {
+        let normalized = self.shared_normalized.get();
+        let value =
+            normalized_to_value_unclamped_f32(normalized, self.min, self.max, self.gradient);
+
+        (normalized, value)
+    }
+
+    /// The current (un-normalized) value of this parameter formatted for a fixed-width
+    /// display, such as the LCD segments of a hardware control surface.
+    ///
+    /// The returned string is always exactly `width` characters long (right-aligned and
+    /// padded with spaces). To make the value fit, decimal places are dropped first, then
+    /// the unit suffix is dropped. Only if the full value still doesn't fit are values of
+    /// `1000` or more abbreviated with a `"k"` (i.e. `"12.5kHz"`, trying the same steps
+    /// again), and as a last resort the string is truncated.
+    pub fn value_display_fixed(&self, width: usize) -> String {
+        let value = self.value();
+        let suffix = self.unit.suffix();
+
+        let mut forms = vec![(value, "")];
+        if value.abs() >= 1_000.0 {
+            forms.push((value / 1_000.0, "k"));
+        }
+
+        let mut text = None;
+        'outer: for &(value, prefix) in forms.iter() {
+            for suffix in [suffix, ""] {
+                for decimals in [2, 1, 0] {
+                    let candidate = format!("{:.*}{}{}", decimals, value, prefix, suffix);
+                    if candidate.chars().count() <= width {
+                        text = Some(candidate);
+                        break 'outer;
+                    }
+                }
+            }
+        }
+        let text = text.unwrap_or_else(|| {
+            let (value, prefix) = forms[forms.len() - 1];
+            format!("{:.0}{}", value, prefix)
+                .chars()
+                .take(width)
+                .collect()
+        });
+
+        format!("{:>width$}", text, width = width)
+    }
+
+    /// The (un-normalized) default value of the parameter.
+    pub fn default_value(&self) -> f32 {
+        self.default_value
+    }
+
+    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
+    pub fn default_normalized(&self) -> f32 {
+        self.value_to_normalized(self.default_value)
+    }
+
+    /// Set the normalized value of this parameter in the range `[0.0, 1.0]` (or up to the
+    /// overshoot limit if the parameter allows overshoot, see
+    /// `ParamF32::set_allow_overshoot()`).
+    ///
+    /// Please note that this will ***NOT*** automatically notify the host of the value change
+    /// if you are using this inside a plugin spec such as VST. It is intended for you use your
+    /// own method for achieving this.
+    pub fn set_normalized(&self, normalized: f32) {
+        let limit = self.shared_normalized_limit.get();
+        if self
+            .shared_normalized
+            .set_if_changed(normalized.clamp(0.0, limit))
+        {
+            self.shared_changes.fetch_add(1, Ordering::Release);
+        }
+    }
+
+    /// Returns `true` if the value of this parameter was changed (either by the [`ParamF32`]
+    /// or through any handle) since the last time this was called on this handle.
+    ///
+    /// This is useful for only redrawing a UI element when the value actually changed,
+    /// instead of comparing the value every frame.
+    ///
+    /// [`ParamF32`]: struct.ParamF32.html
+    pub fn take_dirty(&self) -> bool {
+        let changes = self.shared_changes.load(Ordering::Acquire);
+        self.seen_changes.swap(changes, Ordering::AcqRel) != changes
+    }
+
+    /// Set the (un-normalized) value of this parameter.
+    ///
+    /// Please note that this will ***NOT*** automatically notify the host of the value change
+    /// if you are using this inside a plugin spec such as VST. It is intended for you use your
+    /// own method for achieving this.
+    pub fn set_value(&self, value: f32) {
+        let normalized = value_to_normalized_f32(value, self.min, self.max, self.gradient);
+        self.set_normalized(normalized);
+    }
+
+    /// Set the (un-normalized) value of this parameter from an external controller (i.e. a
+    /// hardware knob mapped with MIDI learn), using the given [`PickupMode`]. Returns `true`
+    /// if the value was applied.
+    ///
+    /// In `PickupMode::Catch`, the value is ignored until the controller crosses (or comes
+    /// within `PICKUP_RADIUS_NORMALIZED` of) the current value of the parameter. From then on
+    /// it follows the controller, until the value is changed by something else.
+    ///
+    /// Note that each handle keeps track of its own controller, so use a separate handle
+    /// (i.e. a clone) for each controller.
+    ///
+    /// [`PickupMode`]: enum.PickupMode.html
+    pub fn set_from_controller_with_pickup(&self, value: f32, mode: PickupMode) -> bool {
+        let normalized = value_to_normalized_f32(value, self.min, self.max, self.gradient);
+        let last = self.last_controller_normalized.get();
+        self.last_controller_normalized.set(normalized);
+
+        if mode == PickupMode::Catch {
+            let current = self.normalized();
+
+            // `last` is `NaN` if this is the first controller value, in which case the
+            // controller has not crossed.
+            let crossed = (last - current) * (normalized - current) <= 0.0;
+            if !crossed && (normalized - current).abs() > PICKUP_RADIUS_NORMALIZED {
+                return false;
+            }
+        }
+
+        self.set_normalized(normalized);
+        true
+    }
+
+    /// The trim offset (in normalized units) that is added on top of the normalized
+    /// (automation) value.
+    pub fn trim_offset_normalized(&self) -> f32 {
+        self.shared_trim.get()
+    }
+
+    /// Set the trim offset (in normalized units) that is added on top of the normalized
+    /// (automation) value before it is mapped to the DSP value, so live tweaks ride on top
+    /// of recorded automation. See `ParamF32::set_trim_offset_normalized()`.
+    ///
+    /// * `offset` - The trim offset in the range `[-1.0, 1.0]`.
+    pub fn set_trim_offset_normalized(&self, offset: f32) {
+        if self.shared_trim.set_if_changed(offset.clamp(-1.0, 1.0)) {
+            self.shared_changes.fetch_add(1, Ordering::Release);
+        }
+    }
+
+    /// The minimum value of this parameter.
+    pub fn min(&self) -> f32 {
+        self.min
+    }
+
+    /// The maximum value of this parameter.
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+
+    /// The [`Gradient`] mapping used when converting from the normalized value
+    /// in the range `[0.0, 1.0]` to the desired value.
+    ///
+    /// [`Gradient`]: enum.Gradient.html
+    pub fn gradient(&self) -> Gradient {
+        self.gradient
+    }
+
+    /// The [`Unit`] that signifies how the value displayed to the end user should
+    /// differ from the actual value used in DSP.
+    ///
+    /// [`Unit`]: enum.Unit.html
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    /// The current (un-normalized) value of this parameter formatted with `precision`
+    /// decimal places and the unit suffix, i.e. `"-6.0 dB"`. See `Unit::format_value_f32()`.
+    pub fn value_to_string(&self, precision: usize) -> String {
+        self.unit.format_value_f32(self.value(), precision)
+    }
+
+    /// Parse an (un-normalized) value of this parameter from text (i.e. typed in by the
+    /// user), the inverse of `ParamF32Handle::value_to_string()`. See
+    /// `Unit::parse_value_f32()`.
+    ///
+    /// Note that the returned value is not clamped to the range of this parameter.
+    pub fn string_to_value(&self, text: &str) -> Option<f32> {
+        self.unit.parse_value_f32(text)
+    }
+
+    /// The static configuration of this parameter.
+    pub fn config(&self) -> ParamF32Config {
+        ParamF32Config {
+            min: self.min,
+            max: self.max,
+            gradient: self.gradient,
+            unit: self.unit,
+            smooth_secs: self.smooth_secs,
+            default_value: self.default_value,
+        }
+    }
+
+    /// The persistent state of this parameter (i.e. for saving it to a project file).
+    pub fn state(&self) -> ParamF32State {
+        ParamF32State {
+            normalized: self.normalized(),
+            default_value: self.default_value,
+            min: self.min,
+            max: self.max,
+            gradient: self.gradient,
+            unit: self.unit,
+        }
+    }
+
+    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
+    /// of this parameter.
+    pub fn value_to_normalized(&self, value: f32) -> f32 {
+        value_to_normalized_f32(value, self.min, self.max, self.gradient)
+    }
+
+    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
+    /// corresponding value of this parameter.
+    pub fn normalized_to_value(&self, normalized: f32) -> f32 {
+        normalized_to_value_f32(normalized, self.min, self.max, self.gradient)
+    }
+
+    /// Get the shared normalized float value.
+    ///
+    /// This can be useful to integrate with various plugin APIs.
+    pub fn shared_normalized(&self) -> Arc<AtomicF32> {
+        Arc::clone(&self.shared_normalized)
+    }
+}
+
+impl Clone for ParamF32Handle {
+    fn clone(&self) -> Self {
+        Self {
+            min: self.min,
+            max: self.max,
+            gradient: self.gradient,
+            unit: self.unit,
+            default_value: self.default_value,
+            smooth_secs: self.smooth_secs,
+
+            shared_normalized: Arc::clone(&self.shared_normalized),
+            shared_changes: Arc::clone(&self.shared_changes),
+            shared_trim: Arc::clone(&self.shared_trim),
+            shared_normalized_limit: Arc::clone(&self.shared_normalized_limit),
+            param_alive: Weak::clone(&self.param_alive),
+            seen_changes: AtomicU64::new(self.seen_changes.load(Ordering::Acquire)),
+            last_controller_normalized: AtomicF32::new(f32::NAN),
+        }
+    }
+}
+
+/// A weak reference to a [`ParamF32Handle`], used to detect when the corresponding
+/// [`ParamF32`] has been dropped.
+///
+/// Note that the shared atomics of a parameter stay alive as long as *either* the
+/// [`ParamF32`] or any of its [`ParamF32Handle`]s exist. To detect when only the audio-side
+/// [`ParamF32`] has been dropped (while strong handles still exist), every handle also keeps
+/// a weak reference to a token that is owned by the [`ParamF32`] alone.
+/// `WeakParamF32Handle::upgrade()` fails once that token is gone, no matter when this weak
+/// handle was created.
+///
+/// [`ParamF32`]: struct.ParamF32.html
+/// [`ParamF32Handle`]: struct.ParamF32Handle.html
+pub struct WeakParamF32Handle {
+    min: f32,
+    max: f32,
+    gradient: Gradient,
+    unit: Unit,
+    default_value: f32,
+    smooth_secs: SecondsF64,
+
+    shared_normalized: Weak<AtomicF32>,
+    shared_changes: Weak<AtomicU64>,
+    shared_trim: Weak<AtomicF32>,
+    shared_normalized_limit: Weak<AtomicF32>,
+    param_alive: Weak<()>,
+}
+
+impl WeakParamF32Handle {
+    /// Try to get a strong [`ParamF32Handle`]. This will return `None` if the corresponding
+    /// [`ParamF32`] has been dropped.
+    ///
+    /// [`ParamF32`]: struct.ParamF32.html
+    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
+    pub fn upgrade(&self) -> Option<ParamF32Handle> {
+        if self.param_alive.strong_count() == 0 {
+            return None;
+        }
+
+        let shared_changes = self.shared_changes.upgrade()?;
+        let seen_changes = AtomicU64::new(shared_changes.load(Ordering::Acquire));
+
+        Some(ParamF32Handle {
+            min: self.min,
+            max: self.max,
+            gradient: self.gradient,
+            unit: self.unit,
+            default_value: self.default_value,
+            smooth_secs: self.smooth_secs,
+
+            shared_normalized: self.shared_normalized.upgrade()?,
+            shared_changes,
+            shared_trim: self.shared_trim.upgrade()?,
+            shared_normalized_limit: self.shared_normalized_limit.upgrade()?,
+            param_alive: Weak::clone(&self.param_alive),
+            seen_changes,
+            last_controller_normalized: AtomicF32::new(f32::NAN),
+        })
+    }
+}
+
+impl Clone for WeakParamF32Handle {
+    fn clone(&self) -> Self {
+        Self {
+            min: self.min,
+            max: self.max,
+            gradient: self.gradient,
+            unit: self.unit,
+            default_value: self.default_value,
+            smooth_secs: self.smooth_secs,
+
+            shared_normalized: Weak::clone(&self.shared_normalized),
+            shared_changes: Weak::clone(&self.shared_changes),
+            shared_trim: Weak::clone(&self.shared_trim),
+            shared_normalized_limit: Weak::clone(&self.shared_normalized_limit),
+            param_alive: Weak::clone(&self.param_alive),
+        }
+    }
+}
+
+pub fn normalized_to_value_f32(normalized: f32, min: f32, max: f32, gradient: Gradient) -> f32 {
+    normalized_to_value_unclamped_f32(normalized.clamp(0.0, 1.0), min, max, gradient)
+}
+
+/// Same as `normalized_to_value_f32()`, except that normalized values greater than `1.0` are
+/// extrapolated past `max` (used for parameters that allow overshoot).
+fn normalized_to_value_unclamped_f32(
+    normalized: f32,
+    min: f32,
+    max: f32,
+    gradient: Gradient,
+) -> f32 {
+    let normalized = normalized.max(0.0);
+
+    let map = |x: f32| -> f32 {
+        let range = max - min;
+        (x * range) + min
+    };
+
+    match gradient {
+        Gradient::Linear => map(normalized),
+
+        Gradient::Power(exponent) => map(normalized.powf(exponent)),
+
+        Gradient::SCurve(steepness) => {
+            if normalized > 1.0 {
+                // Overshoot continues past the end of the curve linearly (the curve itself is
+                // undefined past `1.0` for a positive steepness).
+                return map(normalized);
+            }
+
+            map(s_curve(f64::from(normalized), f64::from(steepness)) as f32)
+        }
+
+        Gradient::Bezier { x1, y1, x2, y2 } => {
+            if normalized > 1.0 {
+                // Overshoot continues past the end of the curve linearly.
+                return map(normalized);
+            }
+
+            map(bezier(f64::from(normalized), x1, y1, x2, y2) as f32)
+        }
+
+        Gradient::Exponential => {
+            if normalized == 0.0 {
+                return min;
+            }
+
+            if normalized == 1.0 {
+                return max;
+            }
+
+            let minl = min.max(EXPONENTIAL_MIN_F32).log2();
+            let range = max.max(EXPONENTIAL_MIN_F32).log2() - minl;
+            2.0f32.powf((normalized * range) + minl)
+        }
+    }
+}
+
+pub fn value_to_normalized_f32(value: f32, min: f32, max: f32, gradient: Gradient) -> f32 {
+    // The range may be inverted (`min > max`), in which case `min` still maps to `0.0`.
+    let (lower, upper, lower_normalized) = if min <= max {
+        (min, max, 0.0)
+    } else {
+        (max, min, 1.0)
+    };
+
+    if value <= lower {
+        return lower_normalized;
+    }
+
+    if value >= upper {
+        return 1.0 - lower_normalized;
+    }
+
+    let unmap = |x: f32| -> f32 {
+        let range = max - min;
+        (x - min) / range
+    };
+
+    match gradient {
+        Gradient::Linear => unmap(value),
+
+        Gradient::Power(exponent) => unmap(value).powf(1.0 / exponent),
+
+        Gradient::SCurve(steepness) => {
+            inverse_s_curve(f64::from(unmap(value)), f64::from(steepness)) as f32
+        }
+
+        Gradient::Bezier { x1, y1, x2, y2 } => {
+            inverse_bezier(f64::from(unmap(value)), x1, y1, x2, y2) as f32
+        }
+
+        Gradient::Exponential => {
+            let min = min.max(EXPONENTIAL_MIN_F32);
+            let max = max.max(EXPONENTIAL_MIN_F32);
+            let value = value.max(EXPONENTIAL_MIN_F32);
+            if (value - min) * (max - min) <= 0.0 {
+                return 0.0;
+            }
+
+            let minl = min.log2();
+            let range = max.log2() - minl;
+            (value.log2() - minl) / range
+        }
+    }
+}
+
+/// A sigmoid through `(0, 0)`, `(0.5, 0.5)`, and `(1, 1)` that is steep in the center.
+fn steep_s_curve(x: f64, k: f64) -> f64 {
+    0.5 + ((k * (x - 0.5)).tanh() / (2.0 * (0.5 * k).tanh()))
+}
+
+/// The inverse of `steep_s_curve()`, which is flat in the center.
+fn flat_s_curve(x: f64, k: f64) -> f64 {
+    0.5 + ((2.0 * (0.5 * k).tanh() * (x - 0.5)).atanh() / k)
+}
+
+/// The mapping used by `Gradient::SCurve` from the normalized range `[0.0, 1.0]` to the
+/// (unscaled) range `[0.0, 1.0]`.
+fn s_curve(x: f64, steepness: f64) -> f64 {
+    if steepness.abs() < 1.0e-6 {
+        x
+    } else if steepness > 0.0 {
+        flat_s_curve(x, steepness)
+    } else {
+        steep_s_curve(x, -steepness)
+    }
+}
+
+/// The inverse of `s_curve()`.
+fn inverse_s_curve(x: f64, steepness: f64) -> f64 {
+    if steepness.abs() < 1.0e-6 {
+        x
+    } else if steepness > 0.0 {
+        steep_s_curve(x, steepness)
+    } else {
+        flat_s_curve(x, -steepness)
+    }
+}
+
+/// The derivative of `s_curve()`.
+fn s_curve_slope(x: f64, steepness: f64) -> f64 {
+    if steepness.abs() < 1.0e-6 {
+        1.0
+    } else if steepness > 0.0 {
+        let t = 2.0 * (0.5 * steepness).tanh();
+        let u = t * (x - 0.5);
+        t / (steepness * (1.0 - (u * u)))
+    } else {
+        let k = -steepness;
+        let th = (k * (x - 0.5)).tanh();
+        k * (1.0 - (th * th)) / (2.0 * (0.5 * k).tanh())
+    }
+}
+
+/// One coordinate of the cubic bezier curve through `0.0` and `1.0` with the control point
+/// coordinates `p1` and `p2`, at the curve parameter `t` in the range `[0.0, 1.0]`.
+fn bezier_coord(t: f64, p1: f64, p2: f64) -> f64 {
+    let u = 1.0 - t;
+    (3.0 * u * u * t * p1) + (3.0 * u * t * t * p2) + (t * t * t)
+}
+
+/// The derivative of `bezier_coord()` with respect to `t`.
+fn bezier_coord_slope(t: f64, p1: f64, p2: f64) -> f64 {
+    let u = 1.0 - t;
+    (3.0 * u * u * p1) + (6.0 * u * t * (p2 - p1)) + (3.0 * t * t * (1.0 - p2))
+}
+
+/// Find the curve parameter `t` where `bezier_coord(t, p1, p2) == target` using bisection,
+/// assuming the coordinate is monotonic.
+fn bezier_solve(target: f64, p1: f64, p2: f64) -> f64 {
+    let mut low = 0.0;
+    let mut high = 1.0;
+
+    // Each iteration halves the interval, so this gets close to the limit of `f64` precision.
+    for _ in 0..52 {
+        let mid = 0.5 * (low + high);
+        if bezier_coord(mid, p1, p2) < target {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    0.5 * (low + high)
+}
+
+/// The mapping used by `Gradient::Bezier` from the normalized range `[0.0, 1.0]` to the
+/// (unscaled) range `[0.0, 1.0]`.
+fn bezier(x: f64, x1: f32, y1: f32, x2: f32, y2: f32) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let t = bezier_solve(
+        x,
+        f64::from(x1.clamp(0.0, 1.0)),
+        f64::from(x2.clamp(0.0, 1.0)),
+    );
+    bezier_coord(t, f64::from(y1), f64::from(y2))
+}
+
+/// The inverse of `bezier()`.
+fn inverse_bezier(y: f64, x1: f32, y1: f32, x2: f32, y2: f32) -> f64 {
+    if y <= 0.0 {
+        return 0.0;
+    }
+    if y >= 1.0 {
+        return 1.0;
+    }
+
+    let t = bezier_solve(y, f64::from(y1), f64::from(y2));
+    bezier_coord(
+        t,
+        f64::from(x1.clamp(0.0, 1.0)),
+        f64::from(x2.clamp(0.0, 1.0)),
+    )
+}
+
+/// The derivative of `bezier()`.
+fn bezier_slope(x: f64, x1: f32, y1: f32, x2: f32, y2: f32) -> f64 {
+    let x1 = f64::from(x1.clamp(0.0, 1.0));
+    let x2 = f64::from(x2.clamp(0.0, 1.0));
+
+    let t = bezier_solve(x.clamp(0.0, 1.0), x1, x2);
+    let dx = bezier_coord_slope(t, x1, x2);
+    let dy = bezier_coord_slope(t, f64::from(y1), f64::from(y2));
+
+    // The curve is vertical if a control point lies directly above or below an end point.
+    dy / dx.max(1.0e-9)
+}
+
+// ------  F64  -------------------------------------------------------------------------
+
+/// An auto-smoothed parameter with an `f64` value.
+pub struct ParamF64 {
+    min: f64,
+    max: f64,
+    gradient: Gradient,
+    unit: Unit,
+
+    shared_normalized: Arc<AtomicF64>,
+    normalized: f64,
+
+    value: f64,
+    default_value: f64,
+
+    smoothed: SmoothF64,
+    smooth_secs: SecondsF64,
+}
+
+impl ParamF64 {
+    /// Create a Parameter/Handle pair from its (de-normalized) value.
+    ///
+    /// * value - The initial (de-normalized) value of the parameter.
+    /// * default_value - The default (de-normalized) value of the parameter.
+    /// * min - The minimum (de-normalized) value of the parameter (at a normalized value of
+    ///   `0.0`). This may be greater than `max` for an inverted range.
+    /// * max - The maximum (de-normalized) value of the parameter.
+    /// * gradient - The [`Gradient`] mapping used when converting from the normalized value
+    ///   in the range `[0.0, 1.0]` to the desired value. If this parameter deals with decibels,
+    ///   you may use `ParamF64::DEFAULT_SMOOTH_SECS` as a good default.
+    /// * unit - The [`Unit`] that signifies how the value displayed to the end user should
+    ///   differ from the actual value used in DSP.
+    /// * smooth_secs: The period of the low-pass parameter smoothing filter (for declicking). You
+    ///   may use `ParamF64::DEFAULT_SMOOTH_SECS` as a good default.
+    /// * sample_rate: The sample rate of this process. This is used for the low-pass parameter
+    ///   smoothing filter.
+    ///
+    /// [`Gradient`]: enum.Gradient.html
+    /// [`Unit`]: enum.Unit.html
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_value(
+        value: f64,
+        default_value: f64,
+        min: f64,
+        max: f64,
+        gradient: Gradient,
+        unit: Unit,
+        smooth_secs: SecondsF64,
+        sample_rate: SampleRate,
+        max_blocksize: usize,
+    ) -> (Self, ParamF64Handle) {
+        let normalized = value_to_normalized_f64(value, min, max, gradient);
+
+        let handle_value = normalized_to_value_f64(normalized, min, max, gradient);
+        let rt_value = unit.unit_to_dsp_f64(handle_value);
+
+        let shared_normalized = Arc::new(AtomicF64::new(normalized));
+
+        let mut smoothed = SmoothF64::new(rt_value, max_blocksize);
+        smoothed.set_speed(sample_rate, smooth_secs);
+
+        (
+            Self {
+                min,
+                max,
+                gradient,
+                unit,
+                shared_normalized: Arc::clone(&shared_normalized),
+                normalized,
+                value: rt_value,
+                default_value,
+                smoothed,
+                smooth_secs,
+            },
+            ParamF64Handle {
+                min,
+                max,
+                gradient,
+                unit,
+                default_value,
+                shared_normalized,
+            },
+        )
+    }
+
+    /// Create a Parameter/Handle pair from its normalized value in the range `[0.0, 1.0]`.
+    ///
+    /// * normalized - The initial normalized value of the parameter in the range `[0.0, 1.0]`.
+    /// * default_value - The default (de-normalized) value of the parameter.
+    /// * min - The minimum (de-normalized) value of the parameter (at a normalized value of
+    ///   `0.0`). This may be greater than `max` for an inverted range.
+    /// * max - The maximum (de-normalized) value of the parameter.
+    /// * gradient - The [`Gradient`] mapping used when converting from the normalized value
+    ///   in the range `[0.0, 1.0]` to the desired value. If this parameter deals with decibels,
+    ///   you may use `ParamF64::DEFAULT_SMOOTH_SECS` as a good default.
+    /// * unit - The [`Unit`] that signifies how the value displayed to the end user should
+    ///   differ from the actual value used in DSP.
+    /// * smooth_secs: The period of the low-pass parameter smoothing filter (for declicking). You
+    ///   may use `ParamF64::DEFAULT_SMOOTH_SECS` as a good default.
+    /// * sample_rate: The sample rate of this process. This is used for the low-pass parameter
+    ///   smoothing filter.
+    ///
+    /// [`Gradient`]: enum.Gradient.html
+    /// [`Unit`]: enum.Unit.html
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_normalized(
+        normalized: f64,
+        default_value: f64,
+        min_value: f64,
+        max_value: f64,
+        gradient: Gradient,
+        unit: Unit,
+        smooth_secs: SecondsF64,
+        sample_rate: SampleRate,
+        max_blocksize: usize,
+    ) -> (Self, ParamF64Handle) {
+        let normalized = normalized.clamp(0.0, 1.0);
+
+        let shared_normalized = Arc::new(AtomicF64::new(normalized));
+
+        let handle_value = normalized_to_value_f64(normalized, min_value, max_value, gradient);
+        let rt_value = unit.unit_to_dsp_f64(handle_value);
+
+        let mut smoothed = SmoothF64::new(rt_value, max_blocksize);
+        smoothed.set_speed(sample_rate, smooth_secs);
+
+        (
+            Self {
+                min: min_value,
+                max: max_value,
+                gradient,
+                unit,
+                shared_normalized: Arc::clone(&shared_normalized),
+                normalized,
+                value: rt_value,
+                default_value,
+                smoothed,
+                smooth_secs,
+            },
+            ParamF64Handle {
+                min: min_value,
+                max: max_value,
+                gradient,
+                unit,
+                default_value,
+                shared_normalized,
+            },
+        )
+    }
+
+    /// Create a Parameter/Handle pair with the same configuration (min, max, gradient, unit,
+    /// and smoothing period) as the given [`ParamF32`], initialized at its current value.
+    ///
+    /// * param - The parameter to copy the configuration from.
+    /// * sample_rate: The sample rate of this process. This is used for the low-pass parameter
+    ///   smoothing filter.
+    ///
+    /// [`ParamF32`]: struct.ParamF32.html
+    pub fn from_f32_config(param: &ParamF32, sample_rate: SampleRate) -> (Self, ParamF64Handle) {
+        Self::from_normalized(
+            f64::from(param.normalized),
+            f64::from(param.default_value),
+            f64::from(param.min),
+            f64::from(param.max),
+            param.gradient,
+            param.unit,
+            param.smooth_secs,
+            sample_rate,
+            param.smoothed.max_blocksize(),
+        )
+    }
+
+    /// Set the (de-normalized) value of this parameter.
+    pub fn set_value(&mut self, value: f64) {
+        if self.value != value {
+            self.normalized = value_to_normalized_f64(value, self.min, self.max, self.gradient);
+            self.shared_normalized.set(self.normalized);
+
+            let v = normalized_to_value_f64(self.normalized, self.min, self.max, self.gradient);
+            self.value = self.unit.unit_to_dsp_f64(v);
+
+            self.smoothed.set(self.value);
+        }
+    }
+
+    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
+    pub fn set_normalized(&mut self, normalized: f64) {
+        if self.normalized != normalized {
+            self.normalized = normalized.clamp(0.0, 1.0);
+            self.shared_normalized.set(self.normalized);
+
+            let v = normalized_to_value_f64(self.normalized, self.min, self.max, self.gradient);
+            self.value = self.unit.unit_to_dsp_f64(v);
+
+            self.smoothed.set(self.value);
+        }
+    }
+
+    /// Reset this parameter (without any smoothing) to the given (de-normalized) value.
+    pub fn reset_from_value(&mut self, value: f64) {
+        self.normalized = value_to_normalized_f64(value, self.min, self.max, self.gradient);
+        self.shared_normalized.set(self.normalized);
+
+        let v = normalized_to_value_f64(self.normalized, self.min, self.max, self.gradient);
+        self.value = self.unit.unit_to_dsp_f64(v);
+
+        self.smoothed.reset(self.value);
+    }
+
+    /// Reset this parameter (without any smoothing) to the given normalized value in the range `[0.0, 1.0]`.
+    pub fn reset_from_normalized(&mut self, normalized: f64) {
+        self.normalized = normalized.clamp(0.0, 1.0);
+        self.shared_normalized.set(self.normalized);
+
+        let v = normalized_to_value_f64(self.normalized, self.min, self.max, self.gradient);
+        self.value = self.unit.unit_to_dsp_f64(v);
+
+        self.smoothed.reset(self.value);
+    }
+
+    /// Reset the internal smoothing buffer.
+    pub fn reset(&mut self) {
+        self.smoothed.reset(self.value);
+    }
+
+    /// Returns `true` if the smoothing filter has not yet settled to its target value (the
+    /// same as `SmoothOutputF64::is_smoothing()`).
+    ///
+    /// If this returns `false`, then every value in the buffer returned by
+    /// `ParamF64::smoothed()` is the same, so per-frame reads can be skipped.
+    ///
+    /// Note that this only reflects the state after the most recent call to
+    /// `ParamF64::smoothed()`. A new value set from the handle is only picked up on the next
+    /// call.
+    pub fn is_smoothing(&self) -> bool {
+        self.smoothed.is_active()
+    }
+
+    /// If the smoothing filter has reached its target value, reset it to that value so that
+    /// `ParamF64::is_smoothing()` returns `false` right away (instead of after the next
+    /// block). Returns `true` if the filter is settled afterwards.
+    ///
+    /// Note that this only reflects the state after the most recent call to
+    /// `ParamF64::smoothed()`.
+    pub fn reset_if_settled(&mut self) -> bool {
+        self.smoothed.reset_if_settled()
+    }
+
+    /// Get the smoothed buffer of values for use in DSP.
+    pub fn smoothed(&mut self, frames: usize) -> SmoothOutputF64<'_> {
+        let new_normalized = self.shared_normalized.get();
+        if self.normalized != new_normalized {
+            self.normalized = new_normalized;
+
+            let v = normalized_to_value_f64(self.normalized, self.min, self.max, self.gradient);
+            self.value = self.unit.unit_to_dsp_f64(v);
+
+            self.smoothed.set(self.value);
+        }
+
+        self.smoothed.process(frames);
+        self.smoothed.update_status();
+
+        self.smoothed.output()
+    }
+
+    /// Update the sample rate (used for the parameter smoothing LPF).
+    pub fn set_sample_rate(&mut self, sample_rate: SampleRate) {
+        self.smoothed.set_speed(sample_rate, self.smooth_secs);
+    }
+
+    /// Change the period of the low-pass parameter smoothing filter (i.e. to momentarily
+    /// lengthen it during a big preset change).
+    ///
+    /// This can be called while the filter is smoothing. The current output is kept as-is,
+    /// and only the speed at which it moves towards the target from there changes. With
+    /// `SmoothMode::Linear`, a ramp in progress is restarted from the current output so that
+    /// it reaches the target in the new `smooth_secs`.
+    ///
+    /// Note that this does not change the `smooth_secs` reported by an existing
+    /// [`ParamF64Handle`].
+    ///
+    /// [`ParamF64Handle`]: struct.ParamF64Handle.html
+    pub fn set_smooth_secs(&mut self, smooth_secs: SecondsF64, sample_rate: SampleRate) {
+        self.smooth_secs = smooth_secs;
+        self.smoothed.set_speed(sample_rate, smooth_secs);
+
+        if self.smoothed.mode() == SmoothMode::Linear && self.smoothed.is_active() {
+            let target = self.smoothed.dest();
+            self.smoothed.set(target);
+        }
+    }
+
+    /// Set how the smoothing filter moves towards a new value.
+    ///
+    /// The default is `SmoothMode::Exponential`, which is best suited for declicking. Use
+    /// `SmoothMode::Linear` for fades that reach the target in exactly `smooth_secs`. Any
+    /// other mode behaves like `SmoothMode::Exponential`.
+    ///
+    /// This is meant to be called right after constructing the parameter.
+    pub fn set_smooth_mode(&mut self, mode: SmoothMode) {
+        self.smoothed.set_mode(mode);
+    }
+
+    /// The minimum value of this parameter.
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// The maximum value of this parameter.
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// The [`Gradient`] mapping used when converting from the normalized value
+    /// in the range `[0.0, 1.0]` to the desired value.
+    ///
+    /// [`Gradient`]: enum.Gradient.html
+    pub fn gradient(&self) -> Gradient {
+        self.gradient
+    }
+
+    /// The [`Unit`] that signifies how the value displayed to the end user should
+    /// differ from the actual value used in DSP.
+    ///
+    /// [`Unit`]: enum.Unit.html
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
+    /// of this parameter.
+    pub fn value_to_normalized(&self, value: f64) -> f64 {
+        value_to_normalized_f64(value, self.min, self.max, self.gradient)
+    }
+
+    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
+    /// corresponding value of this parameter.
+    pub fn normalized_to_value(&self, normalized: f64) -> f64 {
+        normalized_to_value_f64(normalized, self.min, self.max, self.gradient)
+    }
+
+    /// The current normalized value in the range `[0.0, 1.0]`. This is only meant for
+    /// communicating with the host. This is not meant to be used to retrieve the latest
+    /// value for DSP. To get the latest value for DSP please use `ParamF32::smoothed()`
+    /// instead.
+    ///
+    /// Please note that this should be called *after* calling `ParamF32::smoothed()`
+    /// if you need the latest value from the corresponding [`ParamF32Handle`],
+    /// otherwise this may not return the latest value.
+    ///
+    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
+    pub fn host_get_normalized(&self) -> f64 {
+        self.normalized
+    }
+
+    /// The (un-normalized) default value of the parameter.
+    pub fn default_value(&self) -> f64 {
+        self.default_value
+    }
+
+    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
+    pub fn default_normalized(&self) -> f64 {
+        self.value_to_normalized(self.default_value)
+    }
+
+    /// The current (un-normalized) value of the parameter. This is only meant for
+    /// communicating with the host. This is not meant to be used to retrieve the latest
+    /// value for DSP. To get the latest value for DSP please use `ParamF64::smoothed()`
+    /// instead.
+    ///
+    /// Please note that this should be called *after* calling `ParamF64::smoothed()`
+    /// if you need the latest value from the corresponding [`ParamF64Handle`],
+    /// otherwise this may not return the latest value.
+    ///
+    /// [`ParamF64Handle`]: struct.ParamF64Handle.html
+    pub fn host_get_value(&self) -> f64 {
+        self.value
+    }
+
+    /// Get the shared normalized float value.
+    ///
+    /// This can be useful to integrate with various plugin APIs.
+    pub fn shared_normalized(&self) -> Arc<AtomicF64> {
+        Arc::clone(&self.shared_normalized)
+    }
+}
+
+/// A handle to get and update the value of an auto-smoothed [`ParamF64`] from a UI.
+///
+/// [`ParamF64`]: struct.ParamF64.html
+pub struct ParamF64Handle {
+    min: f64,
+    max: f64,
+    gradient: Gradient,
+    unit: Unit,
+    default_value: f64,
+
+    shared_normalized: Arc<AtomicF64>,
+}
+
+impl ParamF64Handle {
+    /// The normalized value in the range `[0.0, 1.0]`.
+    pub fn normalized(&self) -> f64 {
+        self.shared_normalized.get()
+    }
+
+    /// The (un-normalized) value of this parameter.
+    ///
+    /// Please note that this is calculated from the shared normalized value every time, so
+    /// avoid calling this every frame if you can.
+    pub fn value(&self) -> f64 {
+        normalized_to_value_f64(
+            self.shared_normalized.get(),
+            self.min,
+            self.max,
+            self.gradient,
+        )
+    }
+
+    /// The (un-normalized) default value of the parameter.
+    pub fn default_value(&self) -> f64 {
+        self.default_value
+    }
+
+    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
+    pub fn default_normalized(&self) -> f64 {
+        self.value_to_normalized(self.default_value)
+    }
+
+    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
+    ///
+    /// Please note that this will ***NOT*** automatically notify the host of the value change
+    /// if you are using this inside a plugin spec such as VST. It is intended for you use your
+    /// own method for achieving this.
+    pub fn set_normalized(&self, normalized: f64) {
+        self.shared_normalized.set(normalized.clamp(0.0, 1.0));
+    }
+
+    /// Set the (un-normalized) value of this parameter.
+    ///
+    /// Please note that this will ***NOT*** automatically notify the host of the value change
+    /// if you are using this inside a plugin spec such as VST. It is intended for you use your
+    /// own method for achieving this.
+    pub fn set_value(&self, value: f64) {
+        let normalized = value_to_normalized_f64(value, self.min, self.max, self.gradient);
+        self.set_normalized(normalized);
+    }
+
+    /// The minimum value of this parameter.
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// The maximum value of this parameter.
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// The [`Gradient`] mapping used when converting from the normalized value
+    /// in the range `[0.0, 1.0]` to the desired value.
+    ///
+    /// [`Gradient`]: enum.Gradient.html
+    pub fn gradient(&self) -> Gradient {
+        self.gradient
+    }
+
+    /// The [`Unit`] that signifies how the value displayed to the end user should
+    /// differ from the actual value used in DSP.
     ///
-    /// [`Gradient`]: enum.Gradient.html
     /// [`Unit`]: enum.Unit.html
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
+    /// of this parameter.
+    pub fn value_to_normalized(&self, value: f64) -> f64 {
+        value_to_normalized_f64(value, self.min, self.max, self.gradient)
+    }
+
+    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
+    /// corresponding value of this parameter.
+    pub fn normalized_to_value(&self, normalized: f64) -> f64 {
+        normalized_to_value_f64(normalized, self.min, self.max, self.gradient)
+    }
+
+    /// Get the shared normalized float value.
+    ///
+    /// This can be useful to integrate with various plugin APIs.
+    pub fn shared_normalized(&self) -> Arc<AtomicF64> {
+        Arc::clone(&self.shared_normalized)
+    }
+}
+
+impl Clone for ParamF64Handle {
+    fn clone(&self) -> Self {
+        Self {
+            min: self.min,
+            max: self.max,
+            gradient: self.gradient,
+            unit: self.unit,
+            default_value: self.default_value,
+
+            shared_normalized: Arc::clone(&self.shared_normalized),
+        }
+    }
+}
+
+pub fn normalized_to_value_f64(normalized: f64, min: f64, max: f64, gradient: Gradient) -> f64 {
+    let normalized = normalized.clamp(0.0, 1.0);
+
+    let map = |x: f64| -> f64 {
+        let range = max - min;
+        (x * range) + min
+    };
+
+    match gradient {
+        Gradient::Linear => map(normalized),
+
+        Gradient::Power(exponent) => map(normalized.powf(f64::from(exponent))),
+
+        Gradient::SCurve(steepness) => map(s_curve(normalized, f64::from(steepness))),
+
+        Gradient::Bezier { x1, y1, x2, y2 } => map(bezier(normalized, x1, y1, x2, y2)),
+
+        Gradient::Exponential => {
+            if normalized == 0.0 {
+                return min;
+            }
+
+            if normalized == 1.0 {
+                return max;
+            }
+
+            let minl = min.max(EXPONENTIAL_MIN_F64).log2();
+            let range = max.max(EXPONENTIAL_MIN_F64).log2() - minl;
+            2.0f64.powf((normalized * range) + minl)
+        }
+    }
+}
+
+pub fn value_to_normalized_f64(value: f64, min: f64, max: f64, gradient: Gradient) -> f64 {
+    // The range may be inverted (`min > max`), in which case `min` still maps to `0.0`.
+    let (lower, upper, lower_normalized) = if min <= max {
+        (min, max, 0.0)
+    } else {
+        (max, min, 1.0)
+    };
+
+    if value <= lower {
+        return lower_normalized;
+    }
+
+    if value >= upper {
+        return 1.0 - lower_normalized;
+    }
+
+    let unmap = |x: f64| -> f64 {
+        let range = max - min;
+        (x - min) / range
+    };
+
+    match gradient {
+        Gradient::Linear => unmap(value),
+
+        Gradient::Power(exponent) => unmap(value).powf(1.0 / f64::from(exponent)),
+
+        Gradient::SCurve(steepness) => inverse_s_curve(unmap(value), f64::from(steepness)),
+
+        Gradient::Bezier { x1, y1, x2, y2 } => inverse_bezier(unmap(value), x1, y1, x2, y2),
+
+        Gradient::Exponential => {
+            let min = min.max(EXPONENTIAL_MIN_F64);
+            let max = max.max(EXPONENTIAL_MIN_F64);
+            let value = value.max(EXPONENTIAL_MIN_F64);
+            if (value - min) * (max - min) <= 0.0 {
+                return 0.0;
+            }
+
+            let minl = min.log2();
+            let range = max.log2() - minl;
+            (value.log2() - minl) / range
+        }
+    }
+}
+
+/// A parameter with an `i32` value.
+pub struct ParamI32 {
+    min: i32,
+    max: i32,
+    gradient: Gradient,
+    default_value: i32,
+
+    shared: Arc<AtomicI32>,
+}
+
+impl ParamI32 {
+    /// Create a Parameter/Handle pair from its (de-normalized) value.
+    ///
+    /// * value - The initial (de-normalized) value of the parameter.
+    /// * default_value - The (de-normalized) default value of the parameter.
+    /// * min - The minimum (de-normalized) value of the parameter.
+    /// * max - The maximum (de-normalized) value of the parameter.
+    /// * gradient - The [`Gradient`] mapping used when converting from the normalized value
+    ///   in the range `[0.0, 1.0]` to the desired value. The gradient is applied before
+    ///   rounding to the nearest integer.
+    ///
+    /// [`Gradient`]: enum.Gradient.html
     pub fn from_value(
-        value: f64,
-        default_value: f64,
-        min: f64,
-        max: f64,
+        value: i32,
+        default_value: i32,
+        min: i32,
+        max: i32,
         gradient: Gradient,
-        unit: Unit,
-        smooth_secs: SecondsF64,
-        sample_rate: SampleRate,
-        max_blocksize: usize,
-    ) -> (Self, ParamF64Handle) {
-        let normalized = value_to_normalized_f64(value, min, max, gradient);
+    ) -> (Self, ParamI32Handle) {
+        let value = value.clamp(min, max);
 
-        let handle_value = normalized_to_value_f64(normalized, min, max, gradient);
-        let rt_value = match unit {
-            Unit::Decibels => db_to_coeff_clamped_neg_90_db_f64(handle_value),
-            _ => handle_value,
-        };
+        let shared = Arc::new(AtomicI32::new(value));
 
-        let shared_normalized = Arc::new(AtomicF64::new(normalized));
+        (
+            Self {
+                min,
+                max,
+                gradient,
+                default_value,
+                shared: Arc::clone(&shared),
+            },
+            ParamI32Handle {
+                min,
+                max,
+                gradient,
+                default_value,
+                shared,
+            },
+        )
+    }
 
-        let mut smoothed = SmoothF64::new(rt_value, max_blocksize);
-        smoothed.set_speed(sample_rate, smooth_secs);
+    /// Create a Parameter/Handle pair from its normalized value in the range `[0.0, 1.0]`.
+    ///
+    /// * normalized - The initial normalized value of the parameter in the range `[0.0, 1.0]`.
+    /// * default_value - The (de-normalized) default value of the parameter.
+    /// * min - The minimum (de-normalized) value of the parameter.
+    /// * max - The maximum (de-normalized) value of the parameter.
+    /// * gradient - The [`Gradient`] mapping used when converting from the normalized value
+    ///   in the range `[0.0, 1.0]` to the desired value. The gradient is applied before
+    ///   rounding to the nearest integer.
+    ///
+    /// [`Gradient`]: enum.Gradient.html
+    pub fn from_normalized(
+        normalized: f32,
+        default_value: i32,
+        min_value: i32,
+        max_value: i32,
+        gradient: Gradient,
+    ) -> (Self, ParamI32Handle) {
+        let value = normalized_to_value_i32(normalized, min_value, max_value, gradient);
+
+        Self::from_value(value, default_value, min_value, max_value, gradient)
+    }
+
+    /// Set the (de-normalized) value of this parameter.
+    pub fn set_value(&mut self, value: i32) {
+        self.shared
+            .store(value.clamp(self.min, self.max), Ordering::Relaxed);
+    }
+
+    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
+    pub fn set_normalized(&mut self, normalized: f32) {
+        self.set_value(self.normalized_to_value(normalized));
+    }
+
+    /// The (un-normalized) value of this parameter.
+    pub fn value(&self) -> i32 {
+        self.shared.load(Ordering::Relaxed)
+    }
+
+    /// The (un-normalized) default value of the parameter.
+    pub fn default_value(&self) -> i32 {
+        self.default_value
+    }
+
+    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
+    pub fn default_normalized(&self) -> f32 {
+        self.value_to_normalized(self.default_value)
+    }
+
+    /// The minimum value of this parameter.
+    pub fn min(&self) -> i32 {
+        self.min
+    }
+
+    /// The maximum value of this parameter.
+    pub fn max(&self) -> i32 {
+        self.max
+    }
+
+    /// The [`Gradient`] mapping used when converting from the normalized value
+    /// in the range `[0.0, 1.0]` to the desired value.
+    ///
+    /// [`Gradient`]: enum.Gradient.html
+    pub fn gradient(&self) -> Gradient {
+        self.gradient
+    }
+
+    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
+    /// of this parameter.
+    pub fn value_to_normalized(&self, value: i32) -> f32 {
+        value_to_normalized_i32(value, self.min, self.max, self.gradient)
+    }
+
+    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
+    /// corresponding value of this parameter.
+    pub fn normalized_to_value(&self, normalized: f32) -> i32 {
+        normalized_to_value_i32(normalized, self.min, self.max, self.gradient)
+    }
+}
+
+/// A handle to get and update the value of a [`ParamI32`] from a UI.
+///
+/// [`ParamI32`]: struct.ParamI32.html
+pub struct ParamI32Handle {
+    min: i32,
+    max: i32,
+    gradient: Gradient,
+    default_value: i32,
+
+    shared: Arc<AtomicI32>,
+}
+
+impl ParamI32Handle {
+    /// The (un-normalized) value of this parameter.
+    pub fn value(&self) -> i32 {
+        self.shared.load(Ordering::Relaxed)
+    }
+
+    /// The (un-normalized) default value of the parameter.
+    pub fn default_value(&self) -> i32 {
+        self.default_value
+    }
+
+    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
+    pub fn default_normalized(&self) -> f32 {
+        self.value_to_normalized(self.default_value)
+    }
+
+    /// Set the (de-normalized) value of this parameter.
+    pub fn set_value(&mut self, value: i32) {
+        self.shared
+            .store(value.clamp(self.min, self.max), Ordering::Relaxed);
+    }
+
+    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
+    pub fn set_normalized(&mut self, normalized: f32) {
+        self.set_value(self.normalized_to_value(normalized));
+    }
+
+    /// The minimum value of this parameter.
+    pub fn min(&self) -> i32 {
+        self.min
+    }
+
+    /// The maximum value of this parameter.
+    pub fn max(&self) -> i32 {
+        self.max
+    }
+
+    /// The [`Gradient`] mapping used when converting from the normalized value
+    /// in the range `[0.0, 1.0]` to the desired value.
+    ///
+    /// [`Gradient`]: enum.Gradient.html
+    pub fn gradient(&self) -> Gradient {
+        self.gradient
+    }
+
+    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
+    /// of this parameter.
+    pub fn value_to_normalized(&self, value: i32) -> f32 {
+        value_to_normalized_i32(value, self.min, self.max, self.gradient)
+    }
+
+    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
+    /// corresponding value of this parameter.
+    pub fn normalized_to_value(&self, normalized: f32) -> i32 {
+        normalized_to_value_i32(normalized, self.min, self.max, self.gradient)
+    }
+}
+
+impl Clone for ParamI32Handle {
+    fn clone(&self) -> Self {
+        Self {
+            min: self.min,
+            max: self.max,
+            gradient: self.gradient,
+            default_value: self.default_value,
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+/// Map the normalized value in the range `[0.0, 1.0]` to an integer value in the range
+/// `[min, max]`.
+///
+/// The (gradient-mapped) normalized range is split into `max - min + 1` buckets of equal width,
+/// one per integer value, and the value is the bucket that `normalized` falls into. For a
+/// linear gradient this means every integer value occupies an equal-width band of the
+/// normalized range.
+pub fn normalized_to_value_i32(normalized: f32, min: i32, max: i32, gradient: Gradient) -> i32 {
+    if max <= min {
+        return min;
+    }
+
+    let (minf, maxf) = (min as f32, max as f32);
+    let mapped = (normalized_to_value_f32(normalized, minf, maxf, gradient) - minf) / (maxf - minf);
+
+    let num_steps = i64::from(max) - i64::from(min) + 1;
+    let step = ((f64::from(mapped) * num_steps as f64).floor() as i64).clamp(0, num_steps - 1);
+
+    (i64::from(min) + step) as i32
+}
+
+/// Map the integer value in the range `[min, max]` to the normalized range `[0.0, 1.0]`.
+///
+/// `min` maps to `0.0` and `max` maps to `1.0`, with the values in between spaced evenly (in the
+/// gradient-mapped normalized range). Each result lies inside the bucket that
+/// `normalized_to_value_i32()` maps back to the same integer value.
+pub fn value_to_normalized_i32(value: i32, min: i32, max: i32, gradient: Gradient) -> f32 {
+    value_to_normalized_f32(value as f32, min as f32, max as f32, gradient)
+}
+
+/// A parameter with an `bool` value.
+pub struct ParamBool {
+    shared: Arc<AtomicBool>,
+    default_value: bool,
+}
+
+impl ParamBool {
+    /// Create a Parameter/Handle pair from its (de-normalized) boolean value.
+    ///
+    /// * value - The initial boolean value of the parameter.
+    /// * default_value - The default boolean value of the parameter.
+    pub fn from_value(value: bool, default_value: bool) -> (Self, ParamBoolHandle) {
+        let shared = Arc::new(AtomicBool::new(value));
 
         (
             Self {
-                min,
-                max,
-                gradient,
-                unit,
-                shared_normalized: Arc::clone(&shared_normalized),
-                normalized,
-                value: rt_value,
+                shared: Arc::clone(&shared),
                 default_value,
-                smoothed,
-                smooth_secs,
             },
-            ParamF64Handle {
-                min,
-                max,
-                gradient,
-                unit,
+            ParamBoolHandle {
+                shared,
                 default_value,
-                shared_normalized,
             },
         )
     }
@@ -704,763 +3153,1855 @@ impl ParamF64 {
     /// Create a Parameter/Handle pair from its normalized value in the range `[0.0, 1.0]`.
     ///
     /// * normalized - The initial normalized value of the parameter in the range `[0.0, 1.0]`.
-    /// * default_value - The default (de-normalized) value of the parameter.
-    /// * min - The minimum (de-normalized) value of the parameter.
-    /// * max - The maximum (de-normalized) value of the parameter.
-    /// * gradient - The [`Gradient`] mapping used when converting from the normalized value
-    /// in the range `[0.0, 1.0]` to the desired value. If this parameter deals with decibels,
-    /// you may use `ParamF64::DEFAULT_SMOOTH_SECS` as a good default.
-    /// * unit - The [`Unit`] that signifies how the value displayed to the end user should
-    /// differ from the actual value used in DSP.
-    /// * smooth_secs: The period of the low-pass parameter smoothing filter (for declicking). You
-    /// may use `ParamF64::DEFAULT_SMOOTH_SECS` as a good default.
-    /// * sample_rate: The sample rate of this process. This is used for the low-pass parameter
-    /// smoothing filter.
-    ///
-    /// [`Gradient`]: enum.Gradient.html
-    /// [`Unit`]: enum.Unit.html
-    pub fn from_normalized(
-        normalized: f64,
-        default_value: f64,
-        min_value: f64,
-        max_value: f64,
-        gradient: Gradient,
-        unit: Unit,
-        smooth_secs: SecondsF64,
-        sample_rate: SampleRate,
-        max_blocksize: usize,
-    ) -> (Self, ParamF64Handle) {
+    /// * default_value - The (un-normalized) default boolean value of the parameter.
+    pub fn from_normalized(normalized: f32, default_value: bool) -> (Self, ParamBoolHandle) {
         let normalized = normalized.clamp(0.0, 1.0);
+        let value = normalized >= 0.5;
 
-        let shared_normalized = Arc::new(AtomicF64::new(normalized));
+        Self::from_value(value, default_value)
+    }
 
-        let handle_value = normalized_to_value_f64(normalized, min_value, max_value, gradient);
-        let rt_value = match unit {
-            Unit::Decibels => db_to_coeff_clamped_neg_90_db_f64(handle_value),
-            _ => handle_value,
-        };
+    /// Set the (de-normalized) boolean value of this parameter.
+    pub fn set_value(&mut self, value: bool) {
+        self.shared.store(value, Ordering::Relaxed);
+    }
 
-        let mut smoothed = SmoothF64::new(rt_value, max_blocksize);
-        smoothed.set_speed(sample_rate, smooth_secs);
+    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
+    pub fn set_normalized(&mut self, normalized: f32) {
+        let normalized = normalized.clamp(0.0, 1.0);
+        let value = normalized >= 0.5;
 
-        (
-            Self {
-                min: min_value,
-                max: max_value,
-                gradient,
-                unit,
-                shared_normalized: Arc::clone(&shared_normalized),
-                normalized,
-                value: rt_value,
-                default_value,
-                smoothed,
-                smooth_secs,
-            },
-            ParamF64Handle {
-                min: min_value,
-                max: max_value,
-                gradient,
-                unit,
-                default_value,
-                shared_normalized,
-            },
-        )
+        self.set_value(value);
     }
 
-    /// Set the (de-normalized) value of this parameter.
-    pub fn set_value(&mut self, value: f64) {
-        if self.value != value {
-            self.normalized = value_to_normalized_f64(value, self.min, self.max, self.gradient);
-            self.shared_normalized.set(self.normalized);
-
-            let v = normalized_to_value_f64(self.normalized, self.min, self.max, self.gradient);
-            self.value = match self.unit {
-                Unit::Decibels => db_to_coeff_clamped_neg_90_db_f64(v),
-                _ => v,
-            };
+    /// The (un-normalized) boolean value of this parameter.
+    pub fn value(&self) -> bool {
+        self.shared.load(Ordering::Relaxed)
+    }
 
-            self.smoothed.set(self.value);
-        }
+    /// The normalized value of this parameter in the range `[0.0, 1.0]`.
+    pub fn normalized(&self) -> f32 {
+        self.value_to_normalized(self.value())
     }
 
-    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
-    pub fn set_normalized(&mut self, normalized: f64) {
-        if self.normalized != normalized {
-            self.normalized = normalized.clamp(0.0, 1.0);
-            self.shared_normalized.set(self.normalized);
+    /// The (un-normalized) default boolean value of the parameter.
+    pub fn default_value(&self) -> bool {
+        self.default_value
+    }
 
-            let v = normalized_to_value_f64(self.normalized, self.min, self.max, self.gradient);
-            self.value = match self.unit {
-                Unit::Decibels => db_to_coeff_clamped_neg_90_db_f64(v),
-                _ => v,
-            };
+    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
+    pub fn default_normalized(&self) -> f32 {
+        self.value_to_normalized(self.default_value)
+    }
 
-            self.smoothed.set(self.value);
+    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
+    /// of this parameter.
+    pub fn value_to_normalized(&self, value: bool) -> f32 {
+        if value {
+            1.0
+        } else {
+            0.0
         }
     }
 
-    /// Reset this parameter (without any smoothing) to the given (de-normalized) value.
-    pub fn reset_from_value(&mut self, value: f64) {
-        self.normalized = value_to_normalized_f64(value, self.min, self.max, self.gradient);
-        self.shared_normalized.set(self.normalized);
+    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
+    /// corresponding boolean value of this parameter.
+    pub fn normalized_to_value(&self, normalized: f32) -> bool {
+        let normalized = normalized.clamp(0.0, 1.0);
+        normalized >= 0.5
+    }
+}
 
-        let v = normalized_to_value_f64(self.normalized, self.min, self.max, self.gradient);
-        self.value = match self.unit {
-            Unit::Decibels => db_to_coeff_clamped_neg_90_db_f64(v),
-            _ => v,
-        };
+/// A handle to get and update the value of a [`ParamBool`] from a UI.
+///
+/// [`ParamBool`]: struct.ParamBool.html
+pub struct ParamBoolHandle {
+    shared: Arc<AtomicBool>,
+    default_value: bool,
+}
 
-        self.smoothed.reset(self.value);
+impl ParamBoolHandle {
+    /// The (un-normalized) boolean value of this parameter.
+    pub fn value(&self) -> bool {
+        self.shared.load(Ordering::Relaxed)
     }
 
-    /// Reset this parameter (without any smoothing) to the given normalized value in the range `[0.0, 1.0]`.
-    pub fn reset_from_normalized(&mut self, normalized: f64) {
-        self.normalized = normalized.clamp(0.0, 1.0);
-        self.shared_normalized.set(self.normalized);
+    /// The (un-normalized) default boolean value of the parameter.
+    pub fn default_value(&self) -> bool {
+        self.default_value
+    }
 
-        let v = normalized_to_value_f64(self.normalized, self.min, self.max, self.gradient);
-        self.value = match self.unit {
-            Unit::Decibels => db_to_coeff_clamped_neg_90_db_f64(v),
-            _ => v,
-        };
+    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
+    pub fn default_normalized(&self) -> f32 {
+        self.value_to_normalized(self.default_value)
+    }
 
-        self.smoothed.reset(self.value);
+    /// The normalized value of this parameter in the range `[0.0, `1.0]`.
+    pub fn normalized(&self) -> f32 {
+        self.value_to_normalized(self.value())
     }
 
-    /// Reset the internal smoothing buffer.
-    pub fn reset(&mut self) {
-        self.smoothed.reset(self.value);
+    /// Set the (de-normalized) boolean value of this parameter.
+    pub fn set_value(&mut self, value: bool) {
+        self.shared.store(value, Ordering::Relaxed);
     }
 
-    /// Get the smoothed buffer of values for use in DSP.
-    pub fn smoothed(&mut self, frames: usize) -> SmoothOutputF64 {
-        let new_normalized = self.shared_normalized.get();
-        if self.normalized != new_normalized {
-            self.normalized = new_normalized;
+    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
+    pub fn set_normalized(&mut self, normalized: f32) {
+        let normalized = normalized.clamp(0.0, 1.0);
+        let value = normalized >= 0.5;
 
-            let v = normalized_to_value_f64(self.normalized, self.min, self.max, self.gradient);
-            self.value = match self.unit {
-                Unit::Decibels => db_to_coeff_clamped_neg_90_db_f64(v),
-                _ => v,
-            };
+        self.set_value(value);
+    }
 
-            self.smoothed.set(self.value);
+    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
+    /// of this parameter.
+    pub fn value_to_normalized(&self, value: bool) -> f32 {
+        if value {
+            1.0
+        } else {
+            0.0
         }
+    }
 
-        self.smoothed.process(frames);
-        self.smoothed.update_status();
-
-        self.smoothed.output()
+    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
+    /// corresponding boolean value of this parameter.
+    pub fn normalized_to_value(&self, normalized: f32) -> bool {
+        let normalized = normalized.clamp(0.0, 1.0);
+        normalized >= 0.5
     }
+}
 
-    /// Update the sample rate (used for the parameter smoothing LPF).
-    pub fn set_sample_rate(&mut self, sample_rate: SampleRate) {
-        self.smoothed.set_speed(sample_rate, self.smooth_secs);
+impl Clone for ParamBoolHandle {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+            default_value: self.default_value,
+        }
     }
+}
 
-    /// The minimum value of this parameter.
-    pub fn min(&self) -> f64 {
-        self.min
+/// Map the index of a discrete choice to the normalized range `[0.0, 1.0]`, with the indices
+/// spaced evenly so that `0` maps to `0.0` and `num_variants - 1` maps to `1.0`.
+fn index_to_normalized(index: usize, num_variants: usize) -> f32 {
+    if num_variants <= 1 {
+        return 0.0;
     }
 
-    /// The maximum value of this parameter.
-    pub fn max(&self) -> f64 {
-        self.max
+    (index.min(num_variants - 1) as f64 / (num_variants - 1) as f64) as f32
+}
+
+/// Map the normalized value in the range `[0.0, 1.0]` to the index of the nearest step.
+fn normalized_to_index(normalized: f32, num_variants: usize) -> usize {
+    if num_variants <= 1 {
+        return 0;
     }
 
-    /// The [`Gradient`] mapping used when converting from the normalized value
-    /// in the range `[0.0, 1.0]` to the desired value.
+    let normalized = f64::from(normalized.clamp(0.0, 1.0));
+    (normalized * (num_variants - 1) as f64).round() as usize
+}
+
+/// A parameter that selects one of a number of discrete choices (i.e. a filter type or an
+/// oscillator shape).
+///
+/// `T` is usually a fieldless enum whose variants are numbered `0..num_variants`.
+pub struct ParamEnum<T: Into<usize> + TryFrom<usize> + Copy> {
+    shared: Arc<AtomicUsize>,
+    num_variants: usize,
+    default_value: T,
+}
+
+impl<T: Into<usize> + TryFrom<usize> + Copy> ParamEnum<T> {
+    /// Create a Parameter/Handle pair from its (de-normalized) value.
     ///
-    /// [`Gradient`]: enum.Gradient.html
-    pub fn gradient(&self) -> Gradient {
-        self.gradient
+    /// * value - The initial value of the parameter.
+    /// * default_value - The default value of the parameter.
+    /// * num_variants - The number of choices. The indices of all values must be less than
+    ///   this.
+    pub fn from_value(
+        value: T,
+        default_value: T,
+        num_variants: usize,
+    ) -> (Self, ParamEnumHandle<T>) {
+        let shared = Arc::new(AtomicUsize::new(value.into()));
+
+        (
+            Self {
+                shared: Arc::clone(&shared),
+                num_variants,
+                default_value,
+            },
+            ParamEnumHandle {
+                shared,
+                num_variants,
+                default_value,
+            },
+        )
     }
 
-    /// The [`Unit`] that signifies how the value displayed to the end user should
-    /// differ from the actual value used in DSP.
+    /// Create a Parameter/Handle pair from its normalized value in the range `[0.0, 1.0]`.
     ///
-    /// [`Unit`]: enum.Unit.html
-    pub fn unit(&self) -> Unit {
-        self.unit
+    /// * normalized - The initial normalized value of the parameter in the range `[0.0, 1.0]`.
+    /// * default_value - The (un-normalized) default value of the parameter.
+    /// * num_variants - The number of choices. The indices of all values must be less than
+    ///   this.
+    pub fn from_normalized(
+        normalized: f32,
+        default_value: T,
+        num_variants: usize,
+    ) -> (Self, ParamEnumHandle<T>) {
+        let (param, handle) = Self::from_value(default_value, default_value, num_variants);
+        param.shared.store(
+            normalized_to_index(normalized, num_variants),
+            Ordering::Relaxed,
+        );
+
+        (param, handle)
     }
 
-    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
-    /// of this parameter.
-    pub fn value_to_normalized(&self, value: f64) -> f64 {
-        value_to_normalized_f64(value, self.min, self.max, self.gradient)
+    /// Set the (de-normalized) value of this parameter.
+    pub fn set_value(&mut self, value: T) {
+        self.shared.store(value.into(), Ordering::Relaxed);
     }
 
-    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
-    /// corresponding value of this parameter.
-    pub fn normalized_to_value(&self, normalized: f64) -> f64 {
-        normalized_to_value_f64(normalized, self.min, self.max, self.gradient)
+    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`. This selects the
+    /// nearest step.
+    pub fn set_normalized(&mut self, normalized: f32) {
+        self.shared.store(
+            normalized_to_index(normalized, self.num_variants),
+            Ordering::Relaxed,
+        );
     }
 
-    /// The current normalized value in the range `[0.0, 1.0]`. This is only meant for
-    /// communicating with the host. This is not meant to be used to retrieve the latest
-    /// value for DSP. To get the latest value for DSP please use `ParamF32::smoothed()`
-    /// instead.
-    ///
-    /// Please note that this should be called *after* calling `ParamF32::smoothed()`
-    /// if you need the latest value from the corresponding [`ParamF32Handle`],
-    /// otherwise this may not return the latest value.
+    /// The (un-normalized) value of this parameter.
     ///
-    /// [`ParamF32Handle`]: struct.ParamF32Handle.html
-    pub fn host_get_normalized(&self) -> f64 {
-        self.normalized
+    /// If the stored index does not convert to a `T`, then the default value is returned
+    /// instead.
+    pub fn value(&self) -> T {
+        T::try_from(self.shared.load(Ordering::Relaxed)).unwrap_or(self.default_value)
+    }
+
+    /// The normalized value of this parameter in the range `[0.0, 1.0]`.
+    pub fn normalized(&self) -> f32 {
+        index_to_normalized(self.shared.load(Ordering::Relaxed), self.num_variants)
     }
 
     /// The (un-normalized) default value of the parameter.
-    pub fn default_value(&self) -> f64 {
+    pub fn default_value(&self) -> T {
         self.default_value
     }
 
     /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
-    pub fn default_normalized(&self) -> f64 {
+    pub fn default_normalized(&self) -> f32 {
         self.value_to_normalized(self.default_value)
     }
 
-    /// The current (un-normalized) value of the parameter. This is only meant for
-    /// communicating with the host. This is not meant to be used to retrieve the latest
-    /// value for DSP. To get the latest value for DSP please use `ParamF64::smoothed()`
-    /// instead.
-    ///
-    /// Please note that this should be called *after* calling `ParamF64::smoothed()`
-    /// if you need the latest value from the corresponding [`ParamF64Handle`],
-    /// otherwise this may not return the latest value.
-    ///
-    /// [`ParamF64Handle`]: struct.ParamF64Handle.html
-    pub fn host_get_value(&self) -> f64 {
-        self.value
+    /// The number of choices.
+    pub fn num_variants(&self) -> usize {
+        self.num_variants
     }
 
-    /// Get the shared normalized float value.
+    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
+    /// of this parameter.
+    pub fn value_to_normalized(&self, value: T) -> f32 {
+        index_to_normalized(value.into(), self.num_variants)
+    }
+
+    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
+    /// corresponding value of this parameter (the nearest step).
     ///
-    /// This can be useful to integrate with various plugin APIs.
-    pub fn shared_normalized(&self) -> Arc<AtomicF64> {
-        Arc::clone(&self.shared_normalized)
+    /// If the index of the nearest step does not convert to a `T`, then the default value
+    /// is returned instead.
+    pub fn normalized_to_value(&self, normalized: f32) -> T {
+        T::try_from(normalized_to_index(normalized, self.num_variants))
+            .unwrap_or(self.default_value)
     }
 }
 
-/// A handle to get and update the value of an auto-smoothed [`ParamF64`] from a UI.
+/// A handle to get and update the value of a [`ParamEnum`] from a UI.
 ///
-/// [`ParamF64`]: struct.ParamF64.html
-pub struct ParamF64Handle {
-    min: f64,
-    max: f64,
-    gradient: Gradient,
-    unit: Unit,
-    default_value: f64,
-
-    shared_normalized: Arc<AtomicF64>,
+/// [`ParamEnum`]: struct.ParamEnum.html
+pub struct ParamEnumHandle<T: Into<usize> + TryFrom<usize> + Copy> {
+    shared: Arc<AtomicUsize>,
+    num_variants: usize,
+    default_value: T,
 }
 
-impl ParamF64Handle {
-    /// The normalized value in the range `[0.0, 1.0]`.
-    pub fn normalized(&self) -> f64 {
-        self.shared_normalized.get()
-    }
-
+impl<T: Into<usize> + TryFrom<usize> + Copy> ParamEnumHandle<T> {
     /// The (un-normalized) value of this parameter.
     ///
-    /// Please note that this is calculated from the shared normalized value every time, so
-    /// avoid calling this every frame if you can.
-    pub fn value(&self) -> f64 {
-        normalized_to_value_f64(
-            self.shared_normalized.get(),
-            self.min,
-            self.max,
-            self.gradient,
-        )
+    /// If the stored index does not convert to a `T`, then the default value is returned
+    /// instead.
+    pub fn value(&self) -> T {
+        T::try_from(self.shared.load(Ordering::Relaxed)).unwrap_or(self.default_value)
+    }
+
+    /// The normalized value of this parameter in the range `[0.0, 1.0]`.
+    pub fn normalized(&self) -> f32 {
+        index_to_normalized(self.shared.load(Ordering::Relaxed), self.num_variants)
     }
 
     /// The (un-normalized) default value of the parameter.
-    pub fn default_value(&self) -> f64 {
+    pub fn default_value(&self) -> T {
         self.default_value
     }
 
     /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
-    pub fn default_normalized(&self) -> f64 {
+    pub fn default_normalized(&self) -> f32 {
         self.value_to_normalized(self.default_value)
     }
 
-    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
-    ///
-    /// Please note that this will ***NOT*** automatically notify the host of the value change
-    /// if you are using this inside a plugin spec such as VST. It is intended for you use your
-    /// own method for achieving this.
-    pub fn set_normalized(&self, normalized: f64) {
-        self.shared_normalized.set(normalized.clamp(0.0, 1.0));
-    }
-
-    /// Set the (un-normalized) value of this parameter.
-    ///
-    /// Please note that this will ***NOT*** automatically notify the host of the value change
-    /// if you are using this inside a plugin spec such as VST. It is intended for you use your
-    /// own method for achieving this.
-    pub fn set_value(&self, value: f64) {
-        let normalized = value_to_normalized_f64(value, self.min, self.max, self.gradient);
-        self.set_normalized(normalized);
-    }
-
-    /// The minimum value of this parameter.
-    pub fn min(&self) -> f64 {
-        self.min
-    }
-
-    /// The maximum value of this parameter.
-    pub fn max(&self) -> f64 {
-        self.max
+    /// The number of choices.
+    pub fn num_variants(&self) -> usize {
+        self.num_variants
     }
 
-    /// The [`Gradient`] mapping used when converting from the normalized value
-    /// in the range `[0.0, 1.0]` to the desired value.
-    ///
-    /// [`Gradient`]: enum.Gradient.html
-    pub fn gradient(&self) -> Gradient {
-        self.gradient
+    /// Set the (de-normalized) value of this parameter.
+    pub fn set_value(&self, value: T) {
+        self.shared.store(value.into(), Ordering::Relaxed);
     }
 
-    /// The [`Unit`] that signifies how the value displayed to the end user should
-    /// differ from the actual value used in DSP.
-    ///
-    /// [`Unit`]: enum.Unit.html
-    pub fn unit(&self) -> Unit {
-        self.unit
+    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`. This selects the
+    /// nearest step.
+    pub fn set_normalized(&self, normalized: f32) {
+        self.shared.store(
+            normalized_to_index(normalized, self.num_variants),
+            Ordering::Relaxed,
+        );
     }
 
     /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
     /// of this parameter.
-    pub fn value_to_normalized(&self, value: f64) -> f64 {
-        value_to_normalized_f64(value, self.min, self.max, self.gradient)
+    pub fn value_to_normalized(&self, value: T) -> f32 {
+        index_to_normalized(value.into(), self.num_variants)
     }
 
     /// Convert the given normalized value in the range `[0.0, 1.0]` into the
-    /// corresponding value of this parameter.
-    pub fn normalized_to_value(&self, normalized: f64) -> f64 {
-        normalized_to_value_f64(normalized, self.min, self.max, self.gradient)
-    }
-
-    /// Get the shared normalized float value.
+    /// corresponding value of this parameter (the nearest step).
     ///
-    /// This can be useful to integrate with various plugin APIs.
-    pub fn shared_normalized(&self) -> Arc<AtomicF64> {
-        Arc::clone(&self.shared_normalized)
+    /// If the index of the nearest step does not convert to a `T`, then the default value
+    /// is returned instead.
+    pub fn normalized_to_value(&self, normalized: f32) -> T {
+        T::try_from(normalized_to_index(normalized, self.num_variants))
+            .unwrap_or(self.default_value)
     }
 }
 
-impl Clone for ParamF64Handle {
+impl<T: Into<usize> + TryFrom<usize> + Copy> Clone for ParamEnumHandle<T> {
     fn clone(&self) -> Self {
         Self {
-            min: self.min,
-            max: self.max,
-            gradient: self.gradient,
-            unit: self.unit,
+            shared: Arc::clone(&self.shared),
+            num_variants: self.num_variants,
             default_value: self.default_value,
-
-            shared_normalized: Arc::clone(&self.shared_normalized),
         }
     }
 }
 
-pub fn normalized_to_value_f64(normalized: f64, min: f64, max: f64, gradient: Gradient) -> f64 {
-    let normalized = normalized.clamp(0.0, 1.0);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smooth::SmoothStatus;
 
-    let map = |x: f64| -> f64 {
-        let range = max - min;
-        (x * range) + min
-    };
+    fn test_param_f32(
+        value: f32,
+        min: f32,
+        max: f32,
+        gradient: Gradient,
+    ) -> (ParamF32, ParamF32Handle) {
+        ParamF32::from_value(
+            value,
+            value,
+            min,
+            max,
+            gradient,
+            Unit::Generic,
+            DEFAULT_SMOOTH_SECS,
+            SampleRate::default(),
+            256,
+        )
+    }
 
-    match gradient {
-        Gradient::Linear => map(normalized),
+    #[test]
+    fn test_save_restore_state() {
+        let (mut param, _handle) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
 
-        Gradient::Power(exponent) => map(normalized.powf(f64::from(exponent))),
+        param.set_value(1.0);
+        param.smoothed(64);
 
-        Gradient::Exponential => {
-            if normalized == 0.0 {
-                return min;
-            }
+        let state = param.save_state();
 
-            if normalized == 1.0 {
-                return max;
-            }
+        let first: Vec<f32> = (0..4)
+            .flat_map(|_| param.smoothed(64).values[..64].to_vec())
+            .collect();
 
-            let minl = min.log2();
-            let range = max.log2() - minl;
-            2.0f64.powf((normalized * range) + minl)
-        }
+        param.restore_state(state);
+
+        let second: Vec<f32> = (0..4)
+            .flat_map(|_| param.smoothed(64).values[..64].to_vec())
+            .collect();
+
+        assert_eq!(first, second);
     }
-}
 
-pub fn value_to_normalized_f64(value: f64, min: f64, max: f64, gradient: Gradient) -> f64 {
-    if value <= min {
-        return 0.0;
+    #[test]
+    fn test_smoothed_with_elapsed() {
+        let (mut param, _handle) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+
+        param.set_value(1.0);
+        param.smoothed(64);
+
+        // Simulate a gap of one second where processing was suspended.
+        let out = param.smoothed_with_elapsed(64, FrameTime(44_100));
+        assert!(out.values[..64].iter().all(|v| (*v - 1.0).abs() < 0.0001));
+
+        // Without accounting for the gap, the ramp would resume where it left off.
+        let (mut param, _handle) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+
+        param.set_value(1.0);
+        param.smoothed(64);
+
+        let out = param.smoothed(64);
+        assert!(out.values[0] < 0.5);
     }
 
-    if value >= max {
-        return 1.0;
+    #[test]
+    fn test_param_i32_gradient() {
+        let (param, _) = ParamI32::from_value(1, 1, 1, 10_000, Gradient::Exponential);
+        let linear = |n| normalized_to_value_i32(n, 1, 10_000, Gradient::Linear);
+        let exp = |n| normalized_to_value_i32(n, 1, 10_000, Gradient::Exponential);
+
+        assert_eq!(exp(0.0), 1);
+        assert_eq!(exp(1.0), 10_000);
+        assert_eq!(exp(0.5), 100);
+        assert_eq!(linear(0.5), 5_001);
+
+        // The integer steps are much denser at the low end with an exponential gradient.
+        let low_steps = exp(0.1) - exp(0.0);
+        let high_steps = exp(1.0) - exp(0.9);
+        assert!(low_steps < 10);
+        assert!(high_steps > 1_000);
+
+        for value in [1, 2, 10, 100, 1_000, 10_000] {
+            assert_eq!(exp(param.value_to_normalized(value)), value);
+        }
     }
 
-    let unmap = |x: f64| -> f64 {
-        let range = max - min;
-        (x - min) / range
-    };
+    #[test]
+    fn test_param_i32_min_max_round_trip() {
+        for (min, max) in [(0, 10), (1, 127), (-24, 24), (-100, -10), (-1, 0)] {
+            let (mut param, mut handle) =
+                ParamI32::from_value(min, min, min, max, Gradient::Linear);
+
+            assert_eq!(param.value_to_normalized(min), 0.0);
+            assert_eq!(param.value_to_normalized(max), 1.0);
+            assert_eq!(param.normalized_to_value(0.0), min);
+            assert_eq!(param.normalized_to_value(1.0), max);
+
+            param.set_normalized(0.0);
+            assert_eq!(param.value(), min);
+            param.set_normalized(1.0);
+            assert_eq!(param.value(), max);
+
+            handle.set_normalized(0.0);
+            assert_eq!(handle.value(), min);
+            handle.set_normalized(1.0);
+            assert_eq!(handle.value(), max);
+        }
+    }
 
-    match gradient {
-        Gradient::Linear => unmap(value),
+    #[test]
+    fn test_param_i32_equal_width_buckets() {
+        let (param, _) = ParamI32::from_value(0, 0, 0, 3, Gradient::Linear);
+        let to_value = |n| normalized_to_value_i32(n, 0, 3, Gradient::Linear);
 
-        Gradient::Power(exponent) => unmap(value).powf(1.0 / f64::from(exponent)),
+        // Every value occupies a band of width `0.25`.
+        for (value, band) in [(0, 0.0), (1, 0.25), (2, 0.5), (3, 0.75)] {
+            assert_eq!(to_value(band), value);
+            assert_eq!(to_value(band + 0.124), value);
+            assert_eq!(to_value(band + 0.249), value);
+        }
+        assert_eq!(to_value(1.0), 3);
 
-        Gradient::Exponential => {
-            let minl = min.log2();
-            let range = max.log2() - minl;
-            (value.log2() - minl) / range
+        for value in 0..=3 {
+            assert_eq!(to_value(param.value_to_normalized(value)), value);
+        }
+        assert_eq!(param.value_to_normalized(0), 0.0);
+        assert_eq!(param.value_to_normalized(3), 1.0);
+
+        let (param, _) = ParamI32::from_value(-5, -5, -5, 5, Gradient::Linear);
+        let to_value = |n| normalized_to_value_i32(n, -5, 5, Gradient::Linear);
+        for value in -5..=5 {
+            assert_eq!(to_value(param.value_to_normalized(value)), value);
         }
     }
-}
 
-/// A parameter with an `i32` value.
-pub struct ParamI32 {
-    min: i32,
-    max: i32,
-    default_value: i32,
+    #[test]
+    fn test_unit_semitones_and_hz() {
+        assert_eq!(Unit::Semitones.unit_to_dsp_f32(12.0), 2.0);
+        assert_eq!(Unit::Semitones.unit_to_dsp_f64(-12.0), 0.5);
+        assert_eq!(Unit::Semitones.dsp_to_unit_f32(1.0), 0.0);
+        assert_eq!(Unit::Semitones.dsp_to_unit_f64(4.0), 24.0);
+        assert_eq!(Unit::Semitones.suffix(), "st");
 
-    shared: Arc<AtomicI32>,
-}
+        for st in [-48.0, -7.5, -1.0, 0.0, 0.01, 3.0, 19.0, 48.0] {
+            let ratio = Unit::Semitones.unit_to_dsp_f32(st);
+            assert!((Unit::Semitones.dsp_to_unit_f32(ratio) - st).abs() < 1e-4);
 
-impl ParamI32 {
-    /// Create a Parameter/Handle pair from its (de-normalized) value.
-    ///
-    /// * value - The initial (de-normalized) value of the parameter.
-    /// * default_value - The (de-normalized) default value of the parameter.
-    /// * min - The minimum (de-normalized) value of the parameter.
-    /// * max - The maximum (de-normalized) value of the parameter.
-    pub fn from_value(
-        value: i32,
-        default_value: i32,
-        min: i32,
-        max: i32,
-    ) -> (Self, ParamI32Handle) {
-        let value = value.clamp(min, max);
+            let ratio = Unit::Semitones.unit_to_dsp_f64(f64::from(st));
+            assert!((Unit::Semitones.dsp_to_unit_f64(ratio) - f64::from(st)).abs() < 1e-9);
+        }
 
-        let shared = Arc::new(AtomicI32::new(value));
+        for pct in [0.0, 12.5, 50.0, 100.0] {
+            let dsp = Unit::Percent.unit_to_dsp_f32(pct);
+            assert!((Unit::Percent.dsp_to_unit_f32(dsp) - pct).abs() < 1e-4);
+            assert_eq!(
+                Unit::Percent.dsp_to_unit_f64(Unit::Percent.unit_to_dsp_f64(f64::from(pct))),
+                f64::from(pct)
+            );
+        }
 
-        (
-            Self {
-                min,
-                max,
-                default_value,
-                shared: Arc::clone(&shared),
-            },
-            ParamI32Handle {
-                min,
-                max,
-                default_value,
-                shared,
-            },
-        )
+        assert_eq!(Unit::Hz.unit_to_dsp_f32(440.0), 440.0);
+        assert_eq!(Unit::Hz.dsp_to_unit_f64(440.0), 440.0);
+        assert_eq!(Unit::Hz.suffix(), "Hz");
     }
 
-    /// Create a Parameter/Handle pair from its normalized value in the range `[0.0, 1.0]`.
-    ///
-    /// * normalized - The initial normalized value of the parameter in the range `[0.0, 1.0]`.
-    /// * default_value - The (de-normalized) default value of the parameter.
-    /// * min - The minimum (de-normalized) value of the parameter.
-    /// * max - The maximum (de-normalized) value of the parameter.
-    pub fn from_normalized(
-        normalized: f32,
-        default_value: i32,
-        min_value: i32,
-        max_value: i32,
-    ) -> (Self, ParamI32Handle) {
-        let normalized = normalized.clamp(0.0, 1.0);
-        let value = ((normalized * (max_value as f32 - min_value as f32)) + min_value as f32)
-            .round() as i32;
+    #[test]
+    fn test_unit_percent() {
+        assert_eq!(Unit::Percent.dsp_to_unit_f32(0.5), 50.0);
+        assert_eq!(Unit::Percent.unit_to_dsp_f32(50.0), 0.5);
+        assert_eq!(Unit::Percent.suffix(), "%");
+
+        let (mut param, handle) = ParamF32::from_value(
+            100.0,
+            100.0,
+            0.0,
+            100.0,
+            Gradient::Linear,
+            Unit::Percent,
+            DEFAULT_SMOOTH_SECS,
+            SampleRate::default(),
+            256,
+        );
+
+        let parsed = handle.string_to_value("50 %").unwrap();
+        assert_eq!(handle.string_to_value("50"), Some(parsed));
+        handle.set_value(parsed);
+        assert_eq!(handle.value(), 50.0);
+
+        param.smoothed(256);
+        param.reset();
+        assert_eq!(param.smoothed(1).values[0], 0.5);
+    }
 
-        Self::from_value(value, default_value, min_value, max_value)
+    #[test]
+    fn test_display_value() {
+        assert_eq!(Unit::Generic.display_value_f32(0.5), (0.5, false));
+        assert_eq!(Unit::Generic.display_value_f32(-0.5), (0.5, true));
+        assert_eq!(Unit::Generic.display_value_f32(0.0), (0.0, false));
+        assert_eq!(Unit::Generic.display_value_f64(-0.25), (0.25, true));
+
+        let (magnitude, negative) = Unit::Decibels.display_value_f32(0.5);
+        assert!((magnitude - 6.0206).abs() < 1e-3);
+        assert!(negative);
     }
 
-    /// Set the (de-normalized) value of this parameter.
-    pub fn set_value(&mut self, value: i32) {
-        self.shared
-            .store(value.clamp(self.min, self.max), Ordering::Relaxed);
+    #[test]
+    fn test_export_normalized_breakpoints() {
+        let (param, _) = test_param_f32(1_000.0, 20.0, 20_000.0, Gradient::Exponential);
+
+        let changes = [
+            (FrameTime(0), 20.0),
+            (FrameTime(128), 440.0),
+            (FrameTime(256), 20_000.0),
+            (FrameTime(512), 50_000.0),
+        ];
+        let breakpoints = param.export_normalized_breakpoints(&changes);
+
+        assert_eq!(breakpoints.len(), changes.len());
+        assert_eq!(breakpoints[0], (FrameTime(0), 0.0));
+        assert_eq!(breakpoints[2], (FrameTime(256), 1.0));
+        assert_eq!(breakpoints[3], (FrameTime(512), 1.0));
+
+        let (t, normalized) = breakpoints[1];
+        assert_eq!(t, FrameTime(128));
+        assert!((param.normalized_to_value(normalized) - 440.0).abs() < 1e-2);
     }
 
-    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
-    pub fn set_normalized(&mut self, normalized: f32) {
-        let normalized = normalized.clamp(0.0, 1.0);
-        let value =
-            ((normalized * (self.max as f32 - self.min as f32)) + self.max as f32).round() as i32;
+    #[test]
+    fn test_snap_values() {
+        let (mut param, handle) = test_param_f32(0.0, -12.0, 12.0, Gradient::Linear);
+        param.set_snap_values(&[0.0, 7.0], 1.0);
+
+        handle.set_value(0.1);
+        param.poll_shared();
+        assert!(param.host_get_value().abs() < 1e-5);
+        assert!((handle.value() - 0.1).abs() < 1e-5);
+
+        // The snapped value is kept until the handle sets a new value.
+        param.smoothed(64);
+        assert!(param.host_get_value().abs() < 1e-5);
+
+        handle.set_value(6.8);
+        param.poll_shared();
+        assert!((param.host_get_value() - 7.0).abs() < 1e-5);
+
+        // Far away from any snap value.
+        handle.set_value(3.0);
+        param.poll_shared();
+        assert!((param.host_get_value() - 3.0).abs() < 1e-5);
+
+        // A partial pull.
+        param.set_snap_values(&[0.0], 0.5);
+        handle.set_value(0.2);
+        param.poll_shared();
+        assert!((param.host_get_value() - 0.1).abs() < 1e-5);
+
+        // Values set on the parameter itself are not snapped.
+        param.set_value(0.2);
+        param.smoothed(64);
+        assert!((param.host_get_value() - 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_soft_reset_to_value() {
+        let (mut param, _) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+
+        // 100 frames at 44.1 kHz.
+        param.soft_reset_to_value(1.0, SecondsF64(100.0 / 44_100.0));
+
+        let out = param.smoothed(256);
+        for (i, v) in out.values[..100].iter().enumerate() {
+            assert!((v - ((i + 1) as f32 / 100.0)).abs() < 1e-5);
+        }
+        assert!(out.values[100..].iter().all(|v| *v == 1.0));
+        assert!(out.is_smoothing());
+
+        let out = param.smoothed(256);
+        assert!(out.values.iter().all(|v| *v == 1.0));
+    }
 
-        self.set_value(value);
+    #[test]
+    fn test_f32_f64_config_conversion() {
+        let (param_f32, _) = ParamF32::from_value(
+            -6.0,
+            0.0,
+            -90.0,
+            6.0,
+            DEFAULT_DB_GRADIENT,
+            Unit::Decibels,
+            DEFAULT_SMOOTH_SECS,
+            SampleRate::default(),
+            256,
+        );
+
+        let (param_f64, handle_f64) = ParamF64::from_f32_config(&param_f32, SampleRate(48_000.0));
+        assert_eq!(param_f64.min(), -90.0);
+        assert_eq!(param_f64.max(), 6.0);
+        assert_eq!(param_f64.gradient(), DEFAULT_DB_GRADIENT);
+        assert_eq!(param_f64.unit(), Unit::Decibels);
+        assert_eq!(param_f64.default_value(), 0.0);
+        assert!((handle_f64.value() + 6.0).abs() < 1e-4);
+
+        let (param_f32, handle_f32) = ParamF32::from_f64_config(&param_f64, SampleRate(48_000.0));
+        assert_eq!(param_f32.min(), -90.0);
+        assert_eq!(param_f32.max(), 6.0);
+        assert_eq!(param_f32.gradient(), DEFAULT_DB_GRADIENT);
+        assert_eq!(param_f32.unit(), Unit::Decibels);
+        assert!((handle_f32.value() + 6.0).abs() < 1e-4);
     }
 
-    /// The (un-normalized) value of this parameter.
-    pub fn value(&self) -> i32 {
-        self.shared.load(Ordering::Relaxed)
+    #[test]
+    fn test_weak_handle() {
+        let (param, handle) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+
+        let weak = handle.downgrade();
+
+        let upgraded = weak.upgrade().unwrap();
+        upgraded.set_normalized(0.5);
+        assert_eq!(handle.normalized(), 0.5);
+        drop(upgraded);
+
+        // Dropping the audio-side parameter invalidates the weak handle, even though a strong
+        // handle still keeps the shared atomics alive.
+        drop(param);
+        assert!(weak.upgrade().is_none());
+
+        // This also holds for weak handles created after the parameter was dropped.
+        assert!(handle.downgrade().upgrade().is_none());
+        assert!(handle.clone().downgrade().upgrade().is_none());
+
+        drop(handle);
+        assert!(weak.upgrade().is_none());
     }
 
-    /// The (un-normalized) default value of the parameter.
-    pub fn default_value(&self) -> i32 {
-        self.default_value
+    #[test]
+    fn test_min_max_seen() {
+        let (mut param, handle) = test_param_f32(0.5, 0.0, 1.0, Gradient::Linear);
+        assert_eq!((param.min_seen(), param.max_seen()), (0.5, 0.5));
+
+        param.soft_reset_to_value(1.0, SecondsF64(100.0 / 44_100.0));
+        param.smoothed(128);
+        handle.set_value(0.25);
+        for _ in 0..100 {
+            param.smoothed(128);
+        }
+
+        assert_eq!(param.min_seen(), 0.25);
+        assert_eq!(param.max_seen(), 1.0);
+
+        param.reset_seen();
+        assert_eq!((param.min_seen(), param.max_seen()), (0.25, 0.25));
     }
 
-    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
-    pub fn default_normalized(&self) -> f32 {
-        self.value_to_normalized(self.default_value)
+    #[test]
+    fn test_poll_shared_atomic_writes() {
+        let (mut param, handle) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+
+        handle.set_normalized(0.5);
+        assert!(param.poll_shared());
+        assert_eq!(param.peek_value(), 0.5);
+
+        // Writing the same value again is not a change.
+        handle.set_normalized(0.5);
+        assert!(!param.poll_shared());
+
+        // Values written directly to the shared atomic are picked up too.
+        param.shared_normalized().set(0.25);
+        assert!(param.poll_shared());
+        assert_eq!(param.peek_value(), 0.25);
+        assert!(!param.poll_shared());
+
+        handle.shared_normalized().set(0.75);
+        assert!(param.smoothed(64).is_smoothing());
+        assert_eq!(param.peek_value(), 0.75);
+
+        // The same goes for `ParamF64`.
+        let (mut param, _) = ParamF64::from_value(
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            Gradient::Linear,
+            Unit::Generic,
+            DEFAULT_SMOOTH_SECS,
+            SampleRate::default(),
+            256,
+        );
+        param.shared_normalized().set(0.5);
+        assert!(param.smoothed(64).is_smoothing());
+        assert_eq!(param.host_get_normalized(), 0.5);
     }
 
-    /// The minimum value of this parameter.
-    pub fn min(&self) -> i32 {
-        self.min
+    #[test]
+    fn test_skip_unchanged_polls() {
+        let (mut param, handle) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+        param.set_skip_unchanged_polls(true);
+        assert!(!param.has_pending_changes());
+
+        // Handle writes mark the parameter as changed until the next poll.
+        handle.set_normalized(0.5);
+        assert!(param.has_pending_changes());
+        assert!(param.smoothed(64).is_smoothing());
+        assert!(!param.has_pending_changes());
+        assert_eq!(param.host_get_normalized(), 0.5);
+
+        handle.set_trim_offset_normalized(0.1);
+        assert!(param.has_pending_changes());
+        assert!(param.poll_shared());
+        assert!(!param.has_pending_changes());
+        assert!(!param.poll_shared());
+
+        // Writing the same value again is not a change.
+        handle.set_normalized(0.5);
+        assert!(!param.has_pending_changes());
+
+        // Direct writes to the shared atomic are skipped until polling is no longer skipped.
+        param.shared_normalized().set(0.25);
+        assert!(!param.has_pending_changes());
+        assert!(!param.poll_shared());
+        param.set_skip_unchanged_polls(false);
+        assert!(param.poll_shared());
+        assert_eq!(param.host_get_normalized(), 0.25);
     }
 
-    /// The maximum value of this parameter.
-    pub fn max(&self) -> i32 {
-        self.max
+    #[test]
+    fn test_trim_offset() {
+        let (mut param, handle) = test_param_f32(0.0, 0.0, 10.0, Gradient::Linear);
+
+        // The recorded automation value.
+        param.set_normalized(0.5);
+
+        handle.set_trim_offset_normalized(0.2);
+        assert!(param.poll_shared());
+
+        assert_eq!(param.host_get_normalized(), 0.5);
+        assert_eq!(handle.normalized(), 0.5);
+        assert_eq!(param.trim_offset_normalized(), 0.2);
+        assert!((param.effective_normalized() - 0.7).abs() < 1e-6);
+        assert!((param.host_get_value() - 7.0).abs() < 1e-5);
+
+        // The sum is clamped to the normalized range.
+        param.set_normalized(0.9);
+        assert_eq!(param.effective_normalized(), 1.0);
+        assert_eq!(param.host_get_value(), 10.0);
+
+        handle.set_trim_offset_normalized(-1.0);
+        param.poll_shared();
+        assert_eq!(param.host_get_normalized(), 0.9);
+        assert_eq!(param.host_get_value(), 0.0);
     }
 
-    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
-    /// of this parameter.
-    pub fn value_to_normalized(&self, value: i32) -> f32 {
-        let value = value.clamp(self.min, self.max);
-        (value - self.min) as f32 / (self.max as f32 - self.min as f32)
+    #[test]
+    fn test_modulation() {
+        let (mut param, handle) = test_param_f32(0.0, 0.0, 10.0, Gradient::Linear);
+        param.set_normalized(0.5);
+        param.reset();
+        let base = param.smoothed(1).values[0];
+        assert_eq!(base, 5.0);
+
+        param.set_modulation(0.25);
+        param.reset();
+        assert!((param.smoothed(1).values[0] - 7.5).abs() < 1e-5);
+
+        // The user-facing value is still the un-modulated base.
+        assert_eq!(param.host_get_normalized(), 0.5);
+        assert_eq!(handle.normalized(), 0.5);
+        assert_eq!(param.modulation(), 0.25);
+
+        // Changes to the base value are still combined with the modulation, and the sum is
+        // clamped to the normalized range.
+        handle.set_normalized(0.9);
+        param.poll_shared();
+        param.reset();
+        assert_eq!(param.smoothed(1).values[0], 10.0);
+
+        // Clearing the modulation restores the base output.
+        param.set_modulation(0.0);
+        param.reset();
+        assert!((param.smoothed(1).values[0] - 9.0).abs() < 1e-5);
+        assert_eq!(param.host_get_normalized(), 0.9);
     }
 
-    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
-    /// corresponding value of this parameter.
-    pub fn normalized_to_value(&self, normalized: f32) -> i32 {
-        let normalized = normalized.clamp(0.0, 1.0);
-        ((normalized * (self.max as f32 - self.min as f32)) + self.max as f32).round() as i32
+    #[test]
+    fn test_smoothed_as_constant() {
+        let (mut param, handle) = test_param_f32(0.5, 0.0, 1.0, Gradient::Linear);
+        assert_eq!(param.smoothed(64).as_constant(), Some(0.5));
+
+        handle.set_value(1.0);
+        assert_eq!(param.smoothed(64).as_constant(), None);
+
+        param.reset();
+        assert_eq!(param.smoothed(64).as_constant(), Some(1.0));
     }
-}
 
-/// A handle to get and update the value of a [`ParamI32`] from a UI.
-///
-/// [`ParamI32`]: struct.ParamI32.html
-pub struct ParamI32Handle {
-    min: i32,
-    max: i32,
-    default_value: i32,
+    #[test]
+    fn test_peek_value() {
+        let (mut param, _handle) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
 
-    shared: Arc<AtomicI32>,
-}
+        param.set_value(1.0);
+        assert_eq!(param.peek_value(), 1.0);
+        assert_eq!(param.current_smoothed_value(), 0.0);
 
-impl ParamI32Handle {
-    /// The (un-normalized) value of this parameter.
-    pub fn value(&self) -> i32 {
-        self.shared.load(Ordering::Relaxed)
+        let last = param.smoothed(64).values[63];
+        assert_eq!(param.peek_value(), 1.0);
+        assert_eq!(param.current_smoothed_value(), last);
+        assert!(last > 0.0 && last < 1.0);
+
+        param.set_value(0.25);
+        assert_eq!(param.peek_value(), 0.25);
+        assert_eq!(param.current_smoothed_value(), last);
     }
 
-    /// The (un-normalized) default value of the parameter.
-    pub fn default_value(&self) -> i32 {
-        self.default_value
+    #[test]
+    fn test_builder() {
+        let (param, handle) = ParamF32Builder::new(256)
+            .min(-90.0)
+            .max(6.0)
+            .default_value(0.0)
+            .gradient(Gradient::Power(0.15))
+            .unit(Unit::Decibels)
+            .build_from_value(-6.0);
+
+        let (expected, _) = ParamF32::from_value(
+            -6.0,
+            0.0,
+            -90.0,
+            6.0,
+            Gradient::Power(0.15),
+            Unit::Decibels,
+            DEFAULT_SMOOTH_SECS,
+            SampleRate::default(),
+            256,
+        );
+        assert_eq!(param.config(), expected.config());
+        assert_eq!(param.state(), expected.state());
+        assert_eq!(handle.value(), -6.0);
+
+        // Without a default value, the initial value is used.
+        let (param, _) = ParamF32Builder::new(256)
+            .min(20.0)
+            .max(20_000.0)
+            .gradient(Gradient::Exponential)
+            .build_from_normalized(1.0);
+        assert_eq!(param.default_value(), 20_000.0);
+        assert_eq!(param.config().smooth_secs, DEFAULT_SMOOTH_SECS);
+        assert_eq!(param.config().unit, Unit::Generic);
     }
 
-    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
-    pub fn default_normalized(&self) -> f32 {
-        self.value_to_normalized(self.default_value)
+    #[test]
+    fn test_overshoot() {
+        let (mut param, handle) = test_param_f32(0.0, 0.0, 10.0, Gradient::Linear);
+
+        // Without overshoot, the value is capped at `max`.
+        handle.set_normalized(1.2);
+        param.poll_shared();
+        assert_eq!(param.host_get_value(), 10.0);
+
+        param.set_allow_overshoot(true);
+        param.set_overshoot_limit(1.5);
+
+        handle.set_normalized(1.2);
+        param.poll_shared();
+        assert!((param.host_get_value() - 12.0).abs() < 1e-5);
+        assert!((handle.value() - 12.0).abs() < 1e-5);
+        assert_eq!(param.host_get_normalized(), 1.0);
+
+        // Up to the configured limit.
+        handle.set_normalized(2.0);
+        param.poll_shared();
+        assert!((param.host_get_value() - 15.0).abs() < 1e-5);
+
+        // The mapping of in-range values is unchanged.
+        assert_eq!(param.value_to_normalized(5.0), 0.5);
+        assert_eq!(param.normalized_to_value(0.5), 5.0);
+
+        param.set_allow_overshoot(false);
+        assert_eq!(handle.normalized(), 1.0);
+        assert_eq!(param.host_get_value(), 10.0);
+
+        // Curved gradients continue linearly past `max`, and never produce NaN.
+        for gradient in [Gradient::SCurve(4.0), Gradient::SCurve(-4.0)] {
+            let (mut param, handle) = test_param_f32(0.0, 0.0, 10.0, gradient);
+            param.set_allow_overshoot(true);
+            param.set_overshoot_limit(2.0);
+
+            handle.set_normalized(2.0);
+            let out = param.smoothed(64);
+            assert!(out.values[..64].iter().all(|v| v.is_finite()));
+            assert!((param.host_get_value() - 20.0).abs() < 1e-4);
+            assert!((handle.value() - 20.0).abs() < 1e-4);
+        }
     }
 
-    /// Set the (de-normalized) value of this parameter.
-    pub fn set_value(&mut self, value: i32) {
-        self.shared
-            .store(value.clamp(self.min, self.max), Ordering::Relaxed);
+    #[test]
+    fn test_value_display_fixed() {
+        let (_, handle) = test_param_f32(0.0, -2_000.0, 20_000.0, Gradient::Linear);
+
+        for value in [
+            0.0, 1.5, -12.345, 999.99, 1_000.0, 12_500.0, -1_999.0, 20_000.0,
+        ] {
+            handle.set_value(value);
+            for width in [0, 1, 3, 4, 7, 10] {
+                assert_eq!(handle.value_display_fixed(width).chars().count(), width);
+            }
+        }
+
+        handle.set_value(12_500.0);
+        assert_eq!(handle.value_display_fixed(7), "12500.0");
+        assert_eq!(handle.value_display_fixed(6), " 12500");
+        assert_eq!(handle.value_display_fixed(4), " 13k");
+
+        // Values of `1000` or more are only abbreviated if the full value doesn't fit.
+        let (_, handle) = ParamF32::from_value(
+            1_500.0,
+            1_000.0,
+            20.0,
+            20_000.0,
+            Gradient::Linear,
+            Unit::Hz,
+            DEFAULT_SMOOTH_SECS,
+            SampleRate::default(),
+            256,
+        );
+        assert_eq!(handle.value_display_fixed(8), "1500.0Hz");
+        assert_eq!(handle.value_display_fixed(7), " 1500Hz");
+        assert_eq!(handle.value_display_fixed(4), "1500");
+        assert_eq!(handle.value_display_fixed(3), " 2k");
+
+        handle.set_value(12_800.0);
+        assert_eq!(handle.value_display_fixed(5), "12800");
+        assert_eq!(handle.value_display_fixed(4), " 13k");
+
+        let (_, handle) = ParamF32::from_value(
+            -12.4,
+            0.0,
+            -90.0,
+            12.0,
+            Gradient::Linear,
+            Unit::Decibels,
+            DEFAULT_SMOOTH_SECS,
+            SampleRate::default(),
+            256,
+        );
+        assert_eq!(handle.value_display_fixed(7), "-12.4dB");
+        assert_eq!(handle.value_display_fixed(5), "-12dB");
+        assert_eq!(handle.value_display_fixed(4), " -12");
+        assert_eq!(handle.value_display_fixed(2), "-1");
+        assert_eq!(handle.value_display_fixed(9), " -12.40dB");
     }
 
-    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
-    pub fn set_normalized(&mut self, normalized: f32) {
-        let normalized = normalized.clamp(0.0, 1.0);
-        let value =
-            ((normalized * (self.max as f32 - self.min as f32)) + self.max as f32).round() as i32;
+    #[test]
+    fn test_steps() {
+        let (mut param, handle) = test_param_f32(0.0, 0.0, 30.0, Gradient::Linear);
+        param.set_steps(Some(4));
+        assert_eq!(param.steps(), Some(4));
 
-        self.set_value(value);
+        let steps = [0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0];
+        let is_step = |n: f32| steps.iter().any(|s| (s - n).abs() < 1.0e-6);
+
+        for i in 0..=100 {
+            let normalized = i as f32 / 100.0;
+
+            param.set_normalized(normalized);
+            assert!(is_step(param.host_get_normalized()));
+
+            param.set_value(normalized * 30.0);
+            assert!(is_step(param.host_get_normalized()));
+
+            handle.set_normalized(normalized);
+            param.smoothed(64);
+            assert!(is_step(param.host_get_normalized()));
+            assert_eq!(handle.normalized(), normalized);
+        }
+
+        param.set_value(12.0);
+        assert_eq!(param.peek_value(), 10.0);
+        param.set_value(16.0);
+        assert_eq!(param.peek_value(), 20.0);
+
+        // Moving between steps is still smoothed.
+        param.reset();
+        param.set_normalized(1.0);
+        let out = param.smoothed(8);
+        assert!(out.values[0] > 20.0 && out.values[0] < 30.0);
+
+        // Removing the steps makes the parameter continuous again.
+        param.set_steps(None);
+        param.set_normalized(0.5);
+        assert_eq!(param.host_get_normalized(), 0.5);
     }
 
-    /// The minimum value of this parameter.
-    pub fn min(&self) -> i32 {
-        self.min
+    #[test]
+    fn test_bezier_gradient() {
+        let ease = Gradient::Bezier {
+            x1: 0.25,
+            y1: 0.1,
+            x2: 0.25,
+            y2: 1.0,
+        };
+        let (param, _) = test_param_f32(0.0, -10.0, 10.0, ease);
+
+        assert_eq!(param.normalized_to_value(0.0), -10.0);
+        assert_eq!(param.normalized_to_value(1.0), 10.0);
+        assert_eq!(param.value_to_normalized(-10.0), 0.0);
+        assert_eq!(param.value_to_normalized(10.0), 1.0);
+
+        let mut prev = -10.0;
+        for i in 1..=1_000 {
+            let normalized = i as f32 / 1_000.0;
+
+            let value = param.normalized_to_value(normalized);
+            assert!(value > prev, "not monotonic at {}", normalized);
+            prev = value;
+
+            assert!((param.value_to_normalized(value) - normalized).abs() < 1.0e-4);
+            assert!(param.value_slope_at(normalized) >= 0.0);
+
+            let value_f64 = normalized_to_value_f64(f64::from(normalized), -10.0, 10.0, ease);
+            assert!((value_f64 - f64::from(value)).abs() < 1.0e-4);
+            assert!(
+                (value_to_normalized_f64(value_f64, -10.0, 10.0, ease) - f64::from(normalized))
+                    .abs()
+                    < 1.0e-9
+            );
+        }
+
+        // Control points evenly spaced along the diagonal give a linear mapping.
+        let linear = Gradient::Bezier {
+            x1: 1.0 / 3.0,
+            y1: 1.0 / 3.0,
+            x2: 2.0 / 3.0,
+            y2: 2.0 / 3.0,
+        };
+        for i in 0..=100 {
+            let normalized = i as f32 / 100.0;
+            assert!(
+                (normalized_to_value_f32(normalized, 20.0, 80.0, linear)
+                    - normalized_to_value_f32(normalized, 20.0, 80.0, Gradient::Linear))
+                .abs()
+                    < 1.0e-4
+            );
+            assert!(
+                (value_to_normalized_f32(20.0 + (60.0 * normalized), 20.0, 80.0, linear)
+                    - normalized)
+                    .abs()
+                    < 1.0e-5
+            );
+        }
+        let (param, _) = test_param_f32(0.0, 20.0, 80.0, linear);
+        assert!((param.value_slope_at(0.3) - 60.0).abs() < 1.0e-3);
     }
 
-    /// The maximum value of this parameter.
-    pub fn max(&self) -> i32 {
-        self.max
+    #[test]
+    fn test_exponential_gradient_non_positive_min() {
+        let (param, _) = ParamF32::from_value(
+            1_000.0,
+            1_000.0,
+            0.0,
+            20_000.0,
+            Gradient::Exponential,
+            Unit::Generic,
+            DEFAULT_SMOOTH_SECS,
+            SampleRate::default(),
+            256,
+        );
+
+        assert_eq!(param.normalized_to_value(0.0), 0.0);
+        assert_eq!(param.normalized_to_value(1.0), 20_000.0);
+
+        let mut prev = 0.0;
+        for i in 0..=1_000 {
+            let normalized = i as f32 / 1_000.0;
+
+            let value = param.normalized_to_value(normalized);
+            assert!(value.is_finite());
+            assert!(value >= prev);
+            prev = value;
+
+            assert!(param.value_to_normalized(value).is_finite());
+            assert!(param.value_slope_at(normalized).is_finite());
+        }
+        assert!(param.host_get_value().is_finite());
+
+        // A bipolar range that includes negative values.
+        for i in 0..=100 {
+            let normalized = f64::from(i) / 100.0;
+            let value = normalized_to_value_f64(normalized, -10.0, 10.0, Gradient::Exponential);
+            assert!(value.is_finite());
+            assert!(value_to_normalized_f64(value, -10.0, 10.0, Gradient::Exponential).is_finite());
+        }
     }
 
-    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
-    /// of this parameter.
-    pub fn value_to_normalized(&self, value: i32) -> f32 {
-        let value = value.clamp(self.min, self.max);
-        (value - self.min) as f32 / (self.max as f32 - self.min as f32)
+    #[test]
+    fn test_s_curve_gradient() {
+        for steepness in [-4.0, -1.0, 0.0, 1.0, 4.0] {
+            let gradient = Gradient::SCurve(steepness);
+
+            // `0.5` maps to the midpoint.
+            assert!((normalized_to_value_f32(0.5, -10.0, 30.0, gradient) - 10.0).abs() < 1e-5);
+            assert_eq!(normalized_to_value_f32(0.0, -10.0, 30.0, gradient), -10.0);
+            assert!((normalized_to_value_f32(1.0, -10.0, 30.0, gradient) - 30.0).abs() < 1e-4);
+
+            let mut prev = f32::MIN;
+            for i in 0..=1_000 {
+                let normalized = i as f32 / 1_000.0;
+
+                // Monotonic.
+                let value = normalized_to_value_f32(normalized, -10.0, 30.0, gradient);
+                assert!(value >= prev);
+                prev = value;
+
+                let round_trip = value_to_normalized_f32(value, -10.0, 30.0, gradient);
+                assert!((round_trip - normalized).abs() <= 1e-5, "{}", steepness);
+
+                let value = normalized_to_value_f64(f64::from(normalized), -10.0, 30.0, gradient);
+                let round_trip = value_to_normalized_f64(value, -10.0, 30.0, gradient);
+                assert!((round_trip - f64::from(normalized)).abs() <= 1e-9);
+            }
+        }
+
+        // A positive steepness is fine in the center and coarse at the extremes.
+        let (param, _) = test_param_f32(0.0, -1.0, 1.0, Gradient::SCurve(4.0));
+        assert!(param.value_slope_at(0.5) < param.value_slope_at(0.95));
+        let (param, _) = test_param_f32(0.0, -1.0, 1.0, Gradient::SCurve(-4.0));
+        assert!(param.value_slope_at(0.5) > param.value_slope_at(0.95));
     }
 
-    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
-    /// corresponding value of this parameter.
-    pub fn normalized_to_value(&self, normalized: f32) -> i32 {
-        let normalized = normalized.clamp(0.0, 1.0);
-        ((normalized * (self.max as f32 - self.min as f32)) + self.max as f32).round() as i32
+    #[test]
+    fn test_config() {
+        let (param, handle) = ParamF32::from_value(
+            -6.0,
+            -3.0,
+            -90.0,
+            12.0,
+            DEFAULT_DB_GRADIENT,
+            Unit::Decibels,
+            SecondsF64(0.02),
+            SampleRate::default(),
+            256,
+        );
+
+        let expected = ParamF32Config {
+            min: -90.0,
+            max: 12.0,
+            gradient: DEFAULT_DB_GRADIENT,
+            unit: Unit::Decibels,
+            smooth_secs: SecondsF64(0.02),
+            default_value: -3.0,
+        };
+        assert_eq!(param.config(), expected);
+        assert_eq!(handle.config(), expected);
+        assert_eq!(handle.clone().config(), expected);
+        assert_eq!(handle.downgrade().upgrade().unwrap().config(), expected);
     }
-}
 
-impl Clone for ParamI32Handle {
-    fn clone(&self) -> Self {
-        Self {
-            min: self.min,
-            max: self.max,
-            default_value: self.default_value,
-            shared: Arc::clone(&self.shared),
-        }
+    #[test]
+    fn test_is_smoothing() {
+        let (mut param, handle) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+
+        param.smoothed(256);
+        assert!(!param.is_smoothing());
+        assert!(param.reset_if_settled());
+
+        handle.set_value(1.0);
+        assert!(param.smoothed(256).is_smoothing());
+        assert!(param.is_smoothing());
+        assert!(!param.reset_if_settled());
+
+        // Run the filter until it reaches the target, but before it reports settled.
+        while (param.smoothed(64).values[63] - 1.0).abs() >= 1e-6 {}
+        assert!(param.is_smoothing());
+        assert!(param.reset_if_settled());
+        assert!(!param.is_smoothing());
+        assert_eq!(param.smoothed(64).values[0], 1.0);
+
+        let (mut param, handle) = ParamF64::from_value(
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            Gradient::Linear,
+            Unit::Generic,
+            DEFAULT_SMOOTH_SECS,
+            SampleRate::default(),
+            256,
+        );
+        handle.set_value(1.0);
+        param.smoothed(256);
+        assert!(param.is_smoothing());
+        assert!(!param.reset_if_settled());
     }
-}
 
-/// A parameter with an `bool` value.
-pub struct ParamBool {
-    shared: Arc<AtomicBool>,
-    default_value: bool,
-}
+    #[test]
+    fn test_set_smooth_secs_mid_smooth() {
+        // At 1 kHz, 1 ms is one frame.
+        let sample_rate = SampleRate(1_000.0);
+
+        for mode in [SmoothMode::Exponential, SmoothMode::Linear] {
+            let (mut param, handle) = ParamF32::from_value(
+                0.0,
+                0.0,
+                0.0,
+                1.0,
+                Gradient::Linear,
+                Unit::Generic,
+                SecondsF64(0.010),
+                sample_rate,
+                8,
+            );
+            param.set_smooth_mode(mode);
+
+            handle.set_value(1.0);
+            let before = param.smoothed(4).values[3];
+            let step = before / 4.0;
+
+            param.set_smooth_secs(SecondsF64(0.100), sample_rate);
+            let after = param.smoothed(1).values[0];
+
+            // No discontinuity, and the output moves more slowly than before.
+            assert!(after > before);
+            assert!(after - before < step);
+        }
 
-impl ParamBool {
-    /// Create a Parameter/Handle pair from its (de-normalized) boolean value.
-    ///
-    /// * value - The initial boolean value of the parameter.
-    /// * default_value - The default boolean value of the parameter.
-    pub fn from_value(value: bool, default_value: bool) -> (Self, ParamBoolHandle) {
-        let shared = Arc::new(AtomicBool::new(value));
+        // A linear ramp reaches the target in the new time from where it was.
+        let (mut param, handle) = ParamF64::from_value(
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            Gradient::Linear,
+            Unit::Generic,
+            SecondsF64(0.004),
+            sample_rate,
+            8,
+        );
+        param.set_smooth_mode(SmoothMode::Linear);
+
+        handle.set_value(1.0);
+        assert_eq!(param.smoothed(2).values[1], 0.5);
+
+        param.set_smooth_secs(SecondsF64(0.005), sample_rate);
+        let out = param.smoothed(5).values;
+        assert!((out[0] - 0.6).abs() < 1e-9);
+        assert!(out[3] < 1.0);
+        assert_eq!(out[4], 1.0);
+    }
 
-        (
-            Self {
-                shared: Arc::clone(&shared),
-                default_value,
-            },
-            ParamBoolHandle {
-                shared,
-                default_value,
-            },
-        )
+    #[test]
+    fn test_pickup_catch() {
+        let (_param, handle) = test_param_f32(0.5, 0.0, 1.0, Gradient::Linear);
+
+        // The controller starts below the current value, so it is ignored.
+        assert!(!handle.set_from_controller_with_pickup(0.1, PickupMode::Catch));
+        assert!(!handle.set_from_controller_with_pickup(0.3, PickupMode::Catch));
+        assert_eq!(handle.value(), 0.5);
+
+        // Crossing the current value engages the controller.
+        assert!(handle.set_from_controller_with_pickup(0.6, PickupMode::Catch));
+        assert_eq!(handle.value(), 0.6);
+
+        // Once engaged, it follows the controller in both directions.
+        assert!(handle.set_from_controller_with_pickup(0.2, PickupMode::Catch));
+        assert_eq!(handle.value(), 0.2);
+
+        // If the value is changed by something else, the controller must catch it again.
+        handle.set_value(0.8);
+        assert!(!handle.set_from_controller_with_pickup(0.25, PickupMode::Catch));
+        assert_eq!(handle.value(), 0.8);
+
+        // Coming close enough to the current value also engages it.
+        assert!(handle.set_from_controller_with_pickup(0.795, PickupMode::Catch));
+        assert_eq!(handle.value(), 0.795);
+
+        // A new handle (controller) starts unengaged, unless it is already on the value.
+        let other = handle.clone();
+        assert!(!other.set_from_controller_with_pickup(0.0, PickupMode::Catch));
+        assert!(other.set_from_controller_with_pickup(1.0, PickupMode::Catch));
+        assert_eq!(handle.value(), 1.0);
+
+        // Jump mode always applies the value.
+        assert!(handle.set_from_controller_with_pickup(0.0, PickupMode::Jump));
+        assert_eq!(handle.value(), 0.0);
     }
 
-    /// Create a Parameter/Handle pair from its normalized value in the range `[0.0, 1.0]`.
-    ///
-    /// * normalized - The initial normalized value of the parameter in the range `[0.0, 1.0]`.
-    /// * default_value - The (un-normalized) default boolean value of the parameter.
-    pub fn from_normalized(normalized: f32, default_value: bool) -> (Self, ParamBoolHandle) {
-        let normalized = normalized.clamp(0.0, 1.0);
-        let value = normalized >= 0.5;
+    #[test]
+    fn test_inverted_range() {
+        for gradient in [Gradient::Linear, Gradient::Power(2.0)] {
+            assert_eq!(normalized_to_value_f32(0.0, 10.0, 0.0, gradient), 10.0);
+            assert_eq!(normalized_to_value_f32(1.0, 10.0, 0.0, gradient), 0.0);
+            assert_eq!(value_to_normalized_f32(10.0, 10.0, 0.0, gradient), 0.0);
+            assert_eq!(value_to_normalized_f32(0.0, 10.0, 0.0, gradient), 1.0);
+
+            // Values out of range are clamped to the nearest end.
+            assert_eq!(value_to_normalized_f32(20.0, 10.0, 0.0, gradient), 0.0);
+            assert_eq!(value_to_normalized_f32(-5.0, 10.0, 0.0, gradient), 1.0);
+            assert_eq!(value_to_normalized_f64(20.0, 10.0, 0.0, gradient), 0.0);
+            assert_eq!(value_to_normalized_f64(-5.0, 10.0, 0.0, gradient), 1.0);
+
+            for i in 0..=10 {
+                let n = i as f32 / 10.0;
+                let v = normalized_to_value_f32(n, 10.0, 0.0, gradient);
+                assert!((value_to_normalized_f32(v, 10.0, 0.0, gradient) - n).abs() < 1e-6);
+
+                let n = f64::from(n);
+                let v = normalized_to_value_f64(n, 10.0, 0.0, gradient);
+                assert!((value_to_normalized_f64(v, 10.0, 0.0, gradient) - n).abs() < 1e-9);
+            }
+        }
 
-        Self::from_value(value, default_value)
+        assert_eq!(
+            normalized_to_value_f32(0.5, 10.0, 0.0, Gradient::Linear),
+            5.0
+        );
+        assert_eq!(
+            normalized_to_value_f32(0.5, 10.0, 0.0, Gradient::Power(2.0)),
+            7.5
+        );
+        assert!((value_to_normalized_f32(7.5, 10.0, 0.0, Gradient::Power(2.0)) - 0.5).abs() < 1e-6);
+
+        let exp = value_to_normalized_f32(10.0, 100.0, 1.0, Gradient::Exponential);
+        assert!((exp - 0.5).abs() < 1e-6);
+
+        let (param, handle) = test_param_f32(2.5, 10.0, 0.0, Gradient::Linear);
+        assert_eq!(handle.normalized(), 0.75);
+        assert_eq!(handle.value(), 2.5);
+        assert_eq!(param.normalized_to_value(0.0), 10.0);
     }
 
-    /// Set the (de-normalized) boolean value of this parameter.
-    pub fn set_value(&mut self, value: bool) {
-        self.shared.store(value, Ordering::Relaxed);
+    #[test]
+    fn test_state_round_trip() {
+        let (mut param, handle) = ParamF32::from_value(
+            -12.0,
+            0.0,
+            -90.0,
+            6.0,
+            DEFAULT_DB_GRADIENT,
+            Unit::Decibels,
+            DEFAULT_SMOOTH_SECS,
+            SampleRate::default(),
+            64,
+        );
+        handle.set_value(-6.0);
+        param.smoothed(64);
+
+        let state = param.state();
+        assert_eq!(state, handle.state());
+
+        let (restored, restored_handle) =
+            ParamF32::from_state(state, DEFAULT_SMOOTH_SECS, SampleRate::default(), 64);
+        assert_eq!(restored.state().normalized, param.state().normalized);
+        assert_eq!(restored_handle.value(), handle.value());
+        assert_eq!(restored.config(), param.config());
+        assert_eq!(
+            restored.value_to_normalized(-30.0),
+            param.value_to_normalized(-30.0)
+        );
     }
 
-    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
-    pub fn set_normalized(&mut self, normalized: f32) {
-        let normalized = normalized.clamp(0.0, 1.0);
-        let value = normalized >= 0.5;
-
-        self.set_value(value);
+    #[cfg(feature = "serde-derive")]
+    #[test]
+    fn test_state_serde_round_trip() {
+        for gradient in [
+            Gradient::Linear,
+            Gradient::Power(0.15),
+            Gradient::Exponential,
+            Gradient::SCurve(2.0),
+        ] {
+            let (param, handle) = test_param_f32(0.3, 0.01, 1.0, gradient);
+            handle.set_value(0.7);
+
+            let json = serde_json::to_string(&handle.state()).unwrap();
+            let state: ParamF32State = serde_json::from_str(&json).unwrap();
+            assert_eq!(state, handle.state());
+
+            let (restored, restored_handle) =
+                ParamF32::from_state(state, DEFAULT_SMOOTH_SECS, SampleRate::default(), 64);
+            assert_eq!(restored_handle.normalized(), handle.normalized());
+            assert_eq!(restored.gradient(), param.gradient());
+            assert_eq!(
+                restored.normalized_to_value(0.5),
+                param.normalized_to_value(0.5)
+            );
+        }
     }
 
-    /// The (un-normalized) boolean value of this parameter.
-    pub fn value(&self) -> bool {
-        self.shared.load(Ordering::Relaxed)
+    #[test]
+    fn test_change_deadband() {
+        let (mut param, handle) = test_param_f32(0.5, 0.0, 1.0, Gradient::Linear);
+        assert_eq!(param.change_deadband(), DEFAULT_CHANGE_DEADBAND_NORMALIZED);
+        param.smoothed(64);
+        assert!(!param.is_smoothing());
+
+        // A change of one ULP is ignored.
+        param.set_value(0.5 + f32::EPSILON);
+        handle.set_normalized(0.5 - f32::EPSILON);
+        assert!(!param.poll_shared());
+        assert!(!param.smoothed(64).is_smoothing());
+
+        // A real change is applied.
+        param.set_value(0.6);
+        assert!(param.smoothed(64).is_smoothing());
+        assert_eq!(handle.value(), 0.6);
+
+        // With a larger deadband, small changes from the handle are ignored too.
+        param.set_change_deadband(0.01);
+        handle.set_value(0.605);
+        assert!(!param.poll_shared());
+        handle.set_value(0.62);
+        assert!(param.poll_shared());
+
+        // With no deadband, every change is applied.
+        param.set_change_deadband(0.0);
+        param.set_normalized(0.62 + f32::EPSILON);
+        assert_eq!(handle.normalized(), 0.62 + f32::EPSILON);
     }
 
-    /// The (un-normalized) default boolean value of the parameter.
-    pub fn default_value(&self) -> bool {
-        self.default_value
+    #[test]
+    fn test_decibels_with_floor() {
+        let unit = Unit::DecibelsWithFloor(-120.0);
+        assert_eq!(unit.suffix(), "dB");
+
+        // Just below the custom floor clamps to zero, while values between the custom floor
+        // and `-90 dB` do not.
+        assert_eq!(unit.unit_to_dsp_f32(-120.01), 0.0);
+        assert_eq!(unit.unit_to_dsp_f64(-120.01), 0.0);
+        assert!(unit.unit_to_dsp_f32(-100.0) > 0.0);
+        assert_eq!(Unit::Decibels.unit_to_dsp_f32(-100.0), 0.0);
+
+        assert_eq!(unit.dsp_to_unit_f32(0.0), -120.0);
+        assert_eq!(unit.dsp_to_unit_f64(0.0), -120.0);
+        assert!((unit.dsp_to_unit_f32(unit.unit_to_dsp_f32(-100.0)) + 100.0).abs() < 0.001);
+
+        // A higher floor clamps sooner.
+        assert_eq!(Unit::DecibelsWithFloor(-60.0).unit_to_dsp_f32(-61.0), 0.0);
+
+        // `Unit::Decibels` behaves the same as a `-90 dB` floor.
+        let neg_90 = Unit::DecibelsWithFloor(-90.0);
+        for db in [-100.0, -90.0, -89.9, -12.0, 0.0, 6.0] {
+            assert_eq!(
+                neg_90.unit_to_dsp_f32(db),
+                Unit::Decibels.unit_to_dsp_f32(db)
+            );
+        }
     }
 
-    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
-    pub fn default_normalized(&self) -> f32 {
-        self.value_to_normalized(self.default_value)
+    #[test]
+    fn test_smoothed_into() {
+        let (mut param_a, handle_a) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+        let (mut param_b, handle_b) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+        let max_blocksize = param_a.smoothed.max_blocksize();
+
+        handle_a.set_value(1.0);
+        handle_b.set_value(1.0);
+
+        let mut out = [0.0; 32];
+        for _ in 0..4 {
+            assert_eq!(param_a.smoothed_into(&mut out), 32);
+            assert_eq!(&out[..], &param_b.smoothed(32).values[..32]);
+        }
+
+        // The number of frames is clamped to the maximum block size.
+        let mut out = vec![-1.0; max_blocksize + 8];
+        assert_eq!(param_a.smoothed_into(&mut out), max_blocksize);
+        assert_eq!(
+            &out[..max_blocksize],
+            &param_b.smoothed(max_blocksize).values[..max_blocksize]
+        );
+        assert!(out[max_blocksize..].iter().all(|v| *v == -1.0));
     }
 
-    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
-    /// of this parameter.
-    pub fn value_to_normalized(&self, value: bool) -> f32 {
-        if value {
-            1.0
-        } else {
-            0.0
+    #[test]
+    fn test_process_automation() {
+        let (mut param_a, _handle_a) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+        let (mut param_b, _handle_b) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+
+        // An event part-way through the block is the same as splitting the block at it.
+        let out_a = param_a.process_automation(&[(16, 1.0)], 64).values[..64].to_vec();
+        let out_b = param_b.smoothed(16).values[..16].to_vec();
+        param_b.set_normalized(1.0);
+        let out_b_2 = param_b.smoothed(48).values[..48].to_vec();
+
+        assert!(out_a[..16].iter().all(|v| *v == 0.0));
+        assert_eq!(&out_a[..16], &out_b[..]);
+        assert_eq!(&out_a[16..], &out_b_2[..]);
+        assert!(out_a[16] > 0.0);
+
+        // Events at the same offset are applied in order, and out-of-range offsets are
+        // clamped to the last frame of the block.
+        let (mut param, _handle) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+        let out = param.process_automation(&[(0, 0.5), (0, 0.0), (1_000, 1.0)], 32);
+        assert!(out.values[..31].iter().all(|v| *v == 0.0));
+        assert!(out.values[31] > 0.0);
+        assert_eq!(param.state().normalized, 1.0);
+
+        // Without events, the smoothing status goes through the same states on the same
+        // blocks as `ParamF32::smoothed()`. This includes changes that are smaller than the
+        // settling threshold of the smoothing filter.
+        for target in [1.0, 0.000_005] {
+            let (mut param_a, _handle_a) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+            let (mut param_b, _handle_b) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+            param_a.set_normalized(target);
+            param_b.set_normalized(target);
+
+            let mut statuses = Vec::new();
+            for _ in 0..1_000 {
+                let out_a = param_a.process_automation(&[], 64);
+                let (values_a, status_a) = (out_a.values[..64].to_vec(), out_a.status);
+                let out_b = param_b.smoothed(64);
+
+                assert_eq!(status_a, out_b.status);
+                assert_eq!(&values_a[..], &out_b.values[..64]);
+
+                statuses.push(status_a);
+                if status_a == SmoothStatus::Inactive {
+                    break;
+                }
+            }
+            assert!(statuses.ends_with(&[SmoothStatus::Deactivating, SmoothStatus::Inactive]));
         }
     }
 
-    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
-    /// corresponding boolean value of this parameter.
-    pub fn normalized_to_value(&self, normalized: f32) -> bool {
-        let normalized = normalized.clamp(0.0, 1.0);
-        normalized >= 0.5
+    #[test]
+    fn test_value_to_string() {
+        let (param, handle) = ParamF32::from_value(
+            -6.0,
+            0.0,
+            -90.0,
+            6.0,
+            DEFAULT_DB_GRADIENT,
+            Unit::Decibels,
+            DEFAULT_SMOOTH_SECS,
+            SampleRate::default(),
+            64,
+        );
+
+        let text = param.value_to_string(1);
+        assert_eq!(text, "-6.0 dB");
+        assert_eq!(handle.value_to_string(1), text);
+        assert_eq!(param.string_to_value(&text), Some(-6.0));
+
+        // Leading `+`, scientific notation, a missing or differently cased suffix, and extra
+        // whitespace are all accepted.
+        assert_eq!(param.string_to_value("+3"), Some(3.0));
+        assert_eq!(param.string_to_value("3"), Some(3.0));
+        assert_eq!(param.string_to_value(" -1.5e1 db "), Some(-15.0));
+        assert_eq!(handle.string_to_value("-6dB"), Some(-6.0));
+
+        assert_eq!(param.string_to_value("abc"), None);
+        assert_eq!(param.string_to_value(""), None);
+        assert_eq!(param.string_to_value("dB"), None);
+        assert_eq!(param.string_to_value("inf dB"), None);
+
+        // Units without a suffix have no trailing space.
+        let (param, _) = test_param_f32(0.25, 0.0, 1.0, Gradient::Linear);
+        assert_eq!(param.value_to_string(2), "0.25");
+        assert_eq!(Unit::Percent.format_value_f32(12.6, 0), "13 %");
     }
-}
 
-/// A handle to get and update the value of a [`ParamBool`] from a UI.
-///
-/// [`ParamBool`]: struct.ParamBool.html
-pub struct ParamBoolHandle {
-    shared: Arc<AtomicBool>,
-    default_value: bool,
-}
+    #[test]
+    fn test_param_bool_shared() {
+        let (mut param, mut handle) = ParamBool::from_value(false, true);
 
-impl ParamBoolHandle {
-    /// The (un-normalized) boolean value of this parameter.
-    pub fn value(&self) -> bool {
-        self.shared.load(Ordering::Relaxed)
+        assert!(!param.value());
+        assert_eq!(handle.normalized(), 0.0);
+        assert_eq!(param.default_normalized(), 1.0);
+
+        handle.set_value(true);
+        assert!(param.value());
+        assert_eq!(param.normalized(), 1.0);
+
+        param.set_normalized(0.25);
+        assert!(!handle.value());
+
+        handle.set_normalized(0.5);
+        assert!(param.value());
+
+        assert!(!param.normalized_to_value(0.49));
+        assert!(handle.normalized_to_value(2.0));
+        assert_eq!(handle.value_to_normalized(false), 0.0);
     }
 
-    /// The (un-normalized) default boolean value of the parameter.
-    pub fn default_value(&self) -> bool {
-        self.default_value
+    #[test]
+    fn test_poll_shared() {
+        let (mut param, handle) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+
+        assert!(!param.poll_shared());
+
+        handle.set_value(1.0);
+        assert!(param.poll_shared());
+        assert!(!param.poll_shared());
+
+        let state = param.save_state();
+        assert_eq!(state.smooth().target(), 1.0);
+        assert_eq!(state.smooth().current(), 0.0);
+
+        let out = param.smoothed(64);
+        assert!(out.values[63] > 0.0);
     }
 
-    /// The normalized default value of the parameter in the range `[0.0, 1.0]`.
-    pub fn default_normalized(&self) -> f32 {
-        self.value_to_normalized(self.default_value)
+    #[test]
+    fn test_change_notification() {
+        let (mut param, handle) = test_param_f32(0.0, 0.0, 1.0, Gradient::Linear);
+        let other = handle.clone();
+
+        assert!(!param.poll_changed());
+        assert!(!handle.take_dirty());
+
+        // A change from the handle is seen by the parameter and every handle.
+        handle.set_value(0.5);
+        assert!(param.poll_changed());
+        assert!(!param.poll_changed());
+        assert_eq!(param.peek_value(), 0.5);
+        assert!(handle.take_dirty());
+        assert!(!handle.take_dirty());
+        assert!(other.take_dirty());
+        assert!(!other.take_dirty());
+
+        // Setting the same value again is not a change.
+        handle.set_value(0.5);
+        assert!(!param.poll_changed());
+        assert!(!handle.take_dirty());
+
+        // A change from the parameter is seen by the handles.
+        param.set_value(0.25);
+        assert!(handle.take_dirty());
+        assert!(!handle.take_dirty());
+        assert!(param.poll_changed());
+        assert!(!param.poll_changed());
+
+        // Interleaved changes from both sides.
+        param.set_normalized(0.75);
+        handle.set_normalized(0.1);
+        assert!(param.poll_changed());
+        assert_eq!(param.host_get_normalized(), 0.1);
+        assert!(handle.take_dirty());
+        param.reset_from_value(0.9);
+        assert!(other.take_dirty());
+        assert!(!other.take_dirty());
+        assert!(handle.take_dirty());
+        assert!(param.poll_changed());
+
+        // Nothing changed since the last polls.
+        param.smoothed(64);
+        assert!(!param.poll_changed());
+        assert!(!handle.take_dirty());
+        assert!(!other.take_dirty());
+
+        // A new handle only sees changes made after it was created.
+        let weak = handle.downgrade();
+        let upgraded = weak.upgrade().unwrap();
+        assert!(!upgraded.take_dirty());
+        handle.set_trim_offset_normalized(0.05);
+        assert!(upgraded.take_dirty());
+        assert!(param.poll_changed());
+
+        // Every write to the shared state from the parameter side is counted.
+        param.set_trim_offset_normalized(0.1);
+        assert!(handle.take_dirty());
+        param.restore_state(param.save_state());
+        assert!(handle.take_dirty());
+        param.soft_reset_to_value(0.2, SecondsF64(0.01));
+        assert!(handle.take_dirty());
+        assert!(param.poll_changed());
+        assert!(!param.poll_changed());
+
+        // Values that are quantized on the audio side are not written back to the handle.
+        param.set_steps(Some(2));
+        assert!(handle.take_dirty());
+        assert!(param.poll_changed());
+        handle.set_normalized(0.4);
+        assert!(param.poll_changed());
+        assert_eq!(param.host_get_normalized(), 0.0);
+        assert!(handle.take_dirty());
+        assert!(!handle.take_dirty());
+        assert_eq!(handle.normalized(), 0.4);
+
+        // Values written directly to the shared atomic are picked up by the parameter.
+        param.shared_normalized().set(0.9);
+        assert!(param.poll_changed());
+        assert_eq!(param.host_get_normalized(), 1.0);
     }
 
-    /// The normalized value of this parameter in the range `[0.0, `1.0]`.
-    pub fn normalized(&self) -> f32 {
-        self.value_to_normalized(self.value())
+    #[test]
+    fn test_value_slope_at() {
+        for gradient in [
+            Gradient::Linear,
+            Gradient::Power(2.0),
+            Gradient::Power(0.5),
+            Gradient::Exponential,
+        ] {
+            let (param, _) = test_param_f32(1_000.0, 20.0, 20_000.0, gradient);
+
+            for &n in &[0.1f32, 0.25, 0.5, 0.75, 0.9] {
+                let h = 1e-3;
+                let approx = (f64::from(param.normalized_to_value(n + h))
+                    - f64::from(param.normalized_to_value(n - h)))
+                    / (2.0 * f64::from(h));
+                let slope = f64::from(param.value_slope_at(n));
+
+                assert!(
+                    ((slope - approx) / approx).abs() < 1e-3,
+                    "{:?} at {}: {} != {}",
+                    gradient,
+                    n,
+                    slope,
+                    approx
+                );
+            }
+        }
     }
 
-    /// Set the (de-normalized) boolean value of this parameter.
-    pub fn set_value(&mut self, value: bool) {
-        self.shared.store(value, Ordering::Relaxed);
+    #[test]
+    fn test_value_delta_to_normalized_at() {
+        let (param, _) = test_param_f32(1_000.0, 20.0, 20_000.0, Gradient::Exponential);
+
+        let low = param.value_delta_to_normalized_at(100.0, 200.0);
+        let high = param.value_delta_to_normalized_at(10_000.0, 200.0);
+        assert!(low > 0.0 && high > 0.0);
+        assert!(low > high * 10.0);
+
+        // Negative deltas give a negative normalized offset.
+        let down = param.value_delta_to_normalized_at(10_000.0, -200.0);
+        assert!(down < 0.0);
+
+        // A linear gradient gives the same offset anywhere in the range.
+        let (param, _) = test_param_f32(1_000.0, 20.0, 20_000.0, Gradient::Linear);
+        let low = param.value_delta_to_normalized_at(100.0, 200.0);
+        let high = param.value_delta_to_normalized_at(10_000.0, 200.0);
+        assert!((low - high).abs() < 1e-5);
+        assert!((low - 200.0 / 19_980.0).abs() < 1e-5);
     }
 
-    /// Set the normalized value of this parameter in the range `[0.0, 1.0]`.
-    pub fn set_normalized(&mut self, normalized: f32) {
-        let normalized = normalized.clamp(0.0, 1.0);
-        let value = normalized >= 0.5;
+    #[test]
+    fn test_handle_snapshot() {
+        let (_param, handle) = test_param_f32(440.0, 20.0, 20_000.0, Gradient::Exponential);
 
-        self.set_value(value);
+        handle.set_normalized(0.37);
+
+        let (normalized, value) = handle.snapshot();
+        assert_eq!(normalized, 0.37);
+        assert_eq!(value, handle.normalized_to_value(normalized));
     }
 
-    /// Convert the given value to the corresponding normalized range `[0.0, 1.0]`
-    /// of this parameter.
-    pub fn value_to_normalized(&self, value: bool) -> f32 {
-        if value {
-            1.0
-        } else {
-            0.0
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Shape {
+        Sine,
+        Triangle,
+        Saw,
+    }
+
+    impl From<Shape> for usize {
+        fn from(s: Shape) -> usize {
+            s as usize
         }
     }
 
-    /// Convert the given normalized value in the range `[0.0, 1.0]` into the
-    /// corresponding boolean value of this parameter.
-    pub fn normalized_to_value(&self, normalized: f32) -> bool {
-        let normalized = normalized.clamp(0.0, 1.0);
-        normalized >= 0.5
+    impl TryFrom<usize> for Shape {
+        type Error = ();
+
+        fn try_from(i: usize) -> Result<Self, ()> {
+            match i {
+                0 => Ok(Shape::Sine),
+                1 => Ok(Shape::Triangle),
+                2 => Ok(Shape::Saw),
+                _ => Err(()),
+            }
+        }
     }
-}
 
-impl Clone for ParamBoolHandle {
-    fn clone(&self) -> Self {
-        Self {
-            shared: Arc::clone(&self.shared),
-            default_value: self.default_value,
+    #[test]
+    fn test_param_enum() {
+        let (mut param, handle) = ParamEnum::from_value(Shape::Triangle, Shape::Sine, 3);
+
+        assert_eq!(param.value(), Shape::Triangle);
+        assert_eq!(handle.normalized(), 0.5);
+        assert_eq!(param.default_normalized(), 0.0);
+
+        assert_eq!(param.value_to_normalized(Shape::Sine), 0.0);
+        assert_eq!(param.value_to_normalized(Shape::Saw), 1.0);
+
+        // Normalized values select the nearest step.
+        for (normalized, value) in [
+            (0.0, Shape::Sine),
+            (0.24, Shape::Sine),
+            (0.26, Shape::Triangle),
+            (0.74, Shape::Triangle),
+            (0.76, Shape::Saw),
+            (1.0, Shape::Saw),
+            (-1.0, Shape::Sine),
+            (2.0, Shape::Saw),
+        ] {
+            assert_eq!(handle.normalized_to_value(normalized), value);
         }
+
+        handle.set_normalized(0.9);
+        assert_eq!(param.value(), Shape::Saw);
+        assert_eq!(param.normalized(), 1.0);
+
+        param.set_value(Shape::Sine);
+        assert_eq!(handle.value(), Shape::Sine);
+
+        let (param, _) = ParamEnum::from_normalized(0.6, Shape::Sine, 3);
+        assert_eq!(param.value(), Shape::Triangle);
     }
 }
@@ -27,6 +27,29 @@ impl SmoothStatus {
     }
 }
 
+/// How a smoother moves towards a new target value.
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub enum SmoothMode {
+    /// A one-pole low-pass filter. This is best suited for declicking.
+    #[default]
+    Exponential,
+    /// Ramp linearly from the previous value to the new target over exactly one block. This is
+    /// best suited for sample-accurate automation playback.
+    PerBlockLinear,
+    /// A fast and a slow one-pole low-pass filter running in parallel, averaged together.
+    /// This responds quickly to a change and then settles slowly, which gives a more musical
+    /// (analog-like) response for knobs.
+    ///
+    /// The slow stage uses the speed set with `SmoothF32::set_speed()`, and the fast stage
+    /// uses the speed set with `SmoothF32::set_fast_speed()`.
+    TwoStage,
+    /// Ramp linearly from the current value to each new target, reaching it in exactly the
+    /// number of frames given by the speed set with `SmoothF32::set_speed()`. Unlike
+    /// `SmoothMode::Exponential`, this has no tail, so the smoother settles as soon as the
+    /// target is reached. This is best suited for automated fades.
+    Linear,
+}
+
 pub struct SmoothOutputF32<'a> {
     pub values: &'a [f32],
     pub status: SmoothStatus,
@@ -36,6 +59,19 @@ impl<'a> SmoothOutputF32<'a> {
     pub fn is_smoothing(&self) -> bool {
         self.status.is_active()
     }
+
+    /// Returns `Some(value)` if every value in this block is the same constant `value` (the
+    /// smoother was not active), or `None` if the values may differ from frame to frame.
+    ///
+    /// This is useful for taking a fast path in DSP (i.e. applying a constant gain instead
+    /// of a per-frame gain).
+    pub fn as_constant(&self) -> Option<f32> {
+        if self.is_smoothing() {
+            None
+        } else {
+            self.values.first().copied()
+        }
+    }
 }
 
 impl<'a, I> ops::Index<I> for SmoothOutputF32<'a>
@@ -50,27 +86,82 @@ where
     }
 }
 
+/// A snapshot of the internal state of a [`SmoothF32`].
+///
+/// [`SmoothF32`]: struct.SmoothF32.html
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothStateF32 {
+    input: f32,
+    last_output: f32,
+    status: SmoothStatus,
+
+    ramp_step: f32,
+    ramp_frames_left: u64,
+
+    fast_last: f32,
+    slow_last: f32,
+}
+
+impl SmoothStateF32 {
+    /// The target value the smoother was moving towards.
+    pub fn target(&self) -> f32 {
+        self.input
+    }
+
+    /// The most recent output value of the smoother.
+    pub fn current(&self) -> f32 {
+        self.last_output
+    }
+
+    /// The status of the smoother.
+    pub fn status(&self) -> SmoothStatus {
+        self.status
+    }
+}
+
 pub struct SmoothF32 {
     output: Vec<f32>,
     input: f32,
 
     status: SmoothStatus,
+    mode: SmoothMode,
 
     a: f32,
     b: f32,
     last_output: f32,
+
+    ramp_step: f32,
+    ramp_frames_left: u64,
+
+    fast_a: f32,
+    fast_b: f32,
+    fast_last: f32,
+    slow_last: f32,
+
+    linear_frames: u64,
 }
 
 impl SmoothF32 {
     pub fn new(input: f32, max_blocksize: usize) -> Self {
         Self {
             status: SmoothStatus::Inactive,
+            mode: SmoothMode::Exponential,
             input,
             output: vec![input; max_blocksize],
 
             a: 1.0,
             b: 0.0,
             last_output: input,
+
+            ramp_step: 0.0,
+            ramp_frames_left: 0,
+
+            fast_a: 1.0,
+            fast_b: 0.0,
+            fast_last: input,
+            slow_last: input,
+
+            linear_frames: 1,
         }
     }
 
@@ -78,6 +169,8 @@ impl SmoothF32 {
         self.status = SmoothStatus::Inactive;
         self.input = val;
         self.last_output = val;
+        self.ramp_frames_left = 0;
+        self.sync_stages();
 
         let max_blocksize = self.output.len();
 
@@ -88,13 +181,36 @@ impl SmoothF32 {
     pub fn set(&mut self, val: f32) {
         self.input = val;
         self.status = SmoothStatus::Active;
+
+        if self.mode == SmoothMode::Linear {
+            self.ramp_step = (val - self.last_output) / self.linear_frames as f32;
+            self.ramp_frames_left = self.linear_frames;
+        } else {
+            self.ramp_frames_left = 0;
+        }
+    }
+
+    /// Move to the given value with a one-shot linear ramp lasting exactly `frames` frames,
+    /// instead of the usual exponential smoothing.
+    ///
+    /// Calling `SmoothF32::set()` or `SmoothF32::reset()` cancels the ramp.
+    pub fn ramp_to(&mut self, val: f32, frames: u64) {
+        if frames == 0 {
+            self.reset(val);
+            return;
+        }
+
+        self.input = val;
+        self.status = SmoothStatus::Active;
+        self.ramp_step = (val - self.last_output) / frames as f32;
+        self.ramp_frames_left = frames;
     }
 
     pub fn dest(&self) -> f32 {
         self.input
     }
 
-    pub fn output(&self) -> SmoothOutputF32 {
+    pub fn output(&self) -> SmoothOutputF32<'_> {
         SmoothOutputF32 {
             values: &self.output,
             status: self.status,
@@ -109,11 +225,11 @@ impl SmoothF32 {
         let status = self.status;
 
         match status {
-            SmoothStatus::Active => {
-                if (self.input - self.output[0]).abs() < epsilon {
-                    self.reset(self.input);
-                    self.status = SmoothStatus::Deactivating;
-                }
+            SmoothStatus::Active
+                if self.ramp_frames_left == 0 && (self.input - self.output[0]).abs() < epsilon =>
+            {
+                self.reset(self.input);
+                self.status = SmoothStatus::Deactivating;
             }
 
             SmoothStatus::Deactivating => self.status = SmoothStatus::Inactive,
@@ -125,35 +241,219 @@ impl SmoothF32 {
     }
 
     pub fn process(&mut self, frames: usize) {
-        if self.status != SmoothStatus::Active || frames == 0 {
+        self.process_at(0, frames);
+    }
+
+    /// Process `frames` frames, writing them into the output buffer starting at `offset`
+    /// instead of at the start of the buffer.
+    ///
+    /// This allows a block to be processed in several segments (i.e. to change the target
+    /// part-way through the block). The range is clamped to the size of the output buffer.
+    pub fn process_at(&mut self, offset: usize, frames: usize) {
+        let start = offset.min(self.output.len());
+        let end = offset.saturating_add(frames).min(self.output.len());
+
+        if self.status != SmoothStatus::Active || start == end {
+            return;
+        }
+
+        if self.mode == SmoothMode::PerBlockLinear && self.ramp_frames_left == 0 {
+            self.ramp_step = (self.input - self.last_output) / (end - start) as f32;
+            self.ramp_frames_left = (end - start) as u64;
+        }
+
+        if self.ramp_frames_left > 0 {
+            self.process_ramp(start, end);
+            return;
+        }
+
+        if self.mode == SmoothMode::TwoStage {
+            self.process_two_stage(start, end);
+            return;
+        }
+
+        if self.mode == SmoothMode::Linear {
+            // The ramp has already reached the target.
+            self.output[start..end].fill(self.input);
+            self.last_output = self.input;
             return;
         }
 
-        let frames = frames.min(self.output.len());
         let input = self.input * self.a;
 
-        self.output[0] = input + (self.last_output * self.b);
+        self.output[start] = input + (self.last_output * self.b);
 
-        for i in 1..frames {
+        for i in start + 1..end {
             self.output[i] = input + (self.output[i - 1] * self.b);
         }
 
-        self.last_output = self.output[frames - 1];
+        self.last_output = self.output[end - 1];
+    }
+
+    fn process_ramp(&mut self, start: usize, end: usize) {
+        let mut value = self.last_output;
+        for out in self.output[start..end].iter_mut() {
+            if self.ramp_frames_left > 1 {
+                value += self.ramp_step;
+                self.ramp_frames_left -= 1;
+            } else {
+                // Land exactly on the target to avoid accumulated error.
+                value = self.input;
+                self.ramp_frames_left = 0;
+            }
+
+            *out = value;
+        }
+
+        self.last_output = value;
+        self.sync_stages();
+    }
+
+    fn process_two_stage(&mut self, start: usize, end: usize) {
+        let slow_input = self.input * self.a;
+        let fast_input = self.input * self.fast_a;
+
+        let mut slow = self.slow_last;
+        let mut fast = self.fast_last;
+        for out in self.output[start..end].iter_mut() {
+            slow = slow_input + (slow * self.b);
+            fast = fast_input + (fast * self.fast_b);
+
+            *out = 0.5 * (slow + fast);
+        }
+
+        self.slow_last = slow;
+        self.fast_last = fast;
+        self.last_output = self.output[end - 1];
+    }
+
+    /// Bring the state of both stages of `SmoothMode::TwoStage` to the current output.
+    fn sync_stages(&mut self) {
+        self.fast_last = self.last_output;
+        self.slow_last = self.last_output;
+    }
+
+    /// Advance the state of the smoother by the given number of frames without writing
+    /// any output.
+    ///
+    /// This is useful for when processing was suspended for a while, so that the smoother
+    /// doesn't resume the ramp from where it left off.
+    pub fn skip(&mut self, frames: u64) {
+        if self.status != SmoothStatus::Active || frames == 0 {
+            return;
+        }
+
+        if self.ramp_frames_left > 0 {
+            if frames >= self.ramp_frames_left {
+                self.last_output = self.input;
+                self.ramp_frames_left = 0;
+            } else {
+                self.last_output += self.ramp_step * frames as f32;
+                self.ramp_frames_left -= frames;
+            }
+        } else if self.mode == SmoothMode::PerBlockLinear || self.mode == SmoothMode::Linear {
+            self.last_output = self.input;
+        } else if self.mode == SmoothMode::TwoStage {
+            self.slow_last =
+                self.input + ((self.slow_last - self.input) * self.b.powf(frames as f32));
+            self.fast_last =
+                self.input + ((self.fast_last - self.input) * self.fast_b.powf(frames as f32));
+            self.last_output = 0.5 * (self.slow_last + self.fast_last);
+        } else {
+            self.last_output =
+                self.input + ((self.last_output - self.input) * self.b.powf(frames as f32));
+        }
+
+        if self.mode != SmoothMode::TwoStage {
+            self.sync_stages();
+        }
+
+        let max_blocksize = self.output.len();
+
+        self.output.clear();
+        self.output.resize(max_blocksize, self.last_output);
     }
 
     pub fn is_active(&self) -> bool {
         self.status.is_active()
     }
 
+    /// If the current value has reached the target (within the settle threshold), reset the
+    /// smoother to the target so that it is immediately inactive. Returns `true` if the
+    /// smoother is inactive afterwards.
+    pub fn reset_if_settled(&mut self) -> bool {
+        if self.status.is_active()
+            && self.ramp_frames_left == 0
+            && (self.input - self.last_output).abs() < SETTLE
+        {
+            self.reset(self.input);
+        }
+
+        !self.status.is_active()
+    }
+
     pub fn set_speed(&mut self, sample_rate: SampleRate, seconds: SecondsF64) {
         self.b = (-1.0f32 / (seconds.0 as f32 * sample_rate.0 as f32)).exp();
         self.a = 1.0f32 - self.b;
+
+        self.linear_frames = seconds.to_nearest_frame_round(sample_rate).0.max(1);
+    }
+
+    /// Set the speed of the fast stage used by `SmoothMode::TwoStage`. This should be
+    /// shorter than the speed set with `SmoothF32::set_speed()`.
+    pub fn set_fast_speed(&mut self, sample_rate: SampleRate, seconds: SecondsF64) {
+        self.fast_b = (-1.0f32 / (seconds.0 as f32 * sample_rate.0 as f32)).exp();
+        self.fast_a = 1.0f32 - self.fast_b;
+    }
+
+    /// Set how this smoother moves towards a new target value.
+    ///
+    /// Note that with `SmoothMode::PerBlockLinear`, the speed set with
+    /// `SmoothF32::set_speed()` is not used.
+    pub fn set_mode(&mut self, mode: SmoothMode) {
+        if mode != self.mode {
+            self.sync_stages();
+        }
+        self.mode = mode;
+    }
+
+    pub fn mode(&self) -> SmoothMode {
+        self.mode
     }
 
     pub fn update_status(&mut self) -> SmoothStatus {
         self.update_status_with_epsilon(SETTLE)
     }
 
+    /// Capture the current internal state of the smoother.
+    pub fn state(&self) -> SmoothStateF32 {
+        SmoothStateF32 {
+            input: self.input,
+            last_output: self.last_output,
+            status: self.status,
+            ramp_step: self.ramp_step,
+            ramp_frames_left: self.ramp_frames_left,
+            fast_last: self.fast_last,
+            slow_last: self.slow_last,
+        }
+    }
+
+    /// Restore the internal state of the smoother from a previously captured state.
+    pub fn set_state(&mut self, state: SmoothStateF32) {
+        self.input = state.input;
+        self.last_output = state.last_output;
+        self.status = state.status;
+        self.ramp_step = state.ramp_step;
+        self.ramp_frames_left = state.ramp_frames_left;
+        self.fast_last = state.fast_last;
+        self.slow_last = state.slow_last;
+
+        let max_blocksize = self.output.len();
+
+        self.output.clear();
+        self.output.resize(max_blocksize, state.last_output);
+    }
+
     pub fn max_blocksize(&self) -> usize {
         self.output.len()
     }
@@ -161,7 +461,7 @@ impl SmoothF32 {
 
 impl fmt::Debug for SmoothF32 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct(concat!("SmoothF32"))
+        f.debug_struct("SmoothF32")
             .field("output[0]", &self.output[0])
             .field("max_blocksize", &self.output.len())
             .field("input", &self.input)
@@ -182,6 +482,19 @@ impl<'a> SmoothOutputF64<'a> {
     pub fn is_smoothing(&self) -> bool {
         self.status.is_active()
     }
+
+    /// Returns `Some(value)` if every value in this block is the same constant `value` (the
+    /// smoother was not active), or `None` if the values may differ from frame to frame.
+    ///
+    /// This is useful for taking a fast path in DSP (i.e. applying a constant gain instead
+    /// of a per-frame gain).
+    pub fn as_constant(&self) -> Option<f64> {
+        if self.is_smoothing() {
+            None
+        } else {
+            self.values.first().copied()
+        }
+    }
 }
 
 impl<'a, I> ops::Index<I> for SmoothOutputF64<'a>
@@ -201,22 +514,32 @@ pub struct SmoothF64 {
     input: f64,
 
     status: SmoothStatus,
+    mode: SmoothMode,
 
     a: f64,
     b: f64,
     last_output: f64,
+
+    ramp_step: f64,
+    ramp_frames_left: u64,
+    linear_frames: u64,
 }
 
 impl SmoothF64 {
     pub fn new(input: f64, max_blocksize: usize) -> Self {
         Self {
             status: SmoothStatus::Inactive,
+            mode: SmoothMode::Exponential,
             input,
             output: vec![input; max_blocksize],
 
             a: 1.0,
             b: 0.0,
             last_output: input,
+
+            ramp_step: 0.0,
+            ramp_frames_left: 0,
+            linear_frames: 1,
         }
     }
 
@@ -224,6 +547,7 @@ impl SmoothF64 {
         self.status = SmoothStatus::Inactive;
         self.input = val;
         self.last_output = val;
+        self.ramp_frames_left = 0;
 
         let max_blocksize = self.output.len();
 
@@ -234,13 +558,20 @@ impl SmoothF64 {
     pub fn set(&mut self, val: f64) {
         self.input = val;
         self.status = SmoothStatus::Active;
+
+        if self.mode == SmoothMode::Linear {
+            self.ramp_step = (val - self.last_output) / self.linear_frames as f64;
+            self.ramp_frames_left = self.linear_frames;
+        } else {
+            self.ramp_frames_left = 0;
+        }
     }
 
     pub fn dest(&self) -> f64 {
         self.input
     }
 
-    pub fn output(&self) -> SmoothOutputF64 {
+    pub fn output(&self) -> SmoothOutputF64<'_> {
         SmoothOutputF64 {
             values: &self.output,
             status: self.status,
@@ -255,11 +586,11 @@ impl SmoothF64 {
         let status = self.status;
 
         match status {
-            SmoothStatus::Active => {
-                if (self.input - self.output[0]).abs() < epsilon {
-                    self.reset(self.input);
-                    self.status = SmoothStatus::Deactivating;
-                }
+            SmoothStatus::Active
+                if self.ramp_frames_left == 0 && (self.input - self.output[0]).abs() < epsilon =>
+            {
+                self.reset(self.input);
+                self.status = SmoothStatus::Deactivating;
             }
 
             SmoothStatus::Deactivating => self.status = SmoothStatus::Inactive,
@@ -276,6 +607,12 @@ impl SmoothF64 {
         }
 
         let frames = frames.min(self.output.len());
+
+        if self.mode == SmoothMode::Linear {
+            self.process_linear(frames);
+            return;
+        }
+
         let input = self.input * self.a;
 
         self.output[0] = input + (self.last_output * self.b);
@@ -287,13 +624,60 @@ impl SmoothF64 {
         self.last_output = self.output[frames - 1];
     }
 
+    fn process_linear(&mut self, frames: usize) {
+        let mut value = self.last_output;
+        for out in self.output[..frames].iter_mut() {
+            if self.ramp_frames_left > 1 {
+                value += self.ramp_step;
+                self.ramp_frames_left -= 1;
+            } else {
+                // Land exactly on the target to avoid accumulated error.
+                value = self.input;
+                self.ramp_frames_left = 0;
+            }
+
+            *out = value;
+        }
+
+        self.last_output = value;
+    }
+
     pub fn is_active(&self) -> bool {
         self.status.is_active()
     }
 
+    /// If the current value has reached the target (within the settle threshold), reset the
+    /// smoother to the target so that it is immediately inactive. Returns `true` if the
+    /// smoother is inactive afterwards.
+    pub fn reset_if_settled(&mut self) -> bool {
+        if self.status.is_active()
+            && self.ramp_frames_left == 0
+            && (self.input - self.last_output).abs() < SETTLE as f64
+        {
+            self.reset(self.input);
+        }
+
+        !self.status.is_active()
+    }
+
     pub fn set_speed(&mut self, sample_rate: SampleRate, seconds: SecondsF64) {
-        self.b = (-1.0f64 / (seconds.0 as f64 * sample_rate.0 as f64)).exp();
+        self.b = (-1.0f64 / (seconds.0 * sample_rate.0)).exp();
         self.a = 1.0f64 - self.b;
+
+        self.linear_frames = seconds.to_nearest_frame_round(sample_rate).0.max(1);
+    }
+
+    /// Set how this smoother moves towards a new target value.
+    ///
+    /// Note that `SmoothF64` only supports `SmoothMode::Exponential` and
+    /// `SmoothMode::Linear`. Any other mode behaves like `SmoothMode::Exponential`.
+    pub fn set_mode(&mut self, mode: SmoothMode) {
+        self.mode = mode;
+        self.ramp_frames_left = 0;
+    }
+
+    pub fn mode(&self) -> SmoothMode {
+        self.mode
     }
 
     pub fn update_status(&mut self) -> SmoothStatus {
@@ -307,7 +691,7 @@ impl SmoothF64 {
 
 impl fmt::Debug for SmoothF64 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct(concat!("SmoothF64"))
+        f.debug_struct("SmoothF64")
             .field("output[0]", &self.output[0])
             .field("max_blocksize", &self.output.len())
             .field("input", &self.input)
@@ -316,3 +700,118 @@ impl fmt::Debug for SmoothF64 {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_stage() {
+        let sample_rate = SampleRate(1_000.0);
+
+        let mut slow = SmoothF32::new(0.0, 64);
+        slow.set_speed(sample_rate, SecondsF64(0.050));
+
+        let mut fast = SmoothF32::new(0.0, 64);
+        fast.set_speed(sample_rate, SecondsF64(0.005));
+
+        let mut two_stage = SmoothF32::new(0.0, 64);
+        two_stage.set_speed(sample_rate, SecondsF64(0.050));
+        two_stage.set_fast_speed(sample_rate, SecondsF64(0.005));
+        two_stage.set_mode(SmoothMode::TwoStage);
+
+        for smooth in [&mut slow, &mut fast, &mut two_stage] {
+            smooth.set(1.0);
+            smooth.process(64);
+        }
+
+        // Faster initial movement than the slow stage alone...
+        assert!(two_stage.output()[1] > 2.0 * slow.output()[1]);
+        // ...but a slower tail than the fast stage alone.
+        assert!(1.0 - two_stage.output()[63] > 10.0 * (1.0 - fast.output()[63]));
+
+        // It is the average of the two stages.
+        for i in 0..64 {
+            let expected = 0.5 * (slow.output()[i] + fast.output()[i]);
+            assert!((two_stage.output()[i] - expected).abs() < 1e-6);
+        }
+
+        // It still converges to the target.
+        for _ in 0..100 {
+            two_stage.process(64);
+            two_stage.update_status();
+        }
+        assert_eq!(two_stage.current_value(), (1.0, SmoothStatus::Inactive));
+    }
+
+    #[test]
+    fn test_linear() {
+        let sample_rate = SampleRate(1_000.0);
+
+        let mut smooth = SmoothF32::new(0.0, 8);
+        smooth.set_speed(sample_rate, SecondsF64(0.010));
+        smooth.set_mode(SmoothMode::Linear);
+
+        let mut smooth_f64 = SmoothF64::new(0.0, 8);
+        smooth_f64.set_speed(sample_rate, SecondsF64(0.010));
+        smooth_f64.set_mode(SmoothMode::Linear);
+
+        smooth.set(1.0);
+        smooth_f64.set(1.0);
+
+        // The target is reached in exactly 10 frames.
+        let mut values = Vec::new();
+        let mut values_f64 = Vec::new();
+        for _ in 0..3 {
+            smooth.process(8);
+            values.extend_from_slice(smooth.output().values);
+            smooth.update_status();
+
+            smooth_f64.process(8);
+            values_f64.extend_from_slice(smooth_f64.output().values);
+            smooth_f64.update_status();
+        }
+        for i in 0..10 {
+            assert!((values[i] - (i + 1) as f32 / 10.0).abs() < 1e-6);
+            assert!((values_f64[i] - (i + 1) as f64 / 10.0).abs() < 1e-12);
+        }
+        assert!(values[9..].iter().all(|v| *v == 1.0));
+        assert!(values_f64[9..].iter().all(|v| *v == 1.0));
+
+        // ...and then it reports settled on the first block where the output is constant.
+        assert_eq!(smooth.current_value(), (1.0, SmoothStatus::Deactivating));
+        assert_eq!(
+            smooth_f64.current_value(),
+            (1.0, SmoothStatus::Deactivating)
+        );
+        smooth.update_status();
+        smooth_f64.update_status();
+        assert!(!smooth.is_active());
+        assert!(!smooth_f64.is_active());
+    }
+
+    #[test]
+    fn test_per_block_linear() {
+        let mut smooth = SmoothF32::new(0.0, 4);
+        smooth.set_mode(SmoothMode::PerBlockLinear);
+
+        let mut prev = 0.0;
+        for target in [1.0, 0.5, 0.75, 0.75] {
+            smooth.set(target);
+            smooth.process(4);
+            smooth.update_status();
+
+            let out = smooth.output();
+            for (i, v) in out.values.iter().enumerate() {
+                let expected = prev + ((target - prev) * (i + 1) as f32 / 4.0);
+                assert!((v - expected).abs() < 1e-6);
+
+                // No overshoot.
+                assert!(*v >= f32::min(prev, target) && *v <= f32::max(prev, target));
+            }
+            assert_eq!(out.values[3], target);
+
+            prev = target;
+        }
+    }
+}
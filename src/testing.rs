@@ -0,0 +1,168 @@
+//! Conversion functions and their invariants, for use in downstream property tests
+//! (i.e. with `proptest` or `quickcheck`).
+//!
+//! This module is only available with the `testing` feature.
+//!
+//! # Invariants
+//!
+//! The following invariants hold for all valid inputs, and can be checked with the helpers
+//! in this module:
+//!
+//! * Gradient mapping - For any `min < max` and any value `v` in the range `[min, max]`,
+//!   `normalized_to_value_f32(value_to_normalized_f32(v))` is within
+//!   `GRADIENT_ROUND_TRIP_EPSILON_F32 * (max - min)` of `v`. For `Gradient::Power(exponent)`
+//!   this requires `exponent > 0.0`, and for `Gradient::Exponential` this requires
+//!   `min > 0.0`. See `gradient_round_trip_error_f32()`.
+//! * Normalized range - `value_to_normalized_f32()` always returns a value in the range
+//!   `[0.0, 1.0]`, and `normalized_to_value_f32()` always returns a value in the range
+//!   `[min, max]`.
+//! * Decibels - For any `db` in the range `[-90.0, 24.0]`,
+//!   `coeff_to_db_f32(db_to_coeff_f32(db))` is within `DB_ROUND_TRIP_EPSILON_F32` of `db`.
+//! * Sample rates - For any of the common sample rates and any [`FrameTime`] up to and
+//!   including `max_exact_round_trip_frame()` (the last frame whose time in seconds fits in
+//!   [`SuperclockTime`], about 136 years), converting to [`SuperclockTime`] and back is
+//!   exact. Larger frames saturate to the maximum [`SuperclockTime`].
+//!
+//! [`FrameTime`]: ../time/struct.FrameTime.html
+//! [`SuperclockTime`]: ../time/struct.SuperclockTime.html
+
+pub use crate::decibel::{coeff_to_db_f32, coeff_to_db_f64, db_to_coeff_f32, db_to_coeff_f64};
+pub use crate::parameter::{
+    normalized_to_value_f32, normalized_to_value_f64, normalized_to_value_i32,
+    value_to_normalized_f32, value_to_normalized_f64, value_to_normalized_i32, Gradient,
+};
+
+use crate::time::{FrameTime, SampleRate, SuperclockTime};
+
+/// The maximum error of a gradient mapping round trip, relative to the range of the
+/// parameter.
+pub const GRADIENT_ROUND_TRIP_EPSILON_F32: f32 = 1.0e-4;
+
+/// The maximum error of a decibel round trip, in decibels.
+pub const DB_ROUND_TRIP_EPSILON_F32: f32 = 1.0e-3;
+
+/// The common sample rates for which [`FrameTime`] to [`SuperclockTime`] conversions are
+/// exact.
+///
+/// [`FrameTime`]: ../time/struct.FrameTime.html
+/// [`SuperclockTime`]: ../time/struct.SuperclockTime.html
+pub const COMMON_SAMPLE_RATES: [f64; 10] = [
+    22_050.0, 24_000.0, 44_100.0, 48_000.0, 88_200.0, 96_000.0, 176_400.0, 192_000.0, 352_800.0,
+    384_000.0,
+];
+
+/// The error of a gradient mapping round trip from the given value, relative to the range
+/// of the parameter.
+pub fn gradient_round_trip_error_f32(value: f32, min: f32, max: f32, gradient: Gradient) -> f32 {
+    let normalized = value_to_normalized_f32(value, min, max, gradient);
+    let round_trip = normalized_to_value_f32(normalized, min, max, gradient);
+
+    (round_trip - value).abs() / (max - min)
+}
+
+/// The error of a decibel round trip from the given value, in decibels.
+pub fn db_round_trip_error_f32(db: f32) -> f32 {
+    (coeff_to_db_f32(db_to_coeff_f32(db)) - db).abs()
+}
+
+/// The largest [`FrameTime`] that can be converted to [`SuperclockTime`] and back at the given
+/// sample rate without saturating.
+///
+/// [`FrameTime`]: ../time/struct.FrameTime.html
+/// [`SuperclockTime`]: ../time/struct.SuperclockTime.html
+pub fn max_exact_round_trip_frame(sample_rate: SampleRate) -> FrameTime {
+    FrameTime(((u64::from(u32::MAX) + 1) * sample_rate.0 as u64) - 1)
+}
+
+/// Returns `true` if converting the given [`FrameTime`] to [`SuperclockTime`] and back at the
+/// given sample rate gives the same frame.
+///
+/// [`FrameTime`]: ../time/struct.FrameTime.html
+/// [`SuperclockTime`]: ../time/struct.SuperclockTime.html
+pub fn frame_round_trip_is_exact(frame: FrameTime, sample_rate: SampleRate) -> bool {
+    SuperclockTime::from_frame(frame, sample_rate).to_nearest_frame_round(sample_rate) == frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A tiny deterministic pseudo-random generator (xorshift), so the property tests below
+    /// are reproducible without any extra dependencies.
+    struct Rng(u32);
+
+    impl Rng {
+        fn next_f32(&mut self) -> f32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 17;
+            self.0 ^= self.0 << 5;
+            (self.0 >> 8) as f32 / (1u32 << 24) as f32
+        }
+
+        fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+            min + ((max - min) * self.next_f32())
+        }
+    }
+
+    #[test]
+    fn test_power_gradient_round_trip_property() {
+        let mut rng = Rng(0x9E37_79B9);
+
+        for _ in 0..10_000 {
+            let exponent = rng.range_f32(0.1, 4.0);
+            let min = rng.range_f32(-1_000.0, 1_000.0);
+            let max = min + rng.range_f32(0.01, 2_000.0);
+            let value = rng.range_f32(min, max);
+            let gradient = Gradient::Power(exponent);
+
+            let normalized = value_to_normalized_f32(value, min, max, gradient);
+            assert!((0.0..=1.0).contains(&normalized));
+
+            let error = gradient_round_trip_error_f32(value, min, max, gradient);
+            assert!(
+                error <= GRADIENT_ROUND_TRIP_EPSILON_F32,
+                "value: {}, min: {}, max: {}, exponent: {}, error: {}",
+                value,
+                min,
+                max,
+                exponent,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_db_round_trip_property() {
+        let mut rng = Rng(0x1234_5678);
+
+        for _ in 0..10_000 {
+            let db = rng.range_f32(-90.0, 24.0);
+            assert!(db_round_trip_error_f32(db) <= DB_ROUND_TRIP_EPSILON_F32);
+        }
+    }
+
+    #[test]
+    fn test_frame_round_trip_property() {
+        let mut rng = Rng(0xDEAD_BEEF);
+
+        for sample_rate in COMMON_SAMPLE_RATES {
+            for _ in 0..1_000 {
+                let frame = FrameTime((rng.next_f32() * 1.0e9) as u64);
+                assert!(frame_round_trip_is_exact(frame, SampleRate(sample_rate)));
+            }
+
+            // The invariant holds right up to the limit. A second past it, the time saturates.
+            let limit = max_exact_round_trip_frame(SampleRate(sample_rate));
+            for frame in (limit.0 - 1_000)..=limit.0 {
+                assert!(frame_round_trip_is_exact(
+                    FrameTime(frame),
+                    SampleRate(sample_rate)
+                ));
+            }
+            assert!(!frame_round_trip_is_exact(
+                FrameTime(limit.0 + sample_rate as u64),
+                SampleRate(sample_rate)
+            ));
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Helpers for searching sorted lists of timestamped events.
+
+/// Returns the number of events with a time less than or equal to `t`.
+///
+/// This is also the index of the first event with a time greater than `t`.
+///
+/// `events` must be sorted by time in ascending order.
+pub fn partition_point_le<Time: PartialOrd, T>(events: &[(Time, T)], t: Time) -> usize {
+    events.partition_point(|(event_t, _)| *event_t <= t)
+}
+
+/// Returns the number of events with a time less than `t`.
+///
+/// This is also the index of the first event with a time greater than or equal to `t`.
+///
+/// `events` must be sorted by time in ascending order.
+pub fn partition_point_lt<Time: PartialOrd, T>(events: &[(Time, T)], t: Time) -> usize {
+    events.partition_point(|(event_t, _)| *event_t < t)
+}
+
+/// Returns the slice of events with a time in the range `[block_start, block_end)`.
+///
+/// An event lying exactly on `block_end` belongs to the *next* block, not this one.
+///
+/// `events` must be sorted by time in ascending order.
+pub fn range_in_block<Time: PartialOrd, T>(
+    events: &[(Time, T)],
+    block_start: Time,
+    block_end: Time,
+) -> &[(Time, T)] {
+    let start = partition_point_lt(events, block_start);
+    let end = partition_point_lt(events, block_end).max(start);
+
+    &events[start..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::FrameTime;
+
+    #[test]
+    fn test_events_on_block_boundaries() {
+        let events = [
+            (FrameTime(0), 'a'),
+            (FrameTime(64), 'b'),
+            (FrameTime(64), 'c'),
+            (FrameTime(100), 'd'),
+            (FrameTime(128), 'e'),
+        ];
+
+        assert_eq!(partition_point_le(&events, FrameTime(63)), 1);
+        assert_eq!(partition_point_le(&events, FrameTime(64)), 3);
+        assert_eq!(partition_point_lt(&events, FrameTime(64)), 1);
+
+        let block: Vec<char> = range_in_block(&events, FrameTime(0), FrameTime(64))
+            .iter()
+            .map(|(_, e)| *e)
+            .collect();
+        assert_eq!(block, vec!['a']);
+
+        let block: Vec<char> = range_in_block(&events, FrameTime(64), FrameTime(128))
+            .iter()
+            .map(|(_, e)| *e)
+            .collect();
+        assert_eq!(block, vec!['b', 'c', 'd']);
+
+        let block: Vec<char> = range_in_block(&events, FrameTime(128), FrameTime(192))
+            .iter()
+            .map(|(_, e)| *e)
+            .collect();
+        assert_eq!(block, vec!['e']);
+
+        assert!(range_in_block(&events, FrameTime(192), FrameTime(256)).is_empty());
+    }
+}
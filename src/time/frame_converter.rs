@@ -0,0 +1,114 @@
+use super::superclock_time::exact_ticks_per_frame;
+use super::{FrameTime, SampleRate, SecondsF64, SuperclockTime};
+
+/// Converts between [`FrameTime`] and [`SuperclockTime`] for a fixed [`SampleRate`], with
+/// the conversion factor computed once up front.
+///
+/// This is useful for converting many values at once (i.e. buffer lengths or recorded
+/// events), where `SuperclockTime::from_frame()` would otherwise look up the factor on every
+/// call.
+///
+/// [`FrameTime`]: struct.FrameTime.html
+/// [`SuperclockTime`]: struct.SuperclockTime.html
+/// [`SampleRate`]: struct.SampleRate.html
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameConverter {
+    sample_rate: SampleRate,
+    /// The number of super-sample ticks per frame, or `0` if the sample rate does not
+    /// evenly divide `SUPER_SAMPLE_TICKS_PER_SECOND`.
+    ticks_per_frame: u64,
+}
+
+impl FrameConverter {
+    pub fn new(sample_rate: SampleRate) -> Self {
+        Self {
+            sample_rate,
+            ticks_per_frame: exact_ticks_per_frame(sample_rate).map_or(0, u64::from),
+        }
+    }
+
+    pub fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    /// Returns `true` if the sample rate evenly divides `SUPER_SAMPLE_TICKS_PER_SECOND`, in
+    /// which case `FrameConverter::to_super()` is lossless (this is true for all of the
+    /// common sample rates).
+    pub fn is_exact(&self) -> bool {
+        self.ticks_per_frame != 0
+    }
+
+    /// Convert the time in [`FrameTime`] to [`SuperclockTime`].
+    ///
+    /// This is lossless if `FrameConverter::is_exact()` returns `true`, and gives the same
+    /// result as `SuperclockTime::from_frame()` for all of the common sample rates.
+    ///
+    /// [`FrameTime`]: struct.FrameTime.html
+    /// [`SuperclockTime`]: struct.SuperclockTime.html
+    pub fn to_super(&self, frame: FrameTime) -> SuperclockTime {
+        if self.is_exact() {
+            SuperclockTime::from_total_ticks(frame.0.saturating_mul(self.ticks_per_frame))
+        } else {
+            SuperclockTime::from_seconds_f64(SecondsF64(frame.0 as f64 / self.sample_rate.0))
+        }
+    }
+
+    /// Convert the time in [`SuperclockTime`] to [`FrameTime`], rounded to the nearest frame.
+    ///
+    /// If `FrameConverter::is_exact()` returns `true`, then this is computed with integer
+    /// math, so times on an exact frame always convert back to that frame.
+    ///
+    /// Note that this conversion is *NOT* lossless.
+    ///
+    /// [`FrameTime`]: struct.FrameTime.html
+    /// [`SuperclockTime`]: struct.SuperclockTime.html
+    pub fn to_frames(&self, time: SuperclockTime) -> FrameTime {
+        if self.is_exact() {
+            FrameTime((time.total_ticks() + (self.ticks_per_frame / 2)) / self.ticks_per_frame)
+        } else {
+            time.to_nearest_frame_round(self.sample_rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::SUPER_SAMPLE_TICKS_PER_SECOND;
+
+    #[test]
+    fn test_matches_per_call_conversion() {
+        let frames = [0, 1, 441, 47_999, 48_000, 1_234_567, 10_000_000_000];
+
+        for sample_rate in [22_050.0, 44_100.0, 48_000.0, 96_000.0, 384_000.0, 50_000.0] {
+            let sample_rate = SampleRate(sample_rate);
+            let converter = FrameConverter::new(sample_rate);
+            assert_eq!(converter.is_exact(), sample_rate.0 != 50_000.0);
+
+            for frame in frames {
+                let frame = FrameTime(frame);
+                let time = converter.to_super(frame);
+
+                assert_eq!(time, SuperclockTime::from_frame(frame, sample_rate));
+                assert_eq!(converter.to_frames(time), frame);
+                assert_eq!(
+                    converter.to_frames(time),
+                    time.to_nearest_frame_round(sample_rate)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact_uncommon_rate() {
+        // 32 kHz is not one of the common sample rates, but it still divides the super clock.
+        let converter = FrameConverter::new(SampleRate(32_000.0));
+        assert!(converter.is_exact());
+        assert_eq!(
+            converter.to_super(FrameTime(32_001)),
+            SuperclockTime::new(1, SUPER_SAMPLE_TICKS_PER_SECOND / 32_000)
+        );
+
+        assert!(!FrameConverter::new(SampleRate(44_100.5)).is_exact());
+    }
+}
@@ -1,14 +1,19 @@
-use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+
+use std::convert::TryFrom;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
+use std::time::Duration;
 
 use super::{MusicalTime, SampleRate, SecondsF64, SuperclockTime};
 
 /// Unit of time length in frames (samples in a single audio channel).
 #[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Hash)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Hash)]
 pub struct FrameTime(pub u64);
 
 impl FrameTime {
-    pub fn new(frame: u64) -> Self {
+    pub const fn new(frame: u64) -> Self {
         Self(frame)
     }
 
@@ -35,8 +40,8 @@ impl FrameTime {
 
     /// Convert to the corresponding time length in [`SuperclockTime`] from the given [`SampleRate`].
     ///
-    /// This conversion **IS** lossless if the sample rate happens to be equal to one of the common
-    /// sample rates: `22050, 24000, 44100, 48000, 88200, 96000, 176400, or 192000`. This
+    /// This conversion **IS** lossless if the sample rate is a whole number that evenly divides
+    /// `SUPER_SAMPLE_TICKS_PER_SECOND` (which includes all of the common sample rates). This
     /// conversion is *NOT* lossless otherwise.
     ///
     /// [`SuperclockTime`]: struct.SuperclockTime.html
@@ -44,11 +49,100 @@ impl FrameTime {
     pub fn to_super_frame(&self, sample_rate: SampleRate) -> SuperclockTime {
         SuperclockTime::from_frame(*self, sample_rate)
     }
-}
 
-impl Default for FrameTime {
-    fn default() -> Self {
-        FrameTime(0)
+    /// The number of frames as a `usize` (i.e. for indexing into a buffer). Returns `None` if
+    /// the value does not fit in a `usize` (which can only happen on 32-bit platforms).
+    pub fn as_usize(&self) -> Option<usize> {
+        usize::try_from(self.0).ok()
+    }
+
+    /// The number of frames as a `usize` (i.e. for indexing into a buffer), clamped to
+    /// `usize::MAX` if the value does not fit (which can only happen on 32-bit platforms).
+    ///
+    /// Note that the caller is still responsible for deciding whether clamping is correct for
+    /// what the value is used for. A clamped value will still be out of bounds for any buffer.
+    pub fn as_usize_clamped(&self) -> usize {
+        self.as_usize().unwrap_or(usize::MAX)
+    }
+
+    /// Align down to the nearest multiple of `block` (i.e. the start of the block containing
+    /// this time).
+    ///
+    /// If `block` is zero, then this time is returned unchanged.
+    pub fn align_down(&self, block: FrameTime) -> FrameTime {
+        if block.0 == 0 {
+            return *self;
+        }
+
+        Self(self.0 - (self.0 % block.0))
+    }
+
+    /// Align up to the nearest multiple of `block` (i.e. the start of the next block, unless
+    /// this time already lies on a block boundary).
+    ///
+    /// If `block` is zero, then this time is returned unchanged.
+    pub fn align_up(&self, block: FrameTime) -> FrameTime {
+        if block.0 == 0 {
+            return *self;
+        }
+
+        Self(self.0.div_ceil(block.0) * block.0)
+    }
+
+    /// The offset of this time from the start of a block, if this time lies within the block
+    /// `[block_start, block_start + frames)`. Returns `None` if this time lies before or after
+    /// the block.
+    ///
+    /// An event lying exactly on the end of the block belongs to the *next* block, not this
+    /// one.
+    ///
+    /// * `block_start` - The time of the first frame in the block.
+    /// * `frames` - The number of frames in the block.
+    pub fn frame_offset_in_block(&self, block_start: FrameTime, frames: usize) -> Option<usize> {
+        let offset = self.0.checked_sub(block_start.0)?;
+        if offset < frames as u64 {
+            Some(offset as usize)
+        } else {
+            None
+        }
+    }
+
+    /// The wall-clock [`Duration`] from `now` until this time (i.e. for scheduling a UI
+    /// animation in sync with an upcoming audio event).
+    ///
+    /// Returns `None` if this time lies before `now`, or if the sample rate is invalid.
+    ///
+    /// Note that this conversion is *NOT* lossless.
+    ///
+    /// * `now` - The current time (i.e. the frame at the playhead).
+    /// * `sample_rate` - The sample rate of the stream.
+    ///
+    /// [`Duration`]: https://doc.rust-lang.org/std/time/struct.Duration.html
+    pub fn duration_from(&self, now: FrameTime, sample_rate: SampleRate) -> Option<Duration> {
+        let frames = self.0.checked_sub(now.0)?;
+        Duration::try_from_secs_f64(FrameTime(frames).to_seconds_f64(sample_rate).0).ok()
+    }
+
+    /// Try adding `rhs` to self. This will return `None` if the result does not fit in a
+    /// `u64` (overflow).
+    pub fn checked_add(self, rhs: FrameTime) -> Option<FrameTime> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+
+    /// Try subtracting `rhs` from self. This will return `None` if the resulting value
+    /// is negative due to `rhs` being larger than self (overflow).
+    pub fn checked_sub(self, rhs: FrameTime) -> Option<FrameTime> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    /// Add `rhs` to self, returning `FrameTime(u64::MAX)` if the result would overflow.
+    pub fn saturating_add(self, rhs: FrameTime) -> FrameTime {
+        Self(self.0.saturating_add(rhs.0))
+    }
+
+    /// Subtract `rhs` from self, returning a time of zero if the result would be negative.
+    pub fn saturating_sub(self, rhs: FrameTime) -> FrameTime {
+        Self(self.0.saturating_sub(rhs.0))
     }
 }
 
@@ -97,6 +191,20 @@ impl Mul<u64> for FrameTime {
     }
 }
 
+/// The remainder of dividing `self` by `rhs` (i.e. for wrapping a time around a loop length).
+///
+/// Because `FrameTime` is unsigned, this is the same as the Euclidean remainder.
+///
+/// # Panics
+///
+/// This will panic if `rhs` is zero.
+impl Rem<FrameTime> for FrameTime {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self::Output {
+        Self(self.0 % rhs.0)
+    }
+}
+
 impl AddAssign<FrameTime> for FrameTime {
     fn add_assign(&mut self, other: Self) {
         self.0 += other.0;
@@ -112,3 +220,112 @@ impl MulAssign<u64> for FrameTime {
         *self = *self * other
     }
 }
+impl RemAssign<FrameTime> for FrameTime {
+    fn rem_assign(&mut self, other: Self) {
+        self.0 %= other.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_as_usize() {
+        assert_eq!(FrameTime(0).as_usize(), Some(0));
+        assert_eq!(FrameTime(1_024).as_usize(), Some(1_024));
+        assert_eq!(FrameTime(1_024).as_usize_clamped(), 1_024);
+
+        assert_eq!(
+            FrameTime(u64::MAX).as_usize(),
+            usize::try_from(u64::MAX).ok()
+        );
+        assert_eq!(FrameTime(u64::MAX).as_usize_clamped(), usize::MAX);
+    }
+
+    #[test]
+    fn test_align() {
+        let block = FrameTime(128);
+
+        assert_eq!(FrameTime(300).align_down(block), FrameTime(256));
+        assert_eq!(FrameTime(300).align_up(block), FrameTime(384));
+
+        // Times exactly on a boundary are unchanged.
+        assert_eq!(FrameTime(256).align_down(block), FrameTime(256));
+        assert_eq!(FrameTime(256).align_up(block), FrameTime(256));
+
+        assert_eq!(FrameTime(0).align_up(block), FrameTime(0));
+        assert_eq!(FrameTime(5).align_up(FrameTime(0)), FrameTime(5));
+    }
+
+    #[test]
+    fn test_frame_offset_in_block() {
+        let block_start = FrameTime(1_024);
+
+        // Before the block.
+        assert_eq!(FrameTime(0).frame_offset_in_block(block_start, 256), None);
+        assert_eq!(
+            FrameTime(1_023).frame_offset_in_block(block_start, 256),
+            None
+        );
+
+        // Inside the block.
+        assert_eq!(
+            FrameTime(1_024).frame_offset_in_block(block_start, 256),
+            Some(0)
+        );
+        assert_eq!(
+            FrameTime(1_100).frame_offset_in_block(block_start, 256),
+            Some(76)
+        );
+        assert_eq!(
+            FrameTime(1_279).frame_offset_in_block(block_start, 256),
+            Some(255)
+        );
+
+        // After the block.
+        assert_eq!(
+            FrameTime(1_280).frame_offset_in_block(block_start, 256),
+            None
+        );
+        assert_eq!(FrameTime(1_024).frame_offset_in_block(block_start, 0), None);
+    }
+
+    #[test]
+    fn test_duration_from() {
+        let sample_rate = SampleRate(48_000.0);
+        let now = FrameTime(96_000);
+
+        assert_eq!(
+            FrameTime(120_000).duration_from(now, sample_rate),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(now.duration_from(now, sample_rate), Some(Duration::ZERO));
+        assert_eq!(FrameTime(95_999).duration_from(now, sample_rate), None);
+
+        assert_eq!(FrameTime(120_000).duration_from(now, SampleRate(0.0)), None);
+    }
+
+    #[test]
+    fn test_checked_saturating() {
+        let max = FrameTime(u64::MAX);
+
+        assert_eq!(FrameTime(5).checked_add(FrameTime(3)), Some(FrameTime(8)));
+        assert_eq!(max.checked_add(FrameTime(1)), None);
+        assert_eq!(max.saturating_add(FrameTime(1)), max);
+        assert_eq!(FrameTime(u64::MAX - 1).saturating_add(FrameTime(1)), max);
+
+        assert_eq!(FrameTime(5).checked_sub(FrameTime(3)), Some(FrameTime(2)));
+        assert_eq!(FrameTime(3).checked_sub(FrameTime(5)), None);
+        assert_eq!(FrameTime(3).saturating_sub(FrameTime(5)), FrameTime(0));
+    }
+
+    #[test]
+    fn test_rem() {
+        assert_eq!(FrameTime(1_000) % FrameTime(300), FrameTime(1_000 % 300));
+
+        let mut t = FrameTime(1_000);
+        t %= FrameTime(1_000);
+        assert_eq!(t, FrameTime(0));
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+
+use super::{MusicalTime, NoteValue};
+
+/// A swing groove, which delays every other step of a grid (the off-beats) while leaving the
+/// steps in between (the downbeats) untouched.
+///
+/// This uses the same definition of swing as `MusicalTime::swung_step_positions()`.
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Groove {
+    /// The amount of swing in the range `[0.0, 1.0]`. Each off-beat is delayed by `amount`
+    /// times half of the length of a step, so `0.0` is no swing and `1.0` places each
+    /// off-beat halfway between its straight position and the next downbeat.
+    pub amount: f64,
+    /// The length of a step (i.e. `NoteValue::Straight(16)` for 16th-note swing). Every odd
+    /// step counted from time `0` is an off-beat.
+    pub subdivision: NoteValue,
+}
+
+impl Groove {
+    pub fn new(amount: f64, subdivision: NoteValue) -> Self {
+        Self {
+            amount,
+            subdivision,
+        }
+    }
+
+    /// Apply this groove to the straight musical time `t`, rounded to the nearest tick.
+    ///
+    /// Times on a downbeat are unchanged, and times on an off-beat are delayed by the full
+    /// swing amount. Times in between are stretched (or squashed) linearly, so the order of
+    /// events is always preserved.
+    pub fn apply(&self, t: MusicalTime) -> MusicalTime {
+        let step = u128::from(self.subdivision.to_musical().total_ticks());
+        let amount = self.amount.clamp(0.0, 1.0);
+        if step == 0 || amount == 0.0 {
+            return t;
+        }
+
+        let delay = (amount * (step as f64 / 2.0)).round() as u128;
+
+        let ticks = u128::from(t.total_ticks());
+        let pos = ticks % (step * 2);
+        let pair_start = ticks - pos;
+
+        let swung_pos = if pos <= step {
+            ((pos * (step + delay)) + (step / 2)) / step
+        } else {
+            (step + delay) + ((((pos - step) * (step - delay)) + (step / 2)) / step)
+        };
+
+        MusicalTime::from_total_ticks((pair_start + swung_pos).min(u128::from(u64::MAX)) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::TimeSignature;
+
+    #[test]
+    fn test_groove_matches_swung_steps() {
+        let ts = TimeSignature::new(4, 4);
+        let groove = Groove::new(0.6, NoteValue::Straight(16));
+
+        let straight = MusicalTime::swung_step_positions(2, 16, 0.0, ts);
+        let swung = MusicalTime::swung_step_positions(2, 16, 0.6, ts);
+
+        for (t, expected) in straight.iter().zip(swung.iter()) {
+            assert_eq!(groove.apply(*t), *expected);
+        }
+
+        // Times between the steps keep their order.
+        let sixty_fourth = MusicalTime::from_fractional_beats::<16>(0, 1);
+        let mut last = MusicalTime::default();
+        for i in 0..64 {
+            let t = groove.apply(MusicalTime::from_total_ticks(
+                sixty_fourth.total_ticks() * i,
+            ));
+            assert!(t >= last);
+            last = t;
+        }
+    }
+}
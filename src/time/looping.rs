@@ -0,0 +1,227 @@
+use super::{FrameTime, MusicalTime, SampleRate};
+
+/// An iterator that advances a playhead through a looped region, block by block.
+///
+/// Each item is a `(musical_pos, frames, wrapped)` tuple describing a contiguous chunk of
+/// frames to process:
+///
+/// * `musical_pos` - The position of the playhead at the start of this chunk.
+/// * `frames` - The number of frames in this chunk.
+/// * `wrapped` - `true` if the playhead jumped back to the start of the loop right before
+///   this chunk.
+///
+/// A chunk never straddles a block boundary or the end of the loop, so a block that crosses
+/// the end of the loop (possibly multiple times if the loop is shorter than the block) is
+/// split into multiple chunks. The `frames` of all chunks belonging to a single block always
+/// add up to `block_size`.
+///
+/// If the playhead starts at or after the end of the loop, or if the loop has a length of
+/// zero, then the playhead will simply advance without ever wrapping.
+///
+/// Note that this iterator never ends.
+#[derive(Debug, Clone)]
+pub struct LoopingTransportIter {
+    loop_start: MusicalTime,
+    loop_start_frame: FrameTime,
+    loop_end_frame: FrameTime,
+
+    playhead: FrameTime,
+    frames_left_in_block: usize,
+    wrapped: bool,
+
+    block_size: usize,
+    bpm: f64,
+    sample_rate: SampleRate,
+}
+
+impl LoopingTransportIter {
+    /// * `loop_start` - The start of the loop region (inclusive).
+    /// * `loop_end` - The end of the loop region (exclusive).
+    /// * `playhead` - The initial position of the playhead.
+    /// * `block_size` - The number of frames in each processing block. This must be greater
+    ///   than `0`.
+    /// * `bpm` - The tempo in beats per minute.
+    /// * `sample_rate` - The sample rate of the stream.
+    pub fn new(
+        loop_start: MusicalTime,
+        loop_end: MusicalTime,
+        playhead: MusicalTime,
+        block_size: usize,
+        bpm: f64,
+        sample_rate: SampleRate,
+    ) -> Self {
+        assert!(block_size > 0);
+
+        Self {
+            loop_start,
+            loop_start_frame: loop_start.to_nearest_frame_round(bpm, sample_rate),
+            loop_end_frame: loop_end.to_nearest_frame_round(bpm, sample_rate),
+
+            playhead: playhead.to_nearest_frame_round(bpm, sample_rate),
+            frames_left_in_block: block_size,
+            wrapped: false,
+
+            block_size,
+            bpm,
+            sample_rate,
+        }
+    }
+
+    /// The current position of the playhead.
+    pub fn playhead(&self) -> MusicalTime {
+        if self.playhead == self.loop_start_frame {
+            self.loop_start
+        } else {
+            self.playhead.to_musical(self.bpm, self.sample_rate)
+        }
+    }
+
+    /// The current position of the playhead in frames.
+    pub fn playhead_frame(&self) -> FrameTime {
+        self.playhead
+    }
+
+    /// The number of frames in each processing block.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    fn will_wrap(&self) -> bool {
+        self.loop_end_frame > self.loop_start_frame && self.playhead < self.loop_end_frame
+    }
+}
+
+impl Iterator for LoopingTransportIter {
+    type Item = (MusicalTime, usize, bool);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut frames = self.frames_left_in_block;
+        if self.will_wrap() {
+            let frames_until_end = self.loop_end_frame.0 - self.playhead.0;
+            if frames_until_end < frames as u64 {
+                frames = frames_until_end as usize;
+            }
+        }
+
+        let item = (self.playhead(), frames, self.wrapped);
+
+        let will_wrap = self.will_wrap();
+
+        self.playhead += FrameTime(frames as u64);
+        self.wrapped = false;
+
+        if will_wrap && self.playhead == self.loop_end_frame {
+            self.playhead = self.loop_start_frame;
+            self.wrapped = true;
+        }
+
+        self.frames_left_in_block -= frames;
+        if self.frames_left_in_block == 0 {
+            self.frames_left_in_block = self.block_size;
+        }
+
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loop_shorter_than_block() {
+        // One beat at 120 BPM and 48 kHz is 24,000 frames.
+        let mut iter = LoopingTransportIter::new(
+            MusicalTime::from_beats(0),
+            MusicalTime::from_beats(1),
+            MusicalTime::from_beats(0),
+            64_000,
+            120.0,
+            SampleRate(48_000.0),
+        );
+
+        // First block wraps twice.
+        assert_eq!(
+            iter.next(),
+            Some((MusicalTime::from_beats(0), 24_000, false))
+        );
+        assert_eq!(
+            iter.next(),
+            Some((MusicalTime::from_beats(0), 24_000, true))
+        );
+        assert_eq!(
+            iter.next(),
+            Some((MusicalTime::from_beats(0), 16_000, true))
+        );
+
+        // Second block picks up in the middle of the loop.
+        let (pos, frames, wrapped) = iter.next().unwrap();
+        assert_eq!(
+            pos,
+            FrameTime(16_000).to_musical(120.0, SampleRate(48_000.0))
+        );
+        assert_eq!(frames, 8_000);
+        assert!(!wrapped);
+
+        assert_eq!(
+            iter.next(),
+            Some((MusicalTime::from_beats(0), 24_000, true))
+        );
+        assert_eq!(
+            iter.next(),
+            Some((MusicalTime::from_beats(0), 24_000, true))
+        );
+        assert_eq!(iter.next(), Some((MusicalTime::from_beats(0), 8_000, true)));
+    }
+
+    #[test]
+    fn test_exact_boundary_wrap() {
+        let mut iter = LoopingTransportIter::new(
+            MusicalTime::from_beats(4),
+            MusicalTime::from_beats(6),
+            MusicalTime::from_beats(4),
+            24_000,
+            120.0,
+            SampleRate(48_000.0),
+        );
+
+        assert_eq!(
+            iter.next(),
+            Some((MusicalTime::from_beats(4), 24_000, false))
+        );
+        assert_eq!(
+            iter.next(),
+            Some((MusicalTime::from_beats(5), 24_000, false))
+        );
+        // The loop end falls exactly on a block boundary, so no block is split.
+        assert_eq!(
+            iter.next(),
+            Some((MusicalTime::from_beats(4), 24_000, true))
+        );
+        assert_eq!(
+            iter.next(),
+            Some((MusicalTime::from_beats(5), 24_000, false))
+        );
+    }
+
+    #[test]
+    fn test_playhead_after_loop_end_does_not_wrap() {
+        let mut iter = LoopingTransportIter::new(
+            MusicalTime::from_beats(0),
+            MusicalTime::from_beats(1),
+            MusicalTime::from_beats(2),
+            24_000,
+            120.0,
+            SampleRate(48_000.0),
+        );
+
+        assert_eq!(
+            iter.next(),
+            Some((MusicalTime::from_beats(2), 24_000, false))
+        );
+        assert_eq!(
+            iter.next(),
+            Some((MusicalTime::from_beats(3), 24_000, false))
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use super::{FrameTime, SampleRate, SuperclockTime};
+
+/// A report of the values migrated by a [`ProjectTimeMigrator`].
+///
+/// [`ProjectTimeMigrator`]: struct.ProjectTimeMigrator.html
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// The number of [`FrameTime`] values that were recomputed.
+    ///
+    /// [`FrameTime`]: struct.FrameTime.html
+    pub frames_total: usize,
+    /// The number of [`FrameTime`] values whose value changed.
+    ///
+    /// [`FrameTime`]: struct.FrameTime.html
+    pub frames_changed: usize,
+}
+
+/// Migrates the time values of a project saved at one sample rate to another sample rate
+/// (i.e. when opening a project on a device with a different sample rate).
+///
+/// [`MusicalTime`] and [`SuperclockTime`] values are sample-rate independent, so they do not
+/// need to be migrated. [`FrameTime`] values are recomputed by way of [`SuperclockTime`], which is
+/// lossless for all of the common sample rates.
+///
+/// [`FrameTime`]: struct.FrameTime.html
+/// [`MusicalTime`]: struct.MusicalTime.html
+/// [`SuperclockTime`]: struct.SuperclockTime.html
+#[derive(Debug, Clone)]
+pub struct ProjectTimeMigrator {
+    old_sample_rate: SampleRate,
+    new_sample_rate: SampleRate,
+    report: MigrationReport,
+}
+
+impl ProjectTimeMigrator {
+    /// * `old_sample_rate` - The sample rate the project was saved at.
+    /// * `new_sample_rate` - The sample rate the project is being opened at.
+    pub fn new(old_sample_rate: SampleRate, new_sample_rate: SampleRate) -> Self {
+        Self {
+            old_sample_rate,
+            new_sample_rate,
+            report: MigrationReport::default(),
+        }
+    }
+
+    /// Recompute a single [`FrameTime`] value for the new sample rate.
+    ///
+    /// [`FrameTime`]: struct.FrameTime.html
+    pub fn migrate_frame(&mut self, frame: FrameTime) -> FrameTime {
+        let new_frame = SuperclockTime::from_frame(frame, self.old_sample_rate)
+            .to_nearest_frame_round(self.new_sample_rate);
+
+        self.report.frames_total += 1;
+        if new_frame != frame {
+            self.report.frames_changed += 1;
+        }
+
+        new_frame
+    }
+
+    /// Recompute all of the given [`FrameTime`] values in place for the new sample rate.
+    ///
+    /// [`FrameTime`]: struct.FrameTime.html
+    pub fn migrate_frames(&mut self, frames: &mut [FrameTime]) {
+        for frame in frames.iter_mut() {
+            *frame = self.migrate_frame(*frame);
+        }
+    }
+
+    /// The report of all values migrated so far.
+    pub fn report(&self) -> MigrationReport {
+        self.report
+    }
+
+    pub fn old_sample_rate(&self) -> SampleRate {
+        self.old_sample_rate
+    }
+
+    pub fn new_sample_rate(&self) -> SampleRate {
+        self.new_sample_rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_44100_to_48000_and_back() {
+        let original = [
+            FrameTime(0),
+            FrameTime(441),
+            FrameTime(44_100),
+            FrameTime(88_200),
+        ];
+
+        let mut frames = original;
+        let mut migrator = ProjectTimeMigrator::new(SampleRate(44_100.0), SampleRate(48_000.0));
+        migrator.migrate_frames(&mut frames);
+
+        assert_eq!(
+            frames,
+            [
+                FrameTime(0),
+                FrameTime(480),
+                FrameTime(48_000),
+                FrameTime(96_000)
+            ]
+        );
+        assert_eq!(
+            migrator.report(),
+            MigrationReport {
+                frames_total: 4,
+                frames_changed: 3,
+            }
+        );
+
+        let mut migrator = ProjectTimeMigrator::new(SampleRate(48_000.0), SampleRate(44_100.0));
+        migrator.migrate_frames(&mut frames);
+        assert_eq!(frames, original);
+        assert_eq!(migrator.report().frames_changed, 3);
+    }
+}
@@ -3,18 +3,38 @@
 #[cfg(feature = "serde-derive")]
 use serde::{Deserialize, Serialize};
 
+mod events;
+mod frame_converter;
 mod frame_time;
+mod groove;
+mod looping;
+mod migration;
 mod musical_time;
+mod musical_time_range;
+mod note_value;
 mod sample_rate;
 mod seconds;
 mod superclock_time;
+mod tempo;
+mod time_signature;
+mod transport;
 //mod video_timecode;
 
+pub use events::{partition_point_le, partition_point_lt, range_in_block};
+pub use frame_converter::FrameConverter;
 pub use frame_time::FrameTime;
+pub use groove::Groove;
+pub use looping::LoopingTransportIter;
+pub use migration::{MigrationReport, ProjectTimeMigrator};
 pub use musical_time::{MusicalTime, SUPER_BEAT_TICKS_PER_BEAT};
-pub use sample_rate::SampleRate;
-pub use seconds::SecondsF64;
+pub use musical_time_range::MusicalTimeRange;
+pub use note_value::NoteValue;
+pub use sample_rate::{bin_to_hz, hz_to_bin, SampleRate};
+pub use seconds::{lookahead_buffer_len, SecondsF64};
 pub use superclock_time::{SuperclockTime, SUPER_SAMPLE_TICKS_PER_SECOND};
+pub use tempo::{SecondsPerBeat, TempoMap, TempoRamp};
+pub use time_signature::{TimeSignature, TimeSignatureMap};
+pub use transport::Transport;
 //pub use video_timecode::{VideoFpsFormat, VideoTimecode};
 
 /// A reliable timestamp for events on the timeline.
@@ -1,6 +1,12 @@
-use std::ops::{Add, AddAssign, Mul, MulAssign};
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
 
-use super::{FrameTime, SampleRate, SecondsF64, SuperclockTime};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign};
+
+use super::{
+    FrameTime, Groove, MusicalTimeRange, NoteValue, SampleRate, SecondsF64, SecondsPerBeat,
+    SuperclockTime, TimeSignature, SUPER_SAMPLE_TICKS_PER_SECOND,
+};
 
 /// (`1,241,856,000`) This number was chosen because it is nicely divisible by a whole slew of factors
 /// including `2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 18, 20, 24, 32, 64, 128, 256, 512,
@@ -19,7 +25,7 @@ pub static SUPER_BEAT_TICKS_PER_BEAT: u32 = 1_241_856_000;
 /// with *exact* precision. This number is also much larger than all of the common sampling rates,
 /// allowing for sample-accurate precision even at very high sampling rates and very low BPMs.
 #[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
-#[derive(Default, Debug, Clone, Copy, Hash)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct MusicalTime {
     beats: u32,
     ticks: u32,
@@ -28,7 +34,7 @@ pub struct MusicalTime {
 impl MusicalTime {
     /// * `beats` - The time in musical beats.
     /// * `ticks` - The number of ticks (after the time in `beats`) (Note this value
-    /// will be constrained to the range `[0, 1,241,856,000)`).
+    ///   will be constrained to the range `[0, 1,241,856,000)`).
     ///
     /// A "tick" is a unit of time equal to `1 / 1,241,856,000` of a beat. This number was chosen
     /// because it is nicely divisible by a whole slew of factors including `2, 3, 4, 5, 6, 7, 8, 9,
@@ -37,10 +43,14 @@ impl MusicalTime {
     /// stored and operated on with *exact* precision. This number is also much larger than all of
     /// the common sampling rates, allowing for sample-accurate precision even at very high sampling
     /// rates and very low BPMs.
-    pub fn new(beats: u32, ticks: u32) -> Self {
+    pub const fn new(beats: u32, ticks: u32) -> Self {
         Self {
             beats,
-            ticks: ticks.min(SUPER_BEAT_TICKS_PER_BEAT - 1),
+            ticks: if ticks < SUPER_BEAT_TICKS_PER_BEAT {
+                ticks
+            } else {
+                SUPER_BEAT_TICKS_PER_BEAT - 1
+            },
         }
     }
 
@@ -77,179 +87,334 @@ impl MusicalTime {
         (u64::from(self.beats) * u64::from(SUPER_BEAT_TICKS_PER_BEAT)) + u64::from(self.ticks)
     }
 
+    /// Create a musical time from the total number of ticks.
+    ///
+    /// If the resulting number of beats does not fit in a `u32`, then the maximum possible
+    /// musical time will be returned instead.
+    ///
+    /// A "tick" is a unit of time equal to `1 / 1,241,856,000` of a beat.
+    pub const fn from_total_ticks(total_ticks: u64) -> Self {
+        // `u64::from()` cannot be used in a `const fn`.
+        let beats = total_ticks / SUPER_BEAT_TICKS_PER_BEAT as u64;
+        if beats > u32::MAX as u64 {
+            Self {
+                beats: u32::MAX,
+                ticks: SUPER_BEAT_TICKS_PER_BEAT - 1,
+            }
+        } else {
+            Self {
+                beats: beats as u32,
+                ticks: (total_ticks % SUPER_BEAT_TICKS_PER_BEAT as u64) as u32,
+            }
+        }
+    }
+
+    /// Create a musical time from a number of ticks at the given PPQ (pulses per quarter
+    /// note, i.e. the tick resolution of a MIDI file), where one quarter note is one beat.
+    ///
+    /// This is exact if `ppq` divides `SUPER_BEAT_TICKS_PER_BEAT` (which is true for all of
+    /// the common resolutions such as `96`, `480`, and `960`). Otherwise the result is rounded
+    /// to the nearest tick.
+    ///
+    /// If `ppq` is `0`, then a musical time of `0` will be returned instead. If the
+    /// resulting number of beats does not fit in a `u32`, then the maximum possible musical
+    /// time will be returned instead.
+    pub fn from_ppq_ticks(ticks: u64, ppq: u32) -> Self {
+        if ppq == 0 {
+            return Self::default();
+        }
+
+        let ppq = u128::from(ppq);
+        let total_ticks =
+            ((u128::from(ticks) * u128::from(SUPER_BEAT_TICKS_PER_BEAT)) + (ppq / 2)) / ppq;
+
+        Self::from_total_ticks(total_ticks.min(u128::from(u64::MAX)) as u64)
+    }
+
+    /// Convert to the number of ticks at the given PPQ (pulses per quarter note), rounded to
+    /// the nearest tick.
+    ///
+    /// Note that this conversion is *NOT* lossless.
+    pub fn to_ppq_ticks(&self, ppq: u32) -> u64 {
+        let tpb = u128::from(SUPER_BEAT_TICKS_PER_BEAT);
+        let ticks = ((u128::from(self.total_ticks()) * u128::from(ppq)) + (tpb / 2)) / tpb;
+
+        ticks.min(u128::from(u64::MAX)) as u64
+    }
+
+    /// Convert a (possibly negative, i.e. a relative offset) number of ticks at one PPQ
+    /// (pulses per quarter note) to another, rounded to the nearest tick (with ties rounded
+    /// away from zero).
+    ///
+    /// For non-negative ticks this gives the same result as converting through a
+    /// [`MusicalTime`] with `MusicalTime::from_ppq_ticks()` and `MusicalTime::to_ppq_ticks()`.
+    ///
+    /// If `from_ppq` is `0`, then `0` will be returned instead. If the result does not fit
+    /// in an `i64`, then it is saturated.
+    ///
+    /// [`MusicalTime`]: struct.MusicalTime.html
+    pub fn rescale_ppq(ticks: i64, from_ppq: u32, to_ppq: u32) -> i64 {
+        if from_ppq == 0 {
+            return 0;
+        }
+
+        let from_ppq = i128::from(from_ppq);
+        let num = i128::from(ticks) * i128::from(to_ppq);
+        let half = from_ppq / 2;
+        let rescaled = if num >= 0 {
+            (num + half) / from_ppq
+        } else {
+            (num - half) / from_ppq
+        };
+
+        rescaled.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64
+    }
+
     /// * `beats` - The time in musical beats.
-    pub fn from_beats(beats: u32) -> Self {
+    pub const fn from_beats(beats: u32) -> Self {
         Self { beats, ticks: 0 }
     }
 
-    pub fn from_fractional_beats<const DIVISOR: u32>(beats: u32, fract_beats: u32) -> Self {
+    pub const fn from_fractional_beats<const DIVISOR: u32>(beats: u32, fract_beats: u32) -> Self {
+        let fract_beats = if fract_beats < DIVISOR {
+            fract_beats
+        } else {
+            DIVISOR - 1
+        };
+
         Self {
             beats,
-            ticks: fract_beats.min(DIVISOR - 1) * (SUPER_BEAT_TICKS_PER_BEAT / DIVISOR),
+            ticks: fract_beats * (SUPER_BEAT_TICKS_PER_BEAT / DIVISOR),
         }
     }
 
     /// * `beats` - The time in musical beats.
     /// * `half_beats` - The number of half-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 1]`.
-    pub fn from_half_beats(beats: u32, half_beats: u32) -> Self {
+    ///   constrained to the range `[0, 1]`.
+    pub const fn from_half_beats(beats: u32, half_beats: u32) -> Self {
         Self::from_fractional_beats::<2>(beats, half_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `quarter_beats` - The number of quarter-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 3]`.
-    pub fn from_quarter_beats(beats: u32, quarter_beats: u32) -> Self {
+    ///   constrained to the range `[0, 3]`.
+    pub const fn from_quarter_beats(beats: u32, quarter_beats: u32) -> Self {
         Self::from_fractional_beats::<4>(beats, quarter_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `eigth_beats` - The number of eigth-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 7]`.
-    pub fn from_eighth_beats(beats: u32, eigth_beats: u32) -> Self {
+    ///   constrained to the range `[0, 7]`.
+    pub const fn from_eighth_beats(beats: u32, eigth_beats: u32) -> Self {
         Self::from_fractional_beats::<8>(beats, eigth_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `sixteenth_beats` - The number of sixteenth-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 15]`.
-    pub fn from_sixteenth_beats(beats: u32, sixteenth_beats: u32) -> Self {
+    ///   constrained to the range `[0, 15]`.
+    pub const fn from_sixteenth_beats(beats: u32, sixteenth_beats: u32) -> Self {
         Self::from_fractional_beats::<16>(beats, sixteenth_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `_32nd_beats` - The number of 32nd-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 31]`.
-    pub fn from_32nd_beats(beats: u32, _32nd_beats: u32) -> Self {
+    ///   constrained to the range `[0, 31]`.
+    pub const fn from_32nd_beats(beats: u32, _32nd_beats: u32) -> Self {
         Self::from_fractional_beats::<32>(beats, _32nd_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `_64th_beats` - The number of 64th-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 63]`.
-    pub fn from_64th_beats(beats: u32, _64th_beats: u32) -> Self {
+    ///   constrained to the range `[0, 63]`.
+    pub const fn from_64th_beats(beats: u32, _64th_beats: u32) -> Self {
         Self::from_fractional_beats::<64>(beats, _64th_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `_128th_beats` - The number of 128th-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 127]`.
-    pub fn from_128th_beats(beats: u32, _128th_beats: u32) -> Self {
+    ///   constrained to the range `[0, 127]`.
+    pub const fn from_128th_beats(beats: u32, _128th_beats: u32) -> Self {
         Self::from_fractional_beats::<128>(beats, _128th_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `_256th_beats` - The number of 256th-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 255]`.
-    pub fn from_256th_beats(beats: u32, _256th_beats: u32) -> Self {
+    ///   constrained to the range `[0, 255]`.
+    pub const fn from_256th_beats(beats: u32, _256th_beats: u32) -> Self {
         Self::from_fractional_beats::<256>(beats, _256th_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `_512th_beats` - The number of 512th-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 511]`.
-    pub fn from_512th_beats(beats: u32, _512th_beats: u32) -> Self {
+    ///   constrained to the range `[0, 511]`.
+    pub const fn from_512th_beats(beats: u32, _512th_beats: u32) -> Self {
         Self::from_fractional_beats::<512>(beats, _512th_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `_1024th_beats` - The number of 1024th-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 1023]`.
-    pub fn from_1024th_beats(beats: u32, _1024th_beats: u32) -> Self {
+    ///   constrained to the range `[0, 1023]`.
+    pub const fn from_1024th_beats(beats: u32, _1024th_beats: u32) -> Self {
         Self::from_fractional_beats::<1024>(beats, _1024th_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `_2048th_beats` - The number of 2048th-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 2047]`.
-    pub fn from_2048th_beats(beats: u32, _2048th_beats: u32) -> Self {
+    ///   constrained to the range `[0, 2047]`.
+    pub const fn from_2048th_beats(beats: u32, _2048th_beats: u32) -> Self {
         Self::from_fractional_beats::<2048>(beats, _2048th_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `third_beats` - The number of third-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 2]`.
-    pub fn from_third_beats(beats: u32, third_beats: u32) -> Self {
+    ///   constrained to the range `[0, 2]`.
+    pub const fn from_third_beats(beats: u32, third_beats: u32) -> Self {
         Self::from_fractional_beats::<3>(beats, third_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `fifth_beats` - The number of fifth-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 4]`.
-    pub fn from_fifth_beats(beats: u32, fifth_beats: u32) -> Self {
+    ///   constrained to the range `[0, 4]`.
+    pub const fn from_fifth_beats(beats: u32, fifth_beats: u32) -> Self {
         Self::from_fractional_beats::<5>(beats, fifth_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `sixth_beats` - The number of sixth-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 5]`.
-    pub fn from_sixth_beats(beats: u32, sixth_beats: u32) -> Self {
+    ///   constrained to the range `[0, 5]`.
+    pub const fn from_sixth_beats(beats: u32, sixth_beats: u32) -> Self {
         Self::from_fractional_beats::<6>(beats, sixth_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `seventh_beats` - The number of seventh-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 6]`.
-    pub fn from_seventh_beats(beats: u32, seventh_beats: u32) -> Self {
+    ///   constrained to the range `[0, 6]`.
+    pub const fn from_seventh_beats(beats: u32, seventh_beats: u32) -> Self {
         Self::from_fractional_beats::<7>(beats, seventh_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `ninth_beats` - The number of ninth-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 8]`.
-    pub fn from_ninth_beats(beats: u32, ninth_beats: u32) -> Self {
+    ///   constrained to the range `[0, 8]`.
+    pub const fn from_ninth_beats(beats: u32, ninth_beats: u32) -> Self {
         Self::from_fractional_beats::<9>(beats, ninth_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `tenth_beats` - The number of tenth-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 9]`.
-    pub fn from_tenth_beats(beats: u32, tenth_beats: u32) -> Self {
+    ///   constrained to the range `[0, 9]`.
+    pub const fn from_tenth_beats(beats: u32, tenth_beats: u32) -> Self {
         Self::from_fractional_beats::<10>(beats, tenth_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `eleventh_beats` - The number of eleventh-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 10]`.
-    pub fn from_eleventh_beats(beats: u32, eleventh_beats: u32) -> Self {
+    ///   constrained to the range `[0, 10]`.
+    pub const fn from_eleventh_beats(beats: u32, eleventh_beats: u32) -> Self {
         Self::from_fractional_beats::<11>(beats, eleventh_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `twelfth_beats` - The number of twelfth-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 11]`.
-    pub fn from_twelth_beats(beats: u32, twelfth_beats: u32) -> Self {
+    ///   constrained to the range `[0, 11]`.
+    pub const fn from_twelth_beats(beats: u32, twelfth_beats: u32) -> Self {
         Self::from_fractional_beats::<12>(beats, twelfth_beats)
     }
 
     /// * `beats` - The time in musical beats.
     /// * `_24th_beats` - The number of 24th-beats (after the time `beats`). This will be
-    /// constrained to the range `[0, 23]`.
-    pub fn from_24th_beats(beats: u32, _24th_beats: u32) -> Self {
+    ///   constrained to the range `[0, 23]`.
+    pub const fn from_24th_beats(beats: u32, _24th_beats: u32) -> Self {
         Self::from_fractional_beats::<24>(beats, _24th_beats)
     }
 
-    /// Get the corresponding musical time from the number of beats (as an `f64`).
+    /// The length of the note `base` with the given number of dots, where each dot adds half
+    /// of the length added by the previous one (i.e. one dot is `1.5x` the length, and two
+    /// dots are `1.75x` the length).
     ///
-    /// Note that this conversion is *NOT* lossless.
+    /// This is exact as long as `base` is divisible by `2^dots` ticks (which is the case for
+    /// all of the common note values), and is floored to the nearest tick otherwise. If the
+    /// result does not fit, then the maximum possible musical time will be returned instead.
+    pub fn dotted(base: MusicalTime, dots: u32) -> MusicalTime {
+        let dots = dots.min(64);
+        let ticks = u128::from(base.total_ticks());
+
+        // base * (2 - (1 / 2^dots)) = base * (2^(dots + 1) - 1) / 2^dots
+        let scaled = (ticks * ((1u128 << (dots + 1)) - 1)) >> dots;
+
+        Self::from_total_ticks(scaled.min(u128::from(u64::MAX)) as u64)
+    }
+
+    /// The length of one note of a tuplet, where `actual` notes of length `base` are played in
+    /// the time of `in_time_of` of them (i.e. `MusicalTime::tuplet(base, 2, 3)` for a triplet,
+    /// or `MusicalTime::tuplet(base, 4, 5)` for a quintuplet).
+    ///
+    /// This is exact as long as `base * in_time_of` is divisible by `actual` ticks (which is
+    /// the case for all of the divisors of `SUPER_BEAT_TICKS_PER_BEAT`), and is floored to the
+    /// nearest tick otherwise. If `actual` is `0`, then it is treated as `1`.
+    pub fn tuplet(base: MusicalTime, in_time_of: u32, actual: u32) -> MusicalTime {
+        let ticks =
+            u128::from(base.total_ticks()) * u128::from(in_time_of) / u128::from(actual.max(1));
+
+        Self::from_total_ticks(ticks.min(u128::from(u64::MAX)) as u64)
+    }
+
+    /// Linearly interpolate between the times `a` and `b` (i.e. for automation or animation),
+    /// rounded to the nearest tick.
     ///
-    /// If `beats` is less than 0.0, then a musical time of `0` will be returned instead.
+    /// * `t` - The interpolation factor, where `0.0` gives exactly `a` and `1.0` gives exactly
+    ///   `b`. This is clamped to the range `[0.0, 1.0]`, and `NaN` is treated as `0.0`.
+    pub fn lerp(a: MusicalTime, b: MusicalTime, t: f64) -> MusicalTime {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        if t == 1.0 {
+            // The distance may not be exactly representable as an `f64`.
+            return b;
+        }
+
+        let a_ticks = i128::from(a.total_ticks());
+        let delta = i128::from(b.total_ticks()) - a_ticks;
+
+        Self::from_total_ticks((a_ticks + (delta as f64 * t).round() as i128) as u64)
+    }
+
+    /// Get the corresponding musical time from the number of beats (as an `f64`). This is the
+    /// inverse of `MusicalTime::as_beats_f64()`.
+    ///
+    /// Note that this conversion is *NOT* lossless. The fractional part is rounded to the
+    /// nearest tick, so values on any of the exact subdivisions of a beat (i.e. `3.75`) round
+    /// trip exactly, and all other values are within one tick.
+    ///
+    /// If `beats` is less than 0.0 (or `NaN`), then a musical time of `0` will be returned
+    /// instead. If `beats` is too large to fit, then the maximum possible musical time will be
+    /// returned instead.
     pub fn from_beats_f64(beats: f64) -> Self {
-        if beats >= 0.0 {
-            let mut beats_u32 = beats.trunc() as u32;
-            let mut ticks = (beats.fract() * f64::from(SUPER_BEAT_TICKS_PER_BEAT)).round() as u32;
+        const MAX: MusicalTime = MusicalTime {
+            beats: u32::MAX,
+            ticks: SUPER_BEAT_TICKS_PER_BEAT - 1,
+        };
 
-            if ticks >= SUPER_BEAT_TICKS_PER_BEAT {
-                ticks = 0;
-                beats_u32 += 1;
-            }
+        if beats.is_nan() || beats < 0.0 {
+            return Self { beats: 0, ticks: 0 };
+        }
+        if beats >= f64::from(u32::MAX) + 1.0 {
+            return MAX;
+        }
 
-            Self {
-                beats: beats_u32,
-                ticks,
+        let mut beats_u32 = beats.trunc() as u32;
+        let mut ticks = (beats.fract() * f64::from(SUPER_BEAT_TICKS_PER_BEAT)).round() as u32;
+
+        if ticks >= SUPER_BEAT_TICKS_PER_BEAT {
+            if beats_u32 == u32::MAX {
+                return MAX;
             }
-        } else {
-            Self { beats: 0, ticks: 0 }
+
+            ticks = 0;
+            beats_u32 += 1;
+        }
+
+        Self {
+            beats: beats_u32,
+            ticks,
         }
     }
 
@@ -379,6 +544,219 @@ impl MusicalTime {
         self.snap_to_nearest_fractional_beat::<24>()
     }
 
+    /// Snap to the nearest multiple of `grid`, while also making sure the result stays within
+    /// the given `range`.
+    ///
+    /// If the nearest grid line lies outside of `range`, then the nearest grid line inside of
+    /// `range` is used instead. Note that for the purposes of this method both the start and
+    /// the end of `range` are inclusive, so a grid line lying exactly on either boundary is
+    /// allowed.
+    ///
+    /// If there is no grid line inside of `range` (or `grid` is zero), then this is simply
+    /// clamped to `range`.
+    pub fn snap_clamped(&self, grid: MusicalTime, range: MusicalTimeRange) -> MusicalTime {
+        let start = range.start.total_ticks();
+        let end = range.end.total_ticks().max(start);
+        let grid = grid.total_ticks();
+
+        if grid == 0 {
+            return Self::from_total_ticks(self.total_ticks().clamp(start, end));
+        }
+
+        let first_line = start.div_ceil(grid) * grid;
+        let last_line = (end / grid) * grid;
+
+        if first_line > last_line {
+            return Self::from_total_ticks(self.total_ticks().clamp(start, end));
+        }
+
+        let t = self.total_ticks();
+        let nearest_line = ((t + (grid / 2)) / grid) * grid;
+
+        Self::from_total_ticks(nearest_line.clamp(first_line, last_line))
+    }
+
+    /// Generate the note-on and note-off times of `count` arpeggiator steps.
+    ///
+    /// Steps whose note-on time would not fit in a musical time are left out, so fewer than
+    /// `count` steps may be returned. Note-off times that would not fit are set to the
+    /// maximum possible musical time.
+    ///
+    /// * `start` - The note-on time of the first step.
+    /// * `rate` - The time between consecutive note-ons.
+    /// * `count` - The number of steps.
+    /// * `gate` - The length of each note relative to `rate`. This will be constrained to the
+    ///   range `[0.0, 1.0]`, where `0.0` gives notes of zero length.
+    pub fn arp_steps(
+        start: MusicalTime,
+        rate: MusicalTime,
+        count: usize,
+        gate: f64,
+    ) -> Vec<(MusicalTime, MusicalTime)> {
+        let max_ticks = MusicalTime::new(u32::MAX, SUPER_BEAT_TICKS_PER_BEAT - 1).total_ticks();
+
+        let start_ticks = start.total_ticks();
+        let rate_ticks = rate.total_ticks();
+        let gate_ticks = (rate_ticks as f64 * gate.clamp(0.0, 1.0)).round() as u64;
+
+        (0..count as u64)
+            .map_while(|i| {
+                let on = i
+                    .checked_mul(rate_ticks)
+                    .and_then(|t| t.checked_add(start_ticks))
+                    .filter(|on| *on <= max_ticks)?;
+
+                Some((
+                    Self::from_total_ticks(on),
+                    Self::from_total_ticks(on.saturating_add(gate_ticks)),
+                ))
+            })
+            .collect()
+    }
+
+    /// Find the standard [`NoteValue`] (straight, dotted, or triplet, from a whole note down
+    /// to a 64th note) whose length is nearest to this duration.
+    ///
+    /// The distance is measured as a ratio, so a duration is matched just as tolerantly
+    /// against short note values as against long ones. Durations outside of the supported
+    /// range are matched to the longest/shortest note value.
+    ///
+    /// [`NoteValue`]: enum.NoteValue.html
+    pub fn nearest_note_value(&self) -> NoteValue {
+        let ticks = self.total_ticks().max(1) as f64;
+
+        let mut nearest = NoteValue::Straight(4);
+        let mut nearest_dist = f64::INFINITY;
+        for &d in NoteValue::DENOMINATORS.iter() {
+            for note_value in [
+                NoteValue::Straight(d),
+                NoteValue::Dotted(d),
+                NoteValue::Triplet(d),
+            ] {
+                let dist = (ticks / note_value.to_musical().total_ticks() as f64)
+                    .ln()
+                    .abs();
+                if dist < nearest_dist {
+                    nearest = note_value;
+                    nearest_dist = dist;
+                }
+            }
+        }
+
+        nearest
+    }
+
+    /// Generate the positions of every step in a pattern of `bars` bars with `steps_per_bar`
+    /// evenly-spaced steps per bar, with swing applied to the off-steps.
+    ///
+    /// * `bars` - The number of bars in the pattern.
+    /// * `steps_per_bar` - The number of steps in each bar (i.e. `16` for a 16th grid in `4/4`).
+    /// * `swing` - The amount of swing in the range `[0.0, 1.0]`. Every odd step (the
+    ///   off-steps) is delayed by `swing` times half of the length of a step, so `0.0` is no
+    ///   swing and `1.0` places each off-step halfway between its even position and the next
+    ///   step.
+    /// * `ts` - The time signature of the pattern.
+    pub fn swung_step_positions(
+        bars: u32,
+        steps_per_bar: u32,
+        swing: f64,
+        ts: TimeSignature,
+    ) -> Vec<MusicalTime> {
+        if steps_per_bar == 0 {
+            return Vec::new();
+        }
+
+        let bar_ticks = ts.bar_length_ticks();
+        let step_ticks = bar_ticks / u64::from(steps_per_bar);
+        let swing_ticks = (swing.clamp(0.0, 1.0) * (step_ticks as f64 / 2.0)).round() as u64;
+
+        let mut positions = Vec::with_capacity((bars as usize) * (steps_per_bar as usize));
+        for bar in 0..u64::from(bars) {
+            for step in 0..u64::from(steps_per_bar) {
+                let mut t = (bar * bar_ticks) + (step * step_ticks);
+                if step % 2 == 1 {
+                    t += swing_ticks;
+                }
+
+                positions.push(Self::from_total_ticks(t));
+            }
+        }
+
+        positions
+    }
+
+    /// The position of this time in bars and beats, counted from `origin` instead of from
+    /// time `0` (i.e. for a loop region that starts with a pickup/anacrusis, where bar `0`
+    /// starts after the pickup).
+    ///
+    /// Returns the bar index (where `0` is the bar starting at `origin`), the beat within that
+    /// bar (starting from `0`), and the remaining time after the start of that beat. A "beat"
+    /// here is the note value of the time signature's denominator (i.e. an eighth note in
+    /// `6/8`).
+    ///
+    /// Times before `origin` give negative bar indices, with the beat still counted forward
+    /// from the start of that bar. For example with a one-beat pickup in `4/4`, the pickup
+    /// lies in bar `-1` at beat `3`.
+    ///
+    /// * `origin` - The time at which bar `0` starts.
+    /// * `ts` - The time signature, which is assumed to be constant.
+    pub fn bars_beats_relative(
+        &self,
+        origin: MusicalTime,
+        ts: TimeSignature,
+    ) -> (i64, u32, MusicalTime) {
+        let bar_ticks = i128::from(ts.bar_length_ticks().max(1));
+        let beat_ticks =
+            (i128::from(SUPER_BEAT_TICKS_PER_BEAT) * 4 / i128::from(ts.denominator.max(1))).max(1);
+
+        let ticks = i128::from(self.total_ticks()) - i128::from(origin.total_ticks());
+        let bar = ticks.div_euclid(bar_ticks);
+        let ticks_in_bar = ticks.rem_euclid(bar_ticks);
+
+        (
+            bar as i64,
+            (ticks_in_bar / beat_ticks) as u32,
+            Self::from_total_ticks((ticks_in_bar % beat_ticks) as u64),
+        )
+    }
+
+    /// Create a musical time from a position in bars and beats, counted from time `0`.
+    ///
+    /// A "beat" here is the note value of the time signature's denominator (i.e. an eighth
+    /// note in `6/8`). This is the inverse of `MusicalTime::as_bars_beats()`.
+    ///
+    /// If the resulting time does not fit, then the maximum possible musical time will be
+    /// returned instead.
+    ///
+    /// * `bars` - The bar index (starting from `0`).
+    /// * `beats` - The beat within the bar (starting from `0`). Values greater than or equal
+    ///   to the numerator of the time signature spill over into the following bars.
+    /// * `ts` - The time signature, which is assumed to be constant.
+    pub fn from_bars_beats(bars: u64, beats: u32, ts: TimeSignature) -> MusicalTime {
+        let beat_ticks =
+            u128::from(SUPER_BEAT_TICKS_PER_BEAT) * 4 / u128::from(ts.denominator.max(1));
+        let ticks = (u128::from(bars) * u128::from(ts.bar_length_ticks()))
+            + (u128::from(beats) * beat_ticks);
+
+        Self::from_total_ticks(ticks.min(u128::from(u64::MAX)) as u64)
+    }
+
+    /// The position of this time in bars and beats, counted from time `0`.
+    ///
+    /// Returns the bar index (starting from `0`), the beat within that bar (starting from
+    /// `0`), and the remaining number of ticks after the start of that beat. A "beat" here is
+    /// the note value of the time signature's denominator (i.e. an eighth note in `6/8`).
+    ///
+    /// A "tick" is a unit of time equal to `1 / 1,241,856,000` of a quarter note, so the
+    /// result is exact for any position.
+    ///
+    /// * `ts` - The time signature, which is assumed to be constant.
+    pub fn as_bars_beats(&self, ts: TimeSignature) -> (u64, u32, u64) {
+        let (bar, beat, rem) = self.bars_beats_relative(MusicalTime::default(), ts);
+
+        (bar as u64, beat, rem.total_ticks())
+    }
+
     /// The number of fractional-beats *after* `self.beats()` (floored to
     /// the nearest fractional-beat).
     ///
@@ -549,13 +927,45 @@ impl MusicalTime {
 
     /// Convert to the corresponding time in [`SecondsF64`].
     ///
-    /// Note that this conversion is *NOT* lossless.
+    /// Note that this conversion is *NOT* lossless. The relative error is on the order of
+    /// `f64::EPSILON`, which keeps the absolute error well below one [`SuperclockTime`] tick
+    /// for arrangements many hours long. Use `MusicalTime::to_super_frame_exact()` if a
+    /// provable bound is needed.
     ///
     /// [`SecondsF64`]: struct.SecondsF64.html
+    /// [`SuperclockTime`]: struct.SuperclockTime.html
     pub fn to_seconds_f64(&self, bpm: f64) -> SecondsF64 {
         SecondsF64(self.as_beats_f64() * 60.0 / bpm)
     }
 
+    /// Convert to the corresponding time in [`SecondsF64`], with the given [`Groove`] (swing)
+    /// applied first.
+    ///
+    /// Downbeats of the groove are unchanged, and a groove with no swing gives exactly the same
+    /// result as `MusicalTime::to_seconds_f64()`.
+    ///
+    /// Note that this conversion is *NOT* lossless.
+    ///
+    /// [`SecondsF64`]: struct.SecondsF64.html
+    /// [`Groove`]: struct.Groove.html
+    pub fn to_seconds_with_groove(&self, bpm: f64, groove: &Groove) -> SecondsF64 {
+        groove.apply(*self).to_seconds_f64(bpm)
+    }
+
+    /// Convert to the corresponding time in [`SecondsF64`], where the tempo is given as a
+    /// precomputed [`SecondsPerBeat`].
+    ///
+    /// This avoids a division for every conversion, which is useful when converting many
+    /// values at the same tempo.
+    ///
+    /// Note that this conversion is *NOT* lossless.
+    ///
+    /// [`SecondsF64`]: struct.SecondsF64.html
+    /// [`SecondsPerBeat`]: struct.SecondsPerBeat.html
+    pub fn to_seconds_with_spb(&self, spb: SecondsPerBeat) -> SecondsF64 {
+        SecondsF64(self.as_beats_f64() * spb.0)
+    }
+
     /// Convert to the corresponding discrete [`FrameTime`]. This will be rounded to the nearest frame.
     ///
     /// Note that this conversion is *NOT* lossless.
@@ -628,6 +1038,57 @@ impl MusicalTime {
         self.to_seconds_f64(bpm).to_nearest_super_frame_ceil()
     }
 
+    /// Convert to the corresponding discrete [`SuperclockTime`] using exact integer arithmetic,
+    /// where the tempo is given as the fraction `bpm_numerator / bpm_denominator` (i.e. a tempo
+    /// of `127.3` BPM is `1273 / 10`). This will be rounded to the nearest super-frame.
+    ///
+    /// Unlike the `f64` conversions, the error of this conversion is at most half of a
+    /// super-frame regardless of the length of the time, and converting back with
+    /// `MusicalTime::from_super_frame_exact()` is within one super-frame of the original time.
+    ///
+    /// `bpm_numerator` and `bpm_denominator` must both be greater than `0`.
+    ///
+    /// [`SuperclockTime`]: struct.SuperclockTime.html
+    pub fn to_super_frame_exact(&self, bpm_numerator: u32, bpm_denominator: u32) -> SuperclockTime {
+        assert!(bpm_numerator > 0 && bpm_denominator > 0);
+
+        // seconds = beats * 60 / bpm
+        let num = u128::from(self.total_ticks())
+            * 60
+            * u128::from(bpm_denominator)
+            * u128::from(SUPER_SAMPLE_TICKS_PER_SECOND);
+        let den = u128::from(SUPER_BEAT_TICKS_PER_BEAT) * u128::from(bpm_numerator);
+
+        let super_ticks = (num + (den / 2)) / den;
+        SuperclockTime::from_total_ticks(super_ticks.min(u128::from(u64::MAX)) as u64)
+    }
+
+    /// Convert the given [`SuperclockTime`] to the corresponding musical time using exact
+    /// integer arithmetic, where the tempo is given as the fraction
+    /// `bpm_numerator / bpm_denominator`. This will be rounded to the nearest tick.
+    ///
+    /// This is the inverse of `MusicalTime::to_super_frame_exact()`.
+    ///
+    /// `bpm_numerator` and `bpm_denominator` must both be greater than `0`.
+    ///
+    /// [`SuperclockTime`]: struct.SuperclockTime.html
+    pub fn from_super_frame_exact(
+        time: SuperclockTime,
+        bpm_numerator: u32,
+        bpm_denominator: u32,
+    ) -> MusicalTime {
+        assert!(bpm_numerator > 0 && bpm_denominator > 0);
+
+        // beats = seconds * bpm / 60
+        let num = u128::from(time.total_ticks())
+            * u128::from(bpm_numerator)
+            * u128::from(SUPER_BEAT_TICKS_PER_BEAT);
+        let den = u128::from(SUPER_SAMPLE_TICKS_PER_SECOND) * 60 * u128::from(bpm_denominator);
+
+        let ticks = (num + (den / 2)) / den;
+        Self::from_total_ticks(ticks.min(u128::from(u64::MAX)) as u64)
+    }
+
     /// Convert to the corresponding discrete [`SuperclockTime`] floored to the nearest super-frame,
     /// while also returning the fractional sub-super-frame part.
     ///
@@ -652,37 +1113,161 @@ impl MusicalTime {
                     ticks: self.ticks - rhs.ticks,
                 })
             }
+        } else if rhs.ticks > self.ticks {
+            Some(MusicalTime {
+                beats: self.beats - rhs.beats - 1,
+                ticks: SUPER_BEAT_TICKS_PER_BEAT - (rhs.ticks - self.ticks),
+            })
         } else {
-            if rhs.ticks > self.ticks {
-                Some(MusicalTime {
-                    beats: self.beats - rhs.beats - 1,
-                    ticks: SUPER_BEAT_TICKS_PER_BEAT - (rhs.ticks - self.ticks),
-                })
-            } else {
-                Some(MusicalTime {
-                    beats: self.beats - rhs.beats,
-                    ticks: self.ticks - rhs.ticks,
-                })
+            Some(MusicalTime {
+                beats: self.beats - rhs.beats,
+                ticks: self.ticks - rhs.ticks,
+            })
+        }
+    }
+
+    /// Subtract `rhs` from self, returning a time of zero if the result would be negative.
+    ///
+    /// This is useful when computing durations such as the remaining length of a clip
+    /// (`clip_end - playhead`), where a negative result is meaningless.
+    pub fn saturating_sub_to_zero(&self, rhs: MusicalTime) -> MusicalTime {
+        self.checked_sub(rhs).unwrap_or_default()
+    }
+
+    /// Try multiplying self by `rhs`. This will return `None` if the resulting number of beats
+    /// does not fit in a `u32` (overflow).
+    pub fn checked_mul(self, rhs: u32) -> Option<MusicalTime> {
+        let total_ticks = u128::from(self.total_ticks()) * u128::from(rhs);
+        let beats = total_ticks / u128::from(SUPER_BEAT_TICKS_PER_BEAT);
+
+        if beats > u128::from(u32::MAX) {
+            None
+        } else {
+            Some(MusicalTime {
+                beats: beats as u32,
+                ticks: (total_ticks % u128::from(SUPER_BEAT_TICKS_PER_BEAT)) as u32,
+            })
+        }
+    }
+
+    /// Multiply self by `rhs`, returning the maximum possible musical time if the result
+    /// would overflow.
+    pub fn saturating_mul(self, rhs: u32) -> MusicalTime {
+        self.checked_mul(rhs).unwrap_or(MusicalTime {
+            beats: u32::MAX,
+            ticks: SUPER_BEAT_TICKS_PER_BEAT - 1,
+        })
+    }
+
+    /// Offset this time by a random amount in the range `[-max_offset, +max_offset]` (i.e.
+    /// for humanizing the timing of notes), rounded to the nearest tick.
+    ///
+    /// * max_offset - The maximum distance from this time.
+    /// * rng - A random number generator that returns a value in the range `[0.0, 1.0]`.
+    ///   Values outside of this range are clamped.
+    ///
+    /// If the resulting time would be negative, then a musical time of `0` will be returned
+    /// instead.
+    pub fn humanize(&self, max_offset: MusicalTime, rng: &mut impl FnMut() -> f64) -> MusicalTime {
+        let max_ticks = max_offset.total_ticks() as f64;
+        let r = rng().clamp(0.0, 1.0);
+
+        let offset = (((r * 2.0) - 1.0) * max_ticks).round() as i128;
+        let ticks = i128::from(self.total_ticks()) + offset;
+
+        MusicalTime::from_total_ticks(ticks.clamp(0, i128::from(u64::MAX)) as u64)
+    }
+
+    /// Convert to a compact, human-editable string (i.e. for a project file).
+    ///
+    /// The grammar is (see `MusicalTime::from_compact_string()`):
+    ///
+    /// * `"<beats>b"` - A whole number of beats (i.e. `"4b"`).
+    /// * `"<beats>+<num>/<den>b"` - A number of beats plus a fraction of a beat, where
+    ///   `num < den` and `den` divides `SUPER_BEAT_TICKS_PER_BEAT` (i.e. `"3+3/4b"`).
+    /// * `"<ticks>sb"` - A total number of super-beat ticks (i.e. `"1920sb"`).
+    ///
+    /// Whole beats are written with the first form. Times on a subdivision of a beat of up
+    /// to `1/1920` are written with the second form, and all other times are written with
+    /// the third form.
+    pub fn to_compact_string(&self) -> String {
+        if self.ticks == 0 {
+            return format!("{}b", self.beats);
+        }
+
+        let divisor = gcd(self.ticks, SUPER_BEAT_TICKS_PER_BEAT);
+        let den = SUPER_BEAT_TICKS_PER_BEAT / divisor;
+        if den <= 1_920 {
+            format!("{}+{}/{}b", self.beats, self.ticks / divisor, den)
+        } else {
+            format!("{}sb", self.total_ticks())
+        }
+    }
+
+    /// Parse a string created with `MusicalTime::to_compact_string()`. Returns `None` if the
+    /// string is not valid.
+    ///
+    /// The grammar is:
+    ///
+    /// * `"<beats>b"` - A whole number of beats (i.e. `"4b"`).
+    /// * `"<beats>+<num>/<den>b"` - A number of beats plus a fraction of a beat, where
+    ///   `num < den` and `den` divides `SUPER_BEAT_TICKS_PER_BEAT` (i.e. `"3+3/4b"`).
+    /// * `"<ticks>sb"` - A total number of super-beat ticks (i.e. `"1920sb"`).
+    ///
+    /// All numbers are unsigned integers, so negative times (i.e. `"-4b"`) are rejected.
+    /// Leading and trailing whitespace is ignored.
+    pub fn from_compact_string(s: &str) -> Option<Self> {
+        let s = s.trim();
+
+        if let Some(ticks) = s.strip_suffix("sb") {
+            let ticks: u64 = parse_unsigned(ticks)?;
+            if ticks / u64::from(SUPER_BEAT_TICKS_PER_BEAT) > u64::from(u32::MAX) {
+                return None;
+            }
+
+            return Some(Self::from_total_ticks(ticks));
+        }
+
+        let s = s.strip_suffix('b')?;
+        match s.split_once('+') {
+            None => Some(Self::from_beats(parse_unsigned(s)?)),
+            Some((beats, fraction)) => {
+                let (num, den) = fraction.split_once('/')?;
+                let (num, den): (u32, u32) = (parse_unsigned(num)?, parse_unsigned(den)?);
+                if den == 0 || num >= den || SUPER_BEAT_TICKS_PER_BEAT % den != 0 {
+                    return None;
+                }
+
+                Some(Self::new(
+                    parse_unsigned(beats)?,
+                    num * (SUPER_BEAT_TICKS_PER_BEAT / den),
+                ))
             }
         }
     }
 }
 
-impl PartialEq for MusicalTime {
-    fn eq(&self, other: &Self) -> bool {
-        self.beats == other.beats && self.ticks == other.ticks
+/// Parse an unsigned integer, rejecting signs (which `str::parse()` would accept).
+fn parse_unsigned<T: std::str::FromStr>(s: &str) -> Option<T> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
     }
+
+    s.parse().ok()
 }
 
-impl Eq for MusicalTime {}
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
 
 impl PartialOrd for MusicalTime {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.beats != other.beats {
-            self.beats.partial_cmp(&other.beats)
-        } else {
-            self.ticks.partial_cmp(&other.ticks)
-        }
+        Some(self.cmp(other))
     }
 }
 
@@ -710,21 +1295,76 @@ impl Add<MusicalTime> for MusicalTime {
         Self { beats, ticks }
     }
 }
+/// Multiply a musical time by a scalar.
+///
+/// If the result overflows, then this will panic in debug builds, and the maximum possible
+/// musical time will be returned in release builds (like `MusicalTime::saturating_mul()`).
+/// Use `MusicalTime::checked_mul()` if the result may not fit.
 impl Mul<u32> for MusicalTime {
     type Output = Self;
     fn mul(self, rhs: u32) -> Self::Output {
-        let mut beats = self.beats * rhs;
-        let mut ticks = u64::from(self.ticks) * u64::from(rhs);
+        debug_assert!(
+            self.checked_mul(rhs).is_some(),
+            "MusicalTime multiplication overflowed"
+        );
 
-        if ticks >= u64::from(SUPER_BEAT_TICKS_PER_BEAT) {
-            beats += (ticks / u64::from(SUPER_BEAT_TICKS_PER_BEAT)) as u32;
-            ticks = ticks % u64::from(SUPER_BEAT_TICKS_PER_BEAT);
-        }
+        self.saturating_mul(rhs)
+    }
+}
 
-        Self {
-            beats,
-            ticks: ticks as u32,
-        }
+/// Multiply a musical time by a scalar (i.e. for time-stretching a clip), rounded to the
+/// nearest tick.
+///
+/// Note that this operation is *NOT* lossless for large values.
+///
+/// If `rhs` is negative (or `NaN`), then a musical time of `0` will be returned. If the
+/// result is too large to fit, then the maximum possible musical time will be returned.
+impl Mul<f64> for MusicalTime {
+    type Output = Self;
+    fn mul(self, rhs: f64) -> Self::Output {
+        // Float to int casts saturate, and `NaN` is cast to `0`.
+        Self::from_total_ticks((self.total_ticks() as f64 * rhs).round() as u64)
+    }
+}
+
+/// Divide a musical time by a scalar, rounded down to the previous tick.
+///
+/// # Panics
+///
+/// This will panic if `rhs` is zero.
+impl Div<u32> for MusicalTime {
+    type Output = Self;
+    fn div(self, rhs: u32) -> Self::Output {
+        Self::from_total_ticks(self.total_ticks() / u64::from(rhs))
+    }
+}
+
+/// Divide a musical time by a scalar, rounded to the nearest tick.
+///
+/// Note that this operation is *NOT* lossless for large values.
+///
+/// If `rhs` is negative (or `NaN`), then a musical time of `0` will be returned. If the
+/// result is too large to fit (i.e. `rhs` is `0.0`), then the maximum possible musical time
+/// will be returned.
+impl Div<f64> for MusicalTime {
+    type Output = Self;
+    fn div(self, rhs: f64) -> Self::Output {
+        Self::from_total_ticks((self.total_ticks() as f64 / rhs).round() as u64)
+    }
+}
+
+/// The remainder of dividing `self` by `rhs` (i.e. for wrapping a time around a loop length
+/// or finding the offset from the previous grid line).
+///
+/// Because `MusicalTime` is unsigned, this is the same as the Euclidean remainder.
+///
+/// # Panics
+///
+/// This will panic if `rhs` is zero.
+impl Rem<MusicalTime> for MusicalTime {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self::Output {
+        Self::from_total_ticks(self.total_ticks() % rhs.total_ticks())
     }
 }
 
@@ -738,3 +1378,774 @@ impl MulAssign<u32> for MusicalTime {
         *self = *self * other
     }
 }
+impl MulAssign<f64> for MusicalTime {
+    fn mul_assign(&mut self, other: f64) {
+        *self = *self * other
+    }
+}
+impl DivAssign<u32> for MusicalTime {
+    fn div_assign(&mut self, other: u32) {
+        *self = *self / other
+    }
+}
+impl DivAssign<f64> for MusicalTime {
+    fn div_assign(&mut self, other: f64) {
+        *self = *self / other
+    }
+}
+impl RemAssign<MusicalTime> for MusicalTime {
+    fn rem_assign(&mut self, other: Self) {
+        *self = *self % other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_seconds_with_groove() {
+        let bpm = 97.0;
+        let eighth = NoteValue::Straight(8);
+
+        let downbeat = MusicalTime::from_beats(3);
+        let off_beat = MusicalTime::from_half_beats(3, 1);
+        let between = MusicalTime::from_quarter_beats(3, 1);
+
+        // No swing is the identity.
+        let groove = Groove::new(0.0, eighth);
+        for t in [downbeat, off_beat, between] {
+            assert_eq!(
+                t.to_seconds_with_groove(bpm, &groove),
+                t.to_seconds_f64(bpm)
+            );
+        }
+
+        // Swing delays the off-beat by half of the swing amount of a step, and leaves the
+        // downbeats untouched.
+        let groove = Groove::new(0.5, eighth);
+        assert_eq!(
+            downbeat.to_seconds_with_groove(bpm, &groove),
+            downbeat.to_seconds_f64(bpm)
+        );
+        assert_eq!(
+            off_beat.to_seconds_with_groove(bpm, &groove),
+            MusicalTime::from_fractional_beats::<8>(3, 5).to_seconds_f64(bpm)
+        );
+        assert!(between.to_seconds_with_groove(bpm, &groove).0 > between.to_seconds_f64(bpm).0);
+    }
+
+    #[test]
+    fn test_lerp() {
+        let a = MusicalTime::from_beats(4);
+        let b = MusicalTime::from_beats(12);
+
+        assert_eq!(MusicalTime::lerp(a, b, 0.0), a);
+        assert_eq!(MusicalTime::lerp(a, b, 1.0), b);
+        assert_eq!(MusicalTime::lerp(a, b, 0.25), MusicalTime::from_beats(6));
+        assert_eq!(MusicalTime::lerp(b, a, 0.25), MusicalTime::from_beats(10));
+
+        assert_eq!(MusicalTime::lerp(a, b, -0.5), a);
+        assert_eq!(MusicalTime::lerp(a, b, 1.5), b);
+        assert_eq!(MusicalTime::lerp(a, b, f64::NAN), a);
+
+        // Rounded to the nearest tick.
+        let c = MusicalTime::new(0, 3);
+        assert_eq!(
+            MusicalTime::lerp(MusicalTime::default(), c, 0.5),
+            MusicalTime::new(0, 2)
+        );
+
+        // The end points are exact even at very large values.
+        let max = MusicalTime::new(u32::MAX, SUPER_BEAT_TICKS_PER_BEAT - 1);
+        let almost = MusicalTime::new(u32::MAX, SUPER_BEAT_TICKS_PER_BEAT - 2);
+        assert_eq!(MusicalTime::lerp(MusicalTime::default(), max, 1.0), max);
+        assert_eq!(MusicalTime::lerp(max, almost, 0.0), max);
+    }
+
+    #[test]
+    fn test_const_constructors() {
+        const POSITIONS: [MusicalTime; 4] = [
+            MusicalTime::from_beats(1),
+            MusicalTime::from_quarter_beats(2, 3),
+            MusicalTime::from_third_beats(0, 5),
+            MusicalTime::new(3, SUPER_BEAT_TICKS_PER_BEAT),
+        ];
+
+        assert_eq!(POSITIONS[0], MusicalTime::new(1, 0));
+        assert_eq!(POSITIONS[1].as_beats_f64(), 2.75);
+        assert_eq!(POSITIONS[2], MusicalTime::from_third_beats(0, 2));
+        assert_eq!(
+            POSITIONS[3],
+            MusicalTime::new(3, SUPER_BEAT_TICKS_PER_BEAT - 1)
+        );
+
+        const FROM_TICKS: MusicalTime =
+            MusicalTime::from_total_ticks(SUPER_BEAT_TICKS_PER_BEAT as u64 * 5 + 7);
+        assert_eq!(FROM_TICKS, MusicalTime::new(5, 7));
+    }
+
+    #[test]
+    fn test_dotted_and_tuplet() {
+        let quarter = MusicalTime::from_beats(1);
+        let eighth = MusicalTime::from_half_beats(0, 1);
+
+        assert_eq!(MusicalTime::dotted(quarter, 0), quarter);
+        assert_eq!(MusicalTime::dotted(quarter, 1), eighth * 3);
+        assert_eq!(
+            MusicalTime::dotted(quarter, 2),
+            MusicalTime::from_quarter_beats(1, 3)
+        );
+
+        // A quarter note triplet is a third of a half note.
+        assert_eq!(
+            MusicalTime::tuplet(quarter, 2, 3),
+            MusicalTime::from_beats(2) / 3
+        );
+        assert_eq!(
+            MusicalTime::tuplet(quarter, 2, 3) * 3,
+            MusicalTime::from_beats(2)
+        );
+
+        // Quintuplets and septuplets stay exact.
+        assert_eq!(
+            MusicalTime::tuplet(eighth, 4, 5) * 5,
+            MusicalTime::from_beats(2)
+        );
+        assert_eq!(
+            MusicalTime::tuplet(MusicalTime::from_quarter_beats(0, 1), 4, 7) * 7,
+            MusicalTime::from_beats(1)
+        );
+
+        assert_eq!(
+            MusicalTime::tuplet(quarter, 2, 0),
+            MusicalTime::from_beats(2)
+        );
+    }
+
+    #[test]
+    fn test_bars_beats() {
+        let four_four = TimeSignature::new(4, 4);
+        let seven_eight = TimeSignature::new(7, 8);
+        let six_eight = TimeSignature::new(6, 8);
+
+        for (bars, beats, ts) in [
+            (0, 0, four_four),
+            (3, 2, four_four),
+            (1_000, 3, four_four),
+            (0, 6, seven_eight),
+            (5, 1, seven_eight),
+            (2, 5, six_eight),
+        ] {
+            let t = MusicalTime::from_bars_beats(bars, beats, ts);
+            assert_eq!(t.as_bars_beats(ts), (bars, beats, 0));
+        }
+
+        // Bar 2 of 7/8 starts after 7 eighth notes = 3.5 quarter notes.
+        assert_eq!(
+            MusicalTime::from_bars_beats(1, 0, seven_eight),
+            MusicalTime::from_half_beats(3, 1)
+        );
+
+        // Beats past the end of the bar spill over into the next bar.
+        assert_eq!(
+            MusicalTime::from_bars_beats(0, 7, seven_eight).as_bars_beats(seven_eight),
+            (1, 0, 0)
+        );
+
+        // Positions between beats keep the remainder in ticks.
+        let t =
+            MusicalTime::from_bars_beats(1, 4, six_eight) + MusicalTime::from_quarter_beats(0, 1);
+        assert_eq!(
+            t.as_bars_beats(six_eight),
+            (1, 4, u64::from(SUPER_BEAT_TICKS_PER_BEAT / 4))
+        );
+
+        // A denominator of `0` (which can only be set through the public fields) is treated
+        // as `1` instead of panicking.
+        let zero_den = TimeSignature {
+            numerator: 4,
+            denominator: 0,
+        };
+        assert_eq!(
+            MusicalTime::from_bars_beats(1, 1, zero_den),
+            MusicalTime::from_beats(20)
+        );
+        assert_eq!(
+            MusicalTime::from_beats(20).as_bars_beats(zero_den),
+            (1, 1, 0)
+        );
+    }
+
+    #[test]
+    fn test_bars_beats_relative() {
+        let ts = TimeSignature::new(4, 4);
+        // A one-beat pickup before bar 0.
+        let origin = MusicalTime::from_beats(1);
+
+        assert_eq!(
+            origin.bars_beats_relative(origin, ts),
+            (0, 0, MusicalTime::default())
+        );
+        assert_eq!(
+            MusicalTime::from_half_beats(6, 1).bars_beats_relative(origin, ts),
+            (1, 1, MusicalTime::from_half_beats(0, 1))
+        );
+
+        // The pickup lies in the last beat of bar -1.
+        assert_eq!(
+            MusicalTime::default().bars_beats_relative(origin, ts),
+            (-1, 3, MusicalTime::default())
+        );
+        assert_eq!(
+            MusicalTime::from_quarter_beats(0, 3).bars_beats_relative(origin, ts),
+            (-1, 3, MusicalTime::from_quarter_beats(0, 3))
+        );
+
+        // Further before the origin, the bar numbers keep decreasing.
+        let origin = MusicalTime::from_beats(9);
+        assert_eq!(
+            MusicalTime::from_beats(0).bars_beats_relative(origin, ts),
+            (-3, 3, MusicalTime::default())
+        );
+        assert_eq!(
+            MusicalTime::from_beats(5).bars_beats_relative(origin, ts),
+            (-1, 0, MusicalTime::default())
+        );
+
+        // In 6/8, a beat is an eighth note.
+        let ts = TimeSignature::new(6, 8);
+        assert_eq!(
+            MusicalTime::from_half_beats(0, 1).bars_beats_relative(MusicalTime::from_beats(1), ts),
+            (-1, 5, MusicalTime::default())
+        );
+    }
+
+    #[test]
+    fn test_ppq_ticks() {
+        // One beat and a sixteenth at 960 PPQ.
+        let t = MusicalTime::from_ppq_ticks(960 + 240, 960);
+        assert_eq!(t, MusicalTime::from_sixteenth_beats(1, 4));
+        assert_eq!(t.to_ppq_ticks(960), 1200);
+        assert_eq!(t.to_ppq_ticks(480), 600);
+
+        // A round trip between two resolutions that both divide the base is exact.
+        for ticks in [0u64, 1, 479, 480, 12_345, 960 * 1_000_000] {
+            let t = MusicalTime::from_ppq_ticks(ticks * 2, 960);
+            assert_eq!(t.to_ppq_ticks(480), ticks);
+            assert_eq!(MusicalTime::from_ppq_ticks(ticks, 480), t);
+            assert_eq!(
+                MusicalTime::rescale_ppq(ticks as i64 * 2, 960, 480),
+                ticks as i64
+            );
+        }
+
+        // Ticks that fall between two ticks of the coarser resolution round to the nearest.
+        assert_eq!(MusicalTime::from_ppq_ticks(1, 960).to_ppq_ticks(480), 1);
+        assert_eq!(MusicalTime::rescale_ppq(1, 960, 480), 1);
+        assert_eq!(MusicalTime::rescale_ppq(-1, 960, 480), -1);
+        assert_eq!(MusicalTime::rescale_ppq(-3, 960, 480), -2);
+        assert_eq!(MusicalTime::rescale_ppq(-960, 960, 480), -480);
+        assert_eq!(MusicalTime::rescale_ppq(5, 0, 480), 0);
+
+        assert_eq!(MusicalTime::from_ppq_ticks(100, 0), MusicalTime::default());
+    }
+
+    #[test]
+    fn test_humanize_stays_within_bounds() {
+        // A tiny deterministic pseudo-random generator (xorshift).
+        let mut state = 0x9E37_79B9u32;
+        let mut rng = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            f64::from(state) / f64::from(u32::MAX)
+        };
+
+        let t = MusicalTime::from_beats(4);
+        let max_offset = MusicalTime::from_sixteenth_beats(0, 1);
+        let min = t.checked_sub(max_offset).unwrap();
+        let max = t + max_offset;
+
+        let mut moved = false;
+        for _ in 0..1_000 {
+            let h = t.humanize(max_offset, &mut rng);
+            assert!(h >= min && h <= max, "{:?}", h);
+            moved |= h != t;
+        }
+        assert!(moved);
+
+        // The extremes of the RNG map to the extremes of the range.
+        assert_eq!(t.humanize(max_offset, &mut || 0.0), min);
+        assert_eq!(t.humanize(max_offset, &mut || 1.0), max);
+        assert_eq!(t.humanize(max_offset, &mut || 0.5), t);
+
+        // Never goes below zero.
+        assert_eq!(
+            MusicalTime::default().humanize(max_offset, &mut || 0.0),
+            MusicalTime::default()
+        );
+    }
+
+    #[test]
+    fn test_mul_at_large_values() {
+        let t = MusicalTime::from_quarter_beats(1_000_000, 3);
+
+        assert_eq!(t * 4, MusicalTime::from_beats(4_000_003));
+        assert_eq!(t.checked_mul(4), Some(MusicalTime::from_beats(4_000_003)));
+
+        // 1,000,000.75 * 5,000 = 5,000,003,750 beats, which does not fit in a `u32`.
+        assert_eq!(t.checked_mul(5_000), None);
+        assert_eq!(
+            t.saturating_mul(5_000),
+            MusicalTime::new(u32::MAX, SUPER_BEAT_TICKS_PER_BEAT - 1)
+        );
+
+        // Exactly the largest whole number of beats still fits.
+        assert_eq!(
+            MusicalTime::from_beats(u32::MAX / 5).checked_mul(5),
+            Some(MusicalTime::from_beats(u32::MAX))
+        );
+        assert_eq!(
+            MusicalTime::from_beats(u32::MAX).checked_mul(0),
+            Some(MusicalTime::default())
+        );
+    }
+
+    #[test]
+    fn test_scalar_mul_div() {
+        assert_eq!(MusicalTime::from_beats(2) * 3, MusicalTime::from_beats(6));
+        assert_eq!(MusicalTime::from_beats(6) / 3, MusicalTime::from_beats(2));
+        assert_eq!(
+            MusicalTime::from_beats(3) / 4,
+            MusicalTime::from_quarter_beats(0, 3)
+        );
+        assert_eq!(MusicalTime::new(0, 7) / 2, MusicalTime::new(0, 3));
+
+        assert_eq!(MusicalTime::from_beats(2) * 1.5, MusicalTime::from_beats(3));
+        assert_eq!(
+            MusicalTime::from_beats(3) / 2.0,
+            MusicalTime::from_half_beats(1, 1)
+        );
+        assert_eq!(MusicalTime::from_beats(3) * -1.0, MusicalTime::default());
+        assert_eq!(
+            MusicalTime::from_beats(3) * f64::NAN,
+            MusicalTime::default()
+        );
+        assert_eq!(
+            MusicalTime::from_beats(3) / 0.0,
+            MusicalTime::new(u32::MAX, SUPER_BEAT_TICKS_PER_BEAT - 1)
+        );
+
+        let mut t = MusicalTime::from_beats(4);
+        t /= 2;
+        t *= 0.25;
+        assert_eq!(t, MusicalTime::from_half_beats(0, 1));
+
+        // Scaling composes with the conversion to seconds.
+        let t = MusicalTime::from_quarter_beats(5, 1);
+        assert_eq!(
+            (t * 2).to_seconds_f64(120.0).0,
+            t.to_seconds_f64(120.0).0 * 2.0
+        );
+        assert_eq!(
+            (t * 0.5).to_seconds_f64(90.0).0,
+            t.to_seconds_f64(90.0).0 * 0.5
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "MusicalTime multiplication overflowed")]
+    fn test_mul_overflow_panics() {
+        let _ = MusicalTime::from_beats(u32::MAX) * 2;
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[test]
+    fn test_mul_overflow_saturates() {
+        assert_eq!(
+            MusicalTime::from_beats(u32::MAX) * 2,
+            MusicalTime::from_beats(u32::MAX).saturating_mul(2)
+        );
+    }
+
+    #[test]
+    fn test_compact_string_round_trip() {
+        for (t, s) in [
+            (MusicalTime::from_beats(0), "0b"),
+            (MusicalTime::from_beats(4), "4b"),
+            (MusicalTime::from_quarter_beats(3, 3), "3+3/4b"),
+            (MusicalTime::from_third_beats(12, 1), "12+1/3b"),
+            (
+                MusicalTime::from_fractional_beats::<1_920>(0, 7),
+                "0+7/1920b",
+            ),
+            (MusicalTime::new(0, 1_920), "1920sb"),
+            (
+                MusicalTime::new(2, 1),
+                "2483712001sb", // 2 * 1,241,856,000 + 1
+            ),
+        ] {
+            assert_eq!(t.to_compact_string(), s);
+            assert_eq!(MusicalTime::from_compact_string(s), Some(t));
+        }
+
+        // Other spellings of the same times.
+        assert_eq!(
+            MusicalTime::from_compact_string(" 3+6/8b "),
+            Some(MusicalTime::from_quarter_beats(3, 3))
+        );
+        assert_eq!(
+            MusicalTime::from_compact_string("2483712000sb"),
+            Some(MusicalTime::from_beats(2))
+        );
+    }
+
+    #[test]
+    fn test_compact_string_invalid() {
+        for s in [
+            "",
+            "4",
+            "b",
+            "-4b",
+            "+4b",
+            "-1920sb",
+            "4.5b",
+            "3+4/4b",
+            "3+1/0b",
+            "3+1/7919b",
+            "3+1/4",
+            "4bb",
+            "18446744073709551615sb",
+        ] {
+            assert_eq!(MusicalTime::from_compact_string(s), None, "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn test_from_64th_beats() {
+        // 64 64th-beats make up a beat.
+        assert_eq!(
+            MusicalTime::from_64th_beats(0, 32),
+            MusicalTime::from_half_beats(0, 1)
+        );
+        assert_eq!(
+            MusicalTime::from_64th_beats(0, 1).ticks() * 64,
+            SUPER_BEAT_TICKS_PER_BEAT
+        );
+        assert_eq!(
+            MusicalTime::from_64th_beats(1, 0),
+            MusicalTime::from_beats(1)
+        );
+        assert_eq!(MusicalTime::from_64th_beats(2, 63).num_64th_beats(), 63);
+    }
+
+    #[test]
+    fn test_from_fractional_beat_helpers() {
+        type Helper = fn(u32, u32) -> MusicalTime;
+        let helpers: [(Helper, u32); 22] = [
+            (MusicalTime::from_half_beats, 2),
+            (MusicalTime::from_quarter_beats, 4),
+            (MusicalTime::from_eighth_beats, 8),
+            (MusicalTime::from_sixteenth_beats, 16),
+            (MusicalTime::from_32nd_beats, 32),
+            (MusicalTime::from_64th_beats, 64),
+            (MusicalTime::from_128th_beats, 128),
+            (MusicalTime::from_256th_beats, 256),
+            (MusicalTime::from_512th_beats, 512),
+            (MusicalTime::from_1024th_beats, 1_024),
+            (MusicalTime::from_2048th_beats, 2_048),
+            (MusicalTime::from_third_beats, 3),
+            (MusicalTime::from_fifth_beats, 5),
+            (MusicalTime::from_sixth_beats, 6),
+            (MusicalTime::from_seventh_beats, 7),
+            (MusicalTime::from_ninth_beats, 9),
+            (MusicalTime::from_tenth_beats, 10),
+            (MusicalTime::from_eleventh_beats, 11),
+            (MusicalTime::from_twelth_beats, 12),
+            (MusicalTime::from_24th_beats, 24),
+            (MusicalTime::from_fractional_beats::<100>, 100),
+            (MusicalTime::from_fractional_beats::<1_920>, 1_920),
+        ];
+
+        for (helper, divisor) in helpers.iter() {
+            for fract in [0, 1, divisor / 2, divisor - 1] {
+                let t = helper(7, fract);
+                let expected = 7.0 + (f64::from(fract) / f64::from(*divisor));
+
+                assert!((t.as_beats_f64() - expected).abs() < 1e-12, "1/{}", divisor);
+                assert_eq!(
+                    u64::from(t.ticks()) * u64::from(*divisor),
+                    u64::from(fract) * u64::from(SUPER_BEAT_TICKS_PER_BEAT),
+                    "1/{}",
+                    divisor
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_beats_f64_round_trip() {
+        for t in [
+            MusicalTime::from_beats(0),
+            MusicalTime::from_quarter_beats(3, 3),
+            MusicalTime::from_third_beats(17, 1),
+            MusicalTime::from_fractional_beats::<64>(1_000, 63),
+            MusicalTime::from_fractional_beats::<1_920>(5, 7),
+        ] {
+            assert_eq!(MusicalTime::from_beats_f64(t.as_beats_f64()), t);
+        }
+        assert_eq!(
+            MusicalTime::from_beats_f64(3.75),
+            MusicalTime::from_quarter_beats(3, 3)
+        );
+
+        // Values off the grid are within one tick.
+        for beats in [0.1234567, 42.987654321, 12_345.000_000_1] {
+            let t = MusicalTime::from_beats_f64(beats);
+            let ticks = beats * f64::from(SUPER_BEAT_TICKS_PER_BEAT);
+            assert!((t.total_ticks() as f64 - ticks).abs() <= 1.0);
+        }
+    }
+
+    #[test]
+    fn test_from_beats_f64_saturates() {
+        let max = MusicalTime::from_total_ticks(u64::MAX);
+
+        assert_eq!(MusicalTime::from_beats_f64(-3.5), MusicalTime::default());
+        assert_eq!(
+            MusicalTime::from_beats_f64(f64::NAN),
+            MusicalTime::default()
+        );
+        assert_eq!(MusicalTime::from_beats_f64(1.0e12), max);
+        assert_eq!(MusicalTime::from_beats_f64(f64::INFINITY), max);
+        assert_eq!(
+            MusicalTime::from_beats_f64(f64::from(u32::MAX) + 0.999_999_999_9),
+            max
+        );
+    }
+
+    #[test]
+    fn test_to_seconds_with_spb() {
+        for bpm in [60.0, 97.5, 120.0, 174.0] {
+            let spb = SecondsPerBeat::from_bpm(bpm);
+            assert!((spb.to_bpm() - bpm).abs() < 1e-9);
+
+            for t in [
+                MusicalTime::from_beats(0),
+                MusicalTime::from_quarter_beats(3, 1),
+                MusicalTime::from_third_beats(1_000, 2),
+            ] {
+                let expected = t.to_seconds_f64(bpm).0;
+                assert!((t.to_seconds_with_spb(spb).0 - expected).abs() <= expected * 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rem() {
+        let a = MusicalTime::from_quarter_beats(9, 3);
+        let b = MusicalTime::from_half_beats(2, 1);
+
+        assert_eq!((a % b).total_ticks(), a.total_ticks() % b.total_ticks());
+        assert_eq!(a % b, MusicalTime::from_quarter_beats(2, 1));
+
+        let mut c = a;
+        c %= MusicalTime::from_beats(4);
+        assert_eq!(c, MusicalTime::from_quarter_beats(1, 3));
+    }
+
+    #[test]
+    fn test_super_frame_exact_round_trip() {
+        // 127.3 BPM
+        let (num, den) = (1273, 10);
+
+        for beats in [0, 1, 7_200, 7_639, 45_834] {
+            let t = MusicalTime::new(beats, 987_654_321);
+
+            let sc = t.to_super_frame_exact(num, den);
+            let back = MusicalTime::from_super_frame_exact(sc, num, den);
+            let sc_back = back.to_super_frame_exact(num, den);
+
+            let diff = sc.total_ticks().abs_diff(sc_back.total_ticks());
+            assert!(diff <= 1, "{} beats: off by {} super-frames", beats, diff);
+
+            // The f64 path stays within one super-frame as well.
+            let sc_f64 = t.to_nearest_super_frame_round(127.3);
+            let diff = sc.total_ticks().abs_diff(sc_f64.total_ticks());
+            assert!(
+                diff <= 1,
+                "{} beats: f64 off by {} super-frames",
+                beats,
+                diff
+            );
+        }
+
+        // One hour of music at 120 BPM is exactly 3600 seconds.
+        assert_eq!(
+            MusicalTime::from_beats(7_200).to_super_frame_exact(120, 1),
+            SuperclockTime::from_seconds(3_600)
+        );
+        assert_eq!(
+            MusicalTime::from_super_frame_exact(SuperclockTime::from_seconds(3_600), 120, 1),
+            MusicalTime::from_beats(7_200)
+        );
+    }
+
+    #[test]
+    fn test_arp_steps() {
+        let rate = MusicalTime::from_quarter_beats(0, 1);
+        let steps = MusicalTime::arp_steps(MusicalTime::from_beats(2), rate, 8, 0.5);
+
+        assert_eq!(steps.len(), 8);
+        assert_eq!(steps[0].0, MusicalTime::from_beats(2));
+        for w in steps.windows(2) {
+            assert_eq!(w[0].0 + rate, w[1].0);
+        }
+        for (on, off) in steps.iter() {
+            assert_eq!(*on + MusicalTime::from_eighth_beats(0, 1), *off);
+        }
+
+        let legato = MusicalTime::arp_steps(MusicalTime::from_beats(0), rate, 4, 1.0);
+        for w in legato.windows(2) {
+            assert_eq!(w[0].1, w[1].0);
+        }
+
+        // The gate is constrained to the range `[0.0, 1.0]`.
+        let steps = MusicalTime::arp_steps(MusicalTime::from_beats(0), rate, 2, 0.0);
+        assert!(steps.iter().all(|(on, off)| on == off));
+        let steps = MusicalTime::arp_steps(MusicalTime::from_beats(0), rate, 2, 2.0);
+        assert_eq!(steps, legato[..2]);
+
+        // Steps stop once the note-on time no longer fits, instead of overflowing.
+        let start = MusicalTime::from_beats(u32::MAX - 1);
+        let steps = MusicalTime::arp_steps(start, MusicalTime::from_beats(1), 4, 1.0);
+        assert_eq!(
+            steps,
+            vec![
+                (start, MusicalTime::from_beats(u32::MAX)),
+                (
+                    MusicalTime::from_beats(u32::MAX),
+                    MusicalTime::new(u32::MAX, SUPER_BEAT_TICKS_PER_BEAT - 1)
+                ),
+            ]
+        );
+        let steps = MusicalTime::arp_steps(
+            MusicalTime::from_beats(0),
+            MusicalTime::from_beats(u32::MAX),
+            usize::MAX,
+            0.5,
+        );
+        assert_eq!(steps.len(), 2);
+    }
+
+    #[test]
+    fn test_nearest_note_value() {
+        assert_eq!(
+            MusicalTime::from_beats(1).nearest_note_value(),
+            NoteValue::Straight(4)
+        );
+        assert_eq!(
+            MusicalTime::from_quarter_beats(0, 3).nearest_note_value(),
+            NoteValue::Dotted(8)
+        );
+        assert_eq!(
+            MusicalTime::from_fractional_beats::<3>(0, 1).nearest_note_value(),
+            NoteValue::Triplet(8)
+        );
+        assert_eq!(
+            MusicalTime::from_beats(4).nearest_note_value(),
+            NoteValue::Straight(1)
+        );
+
+        // Slightly off durations still map to the nearest note value.
+        let almost_quarter = MusicalTime::from_beats(1)
+            .saturating_sub_to_zero(MusicalTime::from_fractional_beats::<100>(0, 1));
+        assert_eq!(almost_quarter.nearest_note_value(), NoteValue::Straight(4));
+
+        assert_eq!(NoteValue::Dotted(4).to_string(), "1/4 dotted");
+        assert_eq!(NoteValue::Triplet(8).to_string(), "1/8 triplet");
+    }
+
+    #[test]
+    fn test_swung_step_positions() {
+        let ts = TimeSignature::new(4, 4);
+
+        let even = MusicalTime::swung_step_positions(1, 16, 0.0, ts);
+        assert_eq!(even.len(), 16);
+        for (i, t) in even.iter().enumerate() {
+            assert_eq!(
+                *t,
+                MusicalTime::from_quarter_beats(i as u32 / 4, i as u32 % 4)
+            );
+        }
+
+        let swung = MusicalTime::swung_step_positions(2, 16, 0.6, ts);
+        assert_eq!(swung.len(), 32);
+        for (i, t) in swung.iter().enumerate() {
+            let i = i as u32;
+            let even_pos = MusicalTime::from_quarter_beats(i / 4, i % 4);
+            if i % 2 == 0 {
+                assert_eq!(*t, even_pos);
+            } else {
+                // Delayed by 0.6 * (1/8 beat) = 0.075 beats.
+                assert_eq!(
+                    t.total_ticks() - even_pos.total_ticks(),
+                    u64::from(SUPER_BEAT_TICKS_PER_BEAT) * 3 / 40
+                );
+            }
+        }
+
+        // 6/8 has three quarter-note beats per bar.
+        let six_eight = MusicalTime::swung_step_positions(2, 6, 0.0, TimeSignature::new(6, 8));
+        assert_eq!(six_eight[6], MusicalTime::from_beats(3));
+    }
+
+    #[test]
+    fn test_snap_clamped() {
+        let grid = MusicalTime::from_half_beats(0, 1);
+        let range = MusicalTimeRange::new(
+            MusicalTime::from_quarter_beats(2, 1),
+            MusicalTime::from_beats(6),
+        );
+
+        // Inside the range.
+        assert_eq!(
+            MusicalTime::from_eighth_beats(3, 1).snap_clamped(grid, range),
+            MusicalTime::from_half_beats(3, 0)
+        );
+        // Dragged past the start, snaps inward to the first grid line inside the range.
+        assert_eq!(
+            MusicalTime::from_beats(1).snap_clamped(grid, range),
+            MusicalTime::from_half_beats(2, 1)
+        );
+        // Dragged past the end, the grid line exactly on the boundary is allowed.
+        assert_eq!(
+            MusicalTime::from_beats(9).snap_clamped(grid, range),
+            MusicalTime::from_beats(6)
+        );
+        // No grid line inside the range.
+        let narrow = MusicalTimeRange::new(
+            MusicalTime::from_eighth_beats(2, 1),
+            MusicalTime::from_eighth_beats(2, 3),
+        );
+        assert_eq!(
+            MusicalTime::from_beats(9).snap_clamped(grid, narrow),
+            MusicalTime::from_eighth_beats(2, 3)
+        );
+    }
+
+    #[test]
+    fn test_saturating_sub_to_zero() {
+        let a = MusicalTime::from_quarter_beats(4, 1);
+        let b = MusicalTime::from_quarter_beats(1, 3);
+
+        assert_eq!(
+            a.saturating_sub_to_zero(b),
+            MusicalTime::from_quarter_beats(2, 2)
+        );
+        assert_eq!(b.saturating_sub_to_zero(a), MusicalTime::default());
+        assert_eq!(a.saturating_sub_to_zero(a), MusicalTime::default());
+    }
+}
@@ -0,0 +1,145 @@
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+
+use super::{MusicalTime, TimeSignature};
+
+/// A range of musical time, where `start` is inclusive and `end` is exclusive.
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MusicalTimeRange {
+    pub start: MusicalTime,
+    pub end: MusicalTime,
+}
+
+impl MusicalTimeRange {
+    /// * `start` - The start of the range (inclusive).
+    /// * `end` - The end of the range (exclusive).
+    pub const fn new(start: MusicalTime, end: MusicalTime) -> Self {
+        Self { start, end }
+    }
+
+    /// The length of this range.
+    ///
+    /// If `end` is less than `start`, then a length of zero will be returned.
+    pub fn length(&self) -> MusicalTime {
+        self.end.saturating_sub_to_zero(self.start)
+    }
+
+    /// Returns `true` if `t` lies within this range.
+    pub fn contains(&self, t: MusicalTime) -> bool {
+        t >= self.start && t < self.end
+    }
+
+    /// Map the absolute position `pos` into this range, treating it as a loop region (i.e. for
+    /// finding where a transport that is looping this range actually is).
+    ///
+    /// Positions at or past `end` wrap back around to `start` (as many times as needed).
+    /// Positions before `start` are returned unchanged, since the playhead has not reached
+    /// the loop yet. If this range has a length of zero, then `start` will be returned.
+    pub fn map_position(&self, pos: MusicalTime) -> MusicalTime {
+        let length = self.length();
+        if length == MusicalTime::default() {
+            return self.start;
+        }
+        if pos < self.end {
+            return pos;
+        }
+
+        self.start + (pos.saturating_sub_to_zero(self.start) % length)
+    }
+
+    /// Split this range into one sub-range per bar it spans, where bars lie on the bar lines of
+    /// the given time signature (counting from time `0`).
+    ///
+    /// The first and last sub-ranges are partial bars if this range does not start or end on a
+    /// bar line. If this range is empty, then an empty `Vec` will be returned.
+    pub fn split_into_bars(&self, ts: TimeSignature) -> Vec<MusicalTimeRange> {
+        let start = self.start.total_ticks();
+        let end = self.end.total_ticks();
+        let bar_len = ts.bar_length_ticks().max(1);
+
+        let mut bars = Vec::new();
+        let mut bar_start = start;
+        while bar_start < end {
+            let bar_end = (((bar_start / bar_len) + 1) * bar_len).min(end);
+
+            bars.push(MusicalTimeRange::new(
+                MusicalTime::from_total_ticks(bar_start),
+                MusicalTime::from_total_ticks(bar_end),
+            ));
+
+            bar_start = bar_end;
+        }
+
+        bars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_position() {
+        let range = MusicalTimeRange::new(MusicalTime::from_beats(4), MusicalTime::from_beats(8));
+
+        // One beat past the end wraps to one beat past the start.
+        assert_eq!(
+            range.map_position(MusicalTime::from_beats(9)),
+            MusicalTime::from_beats(5)
+        );
+        assert_eq!(
+            range.map_position(MusicalTime::from_beats(8)),
+            MusicalTime::from_beats(4)
+        );
+        assert_eq!(
+            range.map_position(MusicalTime::from_quarter_beats(22, 1)),
+            MusicalTime::from_quarter_beats(6, 1)
+        );
+
+        // Inside the range and before the range.
+        assert_eq!(
+            range.map_position(MusicalTime::from_beats(6)),
+            MusicalTime::from_beats(6)
+        );
+        assert_eq!(
+            range.map_position(MusicalTime::from_beats(1)),
+            MusicalTime::from_beats(1)
+        );
+
+        let empty = MusicalTimeRange::new(MusicalTime::from_beats(4), MusicalTime::from_beats(4));
+        assert_eq!(
+            empty.map_position(MusicalTime::from_beats(9)),
+            MusicalTime::from_beats(4)
+        );
+    }
+
+    #[test]
+    fn test_split_into_bars() {
+        let ts = TimeSignature::new(4, 4);
+
+        let range = MusicalTimeRange::new(MusicalTime::from_beats(0), MusicalTime::from_beats(10));
+        assert_eq!(
+            range.split_into_bars(ts),
+            vec![
+                MusicalTimeRange::new(MusicalTime::from_beats(0), MusicalTime::from_beats(4)),
+                MusicalTimeRange::new(MusicalTime::from_beats(4), MusicalTime::from_beats(8)),
+                MusicalTimeRange::new(MusicalTime::from_beats(8), MusicalTime::from_beats(10)),
+            ]
+        );
+
+        // Partial bars at both ends.
+        let range = MusicalTimeRange::new(MusicalTime::from_beats(2), MusicalTime::from_beats(12));
+        assert_eq!(
+            range.split_into_bars(ts),
+            vec![
+                MusicalTimeRange::new(MusicalTime::from_beats(2), MusicalTime::from_beats(4)),
+                MusicalTimeRange::new(MusicalTime::from_beats(4), MusicalTime::from_beats(8)),
+                MusicalTimeRange::new(MusicalTime::from_beats(8), MusicalTime::from_beats(12)),
+            ]
+        );
+
+        let empty = MusicalTimeRange::new(MusicalTime::from_beats(4), MusicalTime::from_beats(4));
+        assert!(empty.split_into_bars(ts).is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+
+use super::{MusicalTime, SUPER_BEAT_TICKS_PER_BEAT};
+
+/// A standard note value (i.e. "1/8", "1/4 dotted", "1/8 triplet").
+///
+/// The contained value is the denominator of the (straight) note value, so `Straight(4)` is a
+/// quarter note and `Triplet(8)` is an eighth-note triplet.
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoteValue {
+    /// A straight note value.
+    Straight(u32),
+    /// A dotted note value (1.5 times the length of the straight note value).
+    Dotted(u32),
+    /// A triplet note value (2/3 the length of the straight note value).
+    Triplet(u32),
+}
+
+impl NoteValue {
+    /// The denominators of the note values considered by
+    /// `MusicalTime::nearest_note_value()`, from a whole note down to a 64th note.
+    pub const DENOMINATORS: [u32; 7] = [1, 2, 4, 8, 16, 32, 64];
+
+    /// The length of this note value.
+    ///
+    /// Note that a "beat" in [`MusicalTime`] is always a quarter note.
+    ///
+    /// [`MusicalTime`]: struct.MusicalTime.html
+    pub fn to_musical(&self) -> MusicalTime {
+        let straight = |denominator: u32| -> u64 {
+            (u64::from(SUPER_BEAT_TICKS_PER_BEAT) * 4) / u64::from(denominator.max(1))
+        };
+
+        MusicalTime::from_total_ticks(match *self {
+            NoteValue::Straight(d) => straight(d),
+            NoteValue::Dotted(d) => (straight(d) * 3) / 2,
+            NoteValue::Triplet(d) => (straight(d) * 2) / 3,
+        })
+    }
+}
+
+impl std::fmt::Display for NoteValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NoteValue::Straight(d) => write!(f, "1/{}", d),
+            NoteValue::Dotted(d) => write!(f, "1/{} dotted", d),
+            NoteValue::Triplet(d) => write!(f, "1/{} triplet", d),
+        }
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+
 use std::ops::{Div, Mul};
 
 /// Sampling rate in samples per second.
@@ -6,6 +9,11 @@ use std::ops::{Div, Mul};
 pub struct SampleRate(pub f64);
 
 impl SampleRate {
+    /// Create a new sample rate, panicking if it is not greater than `0.0`.
+    ///
+    /// This is not a `const fn`, because comparing floats in a `const fn` requires a newer
+    /// version of Rust than the minimum supported one. Use `SampleRate(sample_rate)` to
+    /// declare a constant instead.
     pub fn new(sample_rate: f64) -> Self {
         assert!(sample_rate > 0.0);
 
@@ -24,7 +32,7 @@ impl SampleRate {
     }
 
     pub fn as_f64(&self) -> f64 {
-        self.0 as f64
+        self.0
     }
 
     pub fn as_u16(&self) -> u16 {
@@ -38,6 +46,30 @@ impl SampleRate {
     pub fn as_usize(&self) -> usize {
         self.0.round() as usize
     }
+
+    /// Returns the Nyquist frequency in Hz (half of the sample rate).
+    pub fn nyquist(&self) -> f32 {
+        (self.0 / 2.0) as f32
+    }
+}
+
+/// Returns the center frequency in Hz of the given FFT `bin`.
+///
+/// * `bin` - The index of the bin, where bin `fft_size / 2` lies at the Nyquist frequency.
+/// * `fft_size` - The size of the FFT.
+/// * `sample_rate` - The sample rate of the analyzed signal.
+pub fn bin_to_hz(bin: usize, fft_size: usize, sample_rate: SampleRate) -> f32 {
+    (bin as f64 * sample_rate.0 / fft_size as f64) as f32
+}
+
+/// Returns the (fractional) FFT bin index at the given frequency in Hz. This is the inverse of
+/// `bin_to_hz()`.
+///
+/// * `hz` - The frequency in Hz.
+/// * `fft_size` - The size of the FFT.
+/// * `sample_rate` - The sample rate of the analyzed signal.
+pub fn hz_to_bin(hz: f32, fft_size: usize, sample_rate: SampleRate) -> f32 {
+    (f64::from(hz) * fft_size as f64 / sample_rate.0) as f32
 }
 
 impl Default for SampleRate {
@@ -91,3 +123,24 @@ impl Div<SampleRate> for f64 {
         self / rhs.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fft_bins() {
+        let sample_rate = SampleRate(48_000.0);
+
+        assert_eq!(sample_rate.nyquist(), 24_000.0);
+        assert_eq!(bin_to_hz(1_024, 2_048, sample_rate), sample_rate.nyquist());
+        assert_eq!(bin_to_hz(0, 2_048, sample_rate), 0.0);
+        assert_eq!(bin_to_hz(1, 2_048, sample_rate), 23.4375);
+
+        assert_eq!(
+            hz_to_bin(sample_rate.nyquist(), 2_048, sample_rate),
+            1_024.0
+        );
+        assert_eq!(hz_to_bin(1_000.0, 2_048, sample_rate), 1_000.0 / 23.4375);
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 
 use super::{FrameTime, MusicalTime, SampleRate, SuperclockTime};
@@ -8,7 +11,7 @@ use super::{FrameTime, MusicalTime, SampleRate, SuperclockTime};
 pub struct SecondsF64(pub f64);
 
 impl SecondsF64 {
-    pub fn new(seconds: f64) -> Self {
+    pub const fn new(seconds: f64) -> Self {
         SecondsF64(seconds)
     }
 
@@ -16,6 +19,17 @@ impl SecondsF64 {
         self.0 as f32
     }
 
+    /// Linearly interpolate between the times `a` and `b` (i.e. for automation or animation).
+    ///
+    /// * `t` - The interpolation factor, where `0.0` gives exactly `a` and `1.0` gives exactly
+    ///   `b`. This is clamped to the range `[0.0, 1.0]`, and `NaN` is treated as `0.0`.
+    pub fn lerp(a: SecondsF64, b: SecondsF64, t: f64) -> SecondsF64 {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+
+        // This form (as opposed to `a + ((b - a) * t)`) lands exactly on `b` when `t == 1.0`.
+        SecondsF64((a.0 * (1.0 - t)) + (b.0 * t))
+    }
+
     /// Creates a new time in `Seconds` from [`FrameTime`] and a [`SampleRate`].
     ///
     /// Note that this conversion is *NOT* lossless.
@@ -163,6 +177,23 @@ impl SecondsF64 {
     }
 }
 
+/// Returns the length of a ring buffer (in frames) large enough to hold the given `lookahead`
+/// time plus a block of `block_size` frames, rounded up to the next power of two.
+///
+/// This is useful for sizing the delay buffer of a processor with lookahead (i.e. a limiter).
+///
+/// * `lookahead` - The lookahead time. This is ceil-ed to the nearest frame.
+/// * `block_size` - The maximum number of frames in a processing block.
+/// * `sample_rate` - The sample rate of the stream.
+pub fn lookahead_buffer_len(
+    lookahead: SecondsF64,
+    block_size: FrameTime,
+    sample_rate: SampleRate,
+) -> FrameTime {
+    let frames = lookahead.to_nearest_frame_ceil(sample_rate).0 + block_size.0;
+    FrameTime(frames.next_power_of_two())
+}
+
 impl Default for SecondsF64 {
     fn default() -> Self {
         SecondsF64(0.0)
@@ -250,3 +281,43 @@ impl DivAssign<SecondsF64> for SecondsF64 {
         self.0 /= other.0;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lerp() {
+        let a = SecondsF64(0.1);
+        let b = SecondsF64(0.7);
+
+        assert_eq!(SecondsF64::lerp(a, b, 0.0), a);
+        assert_eq!(SecondsF64::lerp(a, b, 1.0), b);
+        assert!((SecondsF64::lerp(a, b, 0.5).0 - 0.4).abs() < 1.0e-12);
+
+        // Going backwards, and out-of-range factors.
+        assert!((SecondsF64::lerp(b, a, 0.25).0 - 0.55).abs() < 1.0e-12);
+        assert_eq!(SecondsF64::lerp(a, b, -1.0), a);
+        assert_eq!(SecondsF64::lerp(a, b, 2.0), b);
+        assert_eq!(SecondsF64::lerp(a, b, f64::NAN), a);
+    }
+
+    #[test]
+    fn test_lookahead_buffer_len() {
+        let sample_rate = SampleRate(48_000.0);
+
+        assert_eq!(
+            lookahead_buffer_len(SecondsF64(0.005), FrameTime(128), sample_rate),
+            FrameTime(512)
+        );
+        assert_eq!(
+            lookahead_buffer_len(SecondsF64(0.0), FrameTime(128), sample_rate),
+            FrameTime(128)
+        );
+        // 384 frames of lookahead plus 128 frames fits exactly.
+        assert_eq!(
+            lookahead_buffer_len(SecondsF64(0.008), FrameTime(128), sample_rate),
+            FrameTime(512)
+        );
+    }
+}
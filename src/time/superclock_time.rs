@@ -1,4 +1,8 @@
-use std::ops::{Add, AddAssign, Mul, MulAssign};
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+
+use std::convert::TryFrom;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Rem, RemAssign};
 
 use super::{FrameTime, MusicalTime, SampleRate, SecondsF64};
 
@@ -14,7 +18,7 @@ pub static SUPER_SAMPLE_TICKS_PER_SECOND: u32 = 282_240_000;
 /// 88,200, 96,000, 176,400, 192,000, 352,800, and 384,000`. This ensures that no information is
 /// lost when switching between sample rates.
 #[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
-#[derive(Default, Debug, Clone, Copy, Hash)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SuperclockTime {
     seconds: u32,
     ticks: u32,
@@ -23,16 +27,20 @@ pub struct SuperclockTime {
 impl SuperclockTime {
     /// * `seconds` - The time in seconds.
     /// * `ticks` - The number of ticks (after the time in `seconds`) (Note this value
-    /// will be constrained to the range `[0, 282,240,000)`).
+    ///   will be constrained to the range `[0, 282,240,000)`).
     ///
     /// A "tick" is a unit of time that is exactly 1 / 282,240,000 of a second. This number
     /// happens to be nicely divisible by all common sampling rates: `22,050, 24,000, 44,100,
     /// 48,000, 88,200, 96,000, 176,400, 192,000, 352,800, and 384,000`. This ensures that no
     /// information is lost when switching between sample rates.
-    pub fn new(seconds: u32, ticks: u32) -> Self {
+    pub const fn new(seconds: u32, ticks: u32) -> Self {
         Self {
             seconds,
-            ticks: ticks.min(SUPER_SAMPLE_TICKS_PER_SECOND - 1),
+            ticks: if ticks < SUPER_SAMPLE_TICKS_PER_SECOND {
+                ticks
+            } else {
+                SUPER_SAMPLE_TICKS_PER_SECOND - 1
+            },
         }
     }
 
@@ -63,8 +71,30 @@ impl SuperclockTime {
         (u64::from(self.seconds) * u64::from(SUPER_SAMPLE_TICKS_PER_SECOND)) + u64::from(self.ticks)
     }
 
+    /// Create a superclock time from the total number of ticks.
+    ///
+    /// If the resulting number of seconds does not fit in a `u32`, then the maximum possible
+    /// superclock time will be returned instead.
+    ///
+    /// A "tick" is a unit of time that is exactly 1 / 282,240,000 of a second.
+    pub const fn from_total_ticks(total_ticks: u64) -> Self {
+        // `u64::from()` cannot be used in a `const fn`.
+        let seconds = total_ticks / SUPER_SAMPLE_TICKS_PER_SECOND as u64;
+        if seconds > u32::MAX as u64 {
+            Self {
+                seconds: u32::MAX,
+                ticks: SUPER_SAMPLE_TICKS_PER_SECOND - 1,
+            }
+        } else {
+            Self {
+                seconds: seconds as u32,
+                ticks: (total_ticks % SUPER_SAMPLE_TICKS_PER_SECOND as u64) as u32,
+            }
+        }
+    }
+
     /// * `seconds` - The time in seconds.
-    pub fn from_seconds(seconds: u32) -> Self {
+    pub const fn from_seconds(seconds: u32) -> Self {
         Self { seconds, ticks: 0 }
     }
 
@@ -210,25 +240,50 @@ impl SuperclockTime {
     /// common sample rates: `22,050, 24,000, 44,100, 48,000, 88,200, 96,000, 176,400, 192,000,
     /// 352,800, or 384,000`. Otherwise, please use `Self::from_frame()`.
     ///
+    /// If the time is too large to fit, then the maximum value will be returned.
+    ///
     /// [`SuperclockTime`]: struct.SuperclockTime.html
     /// [`FrameTime`]: struct.FrameTime.html
     pub fn from_frame_with_common_framerate<const SAMPLE_RATE: u32>(sample: FrameTime) -> Self {
-        // Make sure that the compiler optimizes these two operations into a single operation.
-        let seconds = sample.0 / u64::from(SAMPLE_RATE);
-        let samples_after = sample.0 % u64::from(SAMPLE_RATE);
+        Self::from_frame_exact(
+            sample,
+            SAMPLE_RATE,
+            SUPER_SAMPLE_TICKS_PER_SECOND / SAMPLE_RATE,
+        )
+    }
 
-        Self {
-            seconds: seconds as u32,
-            ticks: (samples_after as u32) * (SUPER_SAMPLE_TICKS_PER_SECOND / SAMPLE_RATE),
+    /// Convert frames to [`SuperclockTime`] with integer math, for a sample rate that evenly
+    /// divides `SUPER_SAMPLE_TICKS_PER_SECOND` into `ticks_per_frame` ticks. Saturates at the
+    /// maximum value.
+    ///
+    /// [`SuperclockTime`]: struct.SuperclockTime.html
+    #[inline]
+    fn from_frame_exact(sample: FrameTime, sample_rate: u32, ticks_per_frame: u32) -> Self {
+        // Make sure that the compiler optimizes these two operations into a single operation.
+        let seconds = sample.0 / u64::from(sample_rate);
+        let samples_after = sample.0 % u64::from(sample_rate);
+
+        match u32::try_from(seconds) {
+            Ok(seconds) => Self {
+                seconds,
+                ticks: (samples_after as u32) * ticks_per_frame,
+            },
+            Err(_) => Self {
+                seconds: u32::MAX,
+                ticks: SUPER_SAMPLE_TICKS_PER_SECOND - 1,
+            },
         }
     }
 
     /// Get the time in [`SuperclockTime`] from the time in [`FrameTime`].
     ///
-    /// This conversion **IS** lossless if the sample rate happens to be equal to one of the
-    /// common sample rates: `22,050, 24,000, 44,100, 48,000, 88,200, 96,000, 176,400, 192,000,
-    /// 352,800, or 384,000`. This conversion is *NOT* lossless otherwise (especially if the
-    /// given `sample` value is very large).
+    /// This conversion **IS** lossless if the sample rate is a whole number that evenly divides
+    /// `SUPER_SAMPLE_TICKS_PER_SECOND`, which includes all of the common sample rates: `8,000,
+    /// 22,050, 24,000, 32,000, 44,100, 48,000, 88,200, 96,000, 176,400, 192,000, 352,800, and
+    /// 384,000`. This conversion is *NOT* lossless otherwise (especially if the given `sample`
+    /// value is very large).
+    ///
+    /// If the time is too large to fit, then the maximum value will be returned.
     ///
     /// [`SuperclockTime`]: struct.SuperclockTime.html
     /// [`FrameTime`]: struct.FrameTime.html
@@ -244,8 +299,69 @@ impl SuperclockTime {
             384_000 => Self::from_frame_with_common_framerate::<384_000>(sample),
             22_050 => Self::from_frame_with_common_framerate::<22_050>(sample),
             24_000 => Self::from_frame_with_common_framerate::<24_000>(sample),
-            _ => Self::from_seconds_f64(SecondsF64(sample.0 as f64 / sample_rate.as_f64())),
+            _ => {
+                if let Some(ticks_per_frame) = exact_ticks_per_frame(sample_rate) {
+                    Self::from_frame_exact(
+                        sample,
+                        SUPER_SAMPLE_TICKS_PER_SECOND / ticks_per_frame,
+                        ticks_per_frame,
+                    )
+                } else {
+                    Self::from_seconds_f64(SecondsF64(sample.0 as f64 / sample_rate.as_f64()))
+                }
+            }
+        }
+    }
+
+    /// Convert all of the given `frames` into [`SuperclockTime`] for the given [`SampleRate`],
+    /// appending the results to `out`.
+    ///
+    /// This is the inverse of `SuperclockTime::materialize_all()`, useful for storing recorded
+    /// events in a sample-rate independent format. See `SuperclockTime::from_frame()` for
+    /// details on when this conversion is lossless.
+    ///
+    /// [`SuperclockTime`]: struct.SuperclockTime.html
+    /// [`SampleRate`]: struct.SampleRate.html
+    pub fn from_frames_all(frames: &[FrameTime], sample_rate: SampleRate, out: &mut Vec<Self>) {
+        out.extend(frames.iter().map(|f| Self::from_frame(*f, sample_rate)));
+    }
+
+    /// Convert all of the given `events` into [`FrameTime`] (rounded to the nearest frame) for
+    /// the given [`SampleRate`], appending the results to `out`.
+    ///
+    /// For whole-number sample rates this uses integer math, so every event is rounded to
+    /// exactly the nearest frame (with ties rounded up), no matter how large it is. Because
+    /// `SuperclockTime` is exactly divisible by all of the common sample rates, events
+    /// recorded at one of them can be materialized again at that rate without any loss of
+    /// information. Other sample rates fall back to `SuperclockTime::to_nearest_frame_round()`.
+    ///
+    /// This should be called again whenever the sample rate changes.
+    ///
+    /// [`FrameTime`]: struct.FrameTime.html
+    /// [`SampleRate`]: struct.SampleRate.html
+    pub fn materialize_all(events: &[Self], sample_rate: SampleRate, out: &mut Vec<FrameTime>) {
+        out.extend(events.iter().map(|e| {
+            e.nearest_frame_round_integer(sample_rate)
+                .unwrap_or_else(|| e.to_nearest_frame_round(sample_rate))
+        }));
+    }
+
+    /// Convert to the nearest [`FrameTime`] with integer math, if the sample rate is a whole
+    /// number. Saturates at the maximum frame.
+    ///
+    /// [`FrameTime`]: struct.FrameTime.html
+    fn nearest_frame_round_integer(&self, sample_rate: SampleRate) -> Option<FrameTime> {
+        let sr = sample_rate.as_f64();
+        if !(sr >= 1.0 && sr.fract() == 0.0 && sr <= f64::from(u32::MAX)) {
+            return None;
         }
+
+        let ticks_per_second = u128::from(SUPER_SAMPLE_TICKS_PER_SECOND);
+        let frame = ((u128::from(self.total_ticks()) * u128::from(sr as u32))
+            + (ticks_per_second / 2))
+            / ticks_per_second;
+
+        Some(FrameTime(u64::try_from(frame).unwrap_or(u64::MAX)))
     }
 
     /// Convert to the corresponding time in [`SecondsF64`].
@@ -302,6 +418,19 @@ impl SuperclockTime {
         self.to_seconds_f64().to_nearest_frame_ceil(sample_rate)
     }
 
+    /// Returns the largest number of frames this time length covers across all of the given
+    /// sample `rates` (ceil-ed to the nearest frame).
+    ///
+    /// This is useful for preallocating a single buffer that can hold this time length at any
+    /// of the supported sample rates. If `rates` is empty, then this will return `FrameTime(0)`.
+    pub fn max_frames_over_rates(&self, rates: &[SampleRate]) -> FrameTime {
+        rates
+            .iter()
+            .map(|r| self.to_nearest_frame_ceil(*r))
+            .max_by_key(|f| f.0)
+            .unwrap_or_default()
+    }
+
     /// Try subtracting `rhs` from self. This will return `None` if the resulting value
     /// is negative due to `rhs` being larger than self (overflow).
     pub fn checked_sub(self, rhs: SuperclockTime) -> Option<SuperclockTime> {
@@ -316,37 +445,66 @@ impl SuperclockTime {
                     ticks: self.ticks - rhs.ticks,
                 })
             }
+        } else if rhs.ticks > self.ticks {
+            Some(SuperclockTime {
+                seconds: self.seconds - rhs.seconds - 1,
+                ticks: SUPER_SAMPLE_TICKS_PER_SECOND - (rhs.ticks - self.ticks),
+            })
         } else {
-            if rhs.ticks > self.ticks {
-                Some(SuperclockTime {
-                    seconds: self.seconds - rhs.seconds - 1,
-                    ticks: SUPER_SAMPLE_TICKS_PER_SECOND - (rhs.ticks - self.ticks),
-                })
-            } else {
-                Some(SuperclockTime {
-                    seconds: self.seconds - rhs.seconds,
-                    ticks: self.ticks - rhs.ticks,
-                })
-            }
+            Some(SuperclockTime {
+                seconds: self.seconds - rhs.seconds,
+                ticks: self.ticks - rhs.ticks,
+            })
         }
     }
-}
 
-impl PartialEq for SuperclockTime {
-    fn eq(&self, other: &Self) -> bool {
-        self.seconds == other.seconds && self.ticks == other.ticks
+    /// Try adding `rhs` to self. This will return `None` if the resulting number of seconds
+    /// does not fit in a `u32` (overflow).
+    pub fn checked_add(self, rhs: SuperclockTime) -> Option<SuperclockTime> {
+        let mut seconds = self.seconds.checked_add(rhs.seconds)?;
+        let mut ticks = self.ticks + rhs.ticks;
+
+        if ticks >= SUPER_SAMPLE_TICKS_PER_SECOND {
+            ticks -= SUPER_SAMPLE_TICKS_PER_SECOND;
+            seconds = seconds.checked_add(1)?;
+        }
+
+        Some(Self { seconds, ticks })
+    }
+
+    /// Add `rhs` to self, returning the maximum possible superclock time if the result would
+    /// overflow.
+    pub fn saturating_add(self, rhs: SuperclockTime) -> SuperclockTime {
+        self.checked_add(rhs).unwrap_or(SuperclockTime {
+            seconds: u32::MAX,
+            ticks: SUPER_SAMPLE_TICKS_PER_SECOND - 1,
+        })
+    }
+
+    /// Subtract `rhs` from self, returning a time of zero if the result would be negative.
+    pub fn saturating_sub(self, rhs: SuperclockTime) -> SuperclockTime {
+        self.checked_sub(rhs).unwrap_or_default()
     }
 }
 
-impl Eq for SuperclockTime {}
+/// The number of ticks in a single frame, if the sample rate is a whole number that evenly
+/// divides `SUPER_SAMPLE_TICKS_PER_SECOND`.
+pub(super) fn exact_ticks_per_frame(sample_rate: SampleRate) -> Option<u32> {
+    let sr = sample_rate.as_f64();
+    if sr >= 1.0
+        && sr.fract() == 0.0
+        && sr <= f64::from(SUPER_SAMPLE_TICKS_PER_SECOND)
+        && SUPER_SAMPLE_TICKS_PER_SECOND % (sr as u32) == 0
+    {
+        Some(SUPER_SAMPLE_TICKS_PER_SECOND / sr as u32)
+    } else {
+        None
+    }
+}
 
 impl PartialOrd for SuperclockTime {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.seconds != other.seconds {
-            self.seconds.partial_cmp(&other.seconds)
-        } else {
-            self.ticks.partial_cmp(&other.ticks)
-        }
+        Some(self.cmp(other))
     }
 }
 
@@ -382,7 +540,7 @@ impl Mul<u32> for SuperclockTime {
 
         if ticks >= u64::from(SUPER_SAMPLE_TICKS_PER_SECOND) {
             seconds += (ticks / u64::from(SUPER_SAMPLE_TICKS_PER_SECOND)) as u32;
-            ticks = ticks % u64::from(SUPER_SAMPLE_TICKS_PER_SECOND);
+            ticks %= u64::from(SUPER_SAMPLE_TICKS_PER_SECOND);
         }
 
         Self {
@@ -392,6 +550,20 @@ impl Mul<u32> for SuperclockTime {
     }
 }
 
+/// The remainder of dividing `self` by `rhs` (i.e. for wrapping a time around a loop length).
+///
+/// Because `SuperclockTime` is unsigned, this is the same as the Euclidean remainder.
+///
+/// # Panics
+///
+/// This will panic if `rhs` is zero.
+impl Rem<SuperclockTime> for SuperclockTime {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self::Output {
+        Self::from_total_ticks(self.total_ticks() % rhs.total_ticks())
+    }
+}
+
 impl AddAssign<SuperclockTime> for SuperclockTime {
     fn add_assign(&mut self, other: Self) {
         *self = *self + other
@@ -402,3 +574,182 @@ impl MulAssign<u32> for SuperclockTime {
         *self = *self * other
     }
 }
+impl RemAssign<SuperclockTime> for SuperclockTime {
+    fn rem_assign(&mut self, other: Self) {
+        *self = *self % other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_frame_uncommon_rates() {
+        // 32 kHz and 8 kHz are not in the table of common rates, but still divide the clock.
+        assert_eq!(
+            SuperclockTime::from_frame(FrameTime(32_001), SampleRate(32_000.0)),
+            SuperclockTime::new(1, 8_820)
+        );
+        assert_eq!(
+            SuperclockTime::from_frame(FrameTime(8_000 * 3_600 + 7), SampleRate(8_000.0)),
+            SuperclockTime::new(3_600, 7 * 35_280)
+        );
+
+        // 50 kHz does not divide the clock, so a single frame (5,644.8 ticks) is rounded.
+        let t = SuperclockTime::from_frame(FrameTime(1), SampleRate(50_000.0));
+        assert_eq!(t.seconds, 0);
+        assert!((t.ticks as i64 - 5_645).abs() <= 1);
+        assert_eq!(
+            SuperclockTime::from_frame(FrameTime(100_000), SampleRate(50_000.0)),
+            SuperclockTime::from_seconds(2)
+        );
+    }
+
+    #[test]
+    fn test_from_frame_saturates() {
+        let max = SuperclockTime::new(u32::MAX, SUPER_SAMPLE_TICKS_PER_SECOND - 1);
+
+        for sample_rate in [48_000.0, 32_000.0] {
+            let sample_rate = SampleRate(sample_rate);
+            let last_second = FrameTime(u64::from(u32::MAX) * sample_rate.0 as u64);
+
+            assert_eq!(
+                SuperclockTime::from_frame(last_second, sample_rate),
+                SuperclockTime::from_seconds(u32::MAX)
+            );
+            assert_eq!(
+                SuperclockTime::from_frame(FrameTime(last_second.0 * 2), sample_rate),
+                max
+            );
+            assert_eq!(
+                SuperclockTime::from_frame(FrameTime(u64::MAX), sample_rate),
+                max
+            );
+        }
+    }
+
+    #[test]
+    fn test_checked_saturating() {
+        let max = SuperclockTime::new(u32::MAX, SUPER_SAMPLE_TICKS_PER_SECOND - 1);
+        let half = SuperclockTime::new(0, SUPER_SAMPLE_TICKS_PER_SECOND / 2);
+
+        // The ticks carry over into the seconds.
+        assert_eq!(
+            half.checked_add(half),
+            Some(SuperclockTime::from_seconds(1))
+        );
+        assert_eq!(
+            SuperclockTime::new(u32::MAX - 1, 0).checked_add(half + half),
+            Some(SuperclockTime::from_seconds(u32::MAX))
+        );
+
+        assert_eq!(max.checked_add(SuperclockTime::new(0, 1)), None);
+        assert_eq!(
+            SuperclockTime::new(u32::MAX, half.ticks).checked_add(half),
+            None
+        );
+        assert_eq!(max.saturating_add(SuperclockTime::from_seconds(1)), max);
+        assert_eq!(max.saturating_add(SuperclockTime::default()), max);
+
+        assert_eq!(SuperclockTime::from_seconds(1).saturating_sub(half), half);
+        assert_eq!(
+            half.saturating_sub(SuperclockTime::from_seconds(1)),
+            SuperclockTime::default()
+        );
+    }
+
+    #[test]
+    fn test_rem() {
+        let a = SuperclockTime::new(7, 100);
+        let b = SuperclockTime::new(2, 50);
+
+        assert_eq!((a % b).total_ticks(), a.total_ticks() % b.total_ticks());
+        assert_eq!(a % b, SuperclockTime::new(0, 282_240_000 - 50));
+
+        let mut c = a;
+        c %= SuperclockTime::from_seconds(7);
+        assert_eq!(c, SuperclockTime::new(0, 100));
+    }
+
+    #[test]
+    fn test_max_frames_over_rates() {
+        let rates = [SampleRate(44_100.0), SampleRate(192_000.0)];
+
+        assert_eq!(
+            SuperclockTime::from_seconds(2).max_frames_over_rates(&rates),
+            FrameTime(384_000)
+        );
+        assert_eq!(
+            SuperclockTime::new(0, SUPER_SAMPLE_TICKS_PER_SECOND / 100)
+                .max_frames_over_rates(&rates),
+            FrameTime(1_920)
+        );
+        assert_eq!(
+            SuperclockTime::from_seconds(2).max_frames_over_rates(&[]),
+            FrameTime(0)
+        );
+    }
+
+    #[test]
+    fn test_materialize_all() {
+        let events = [
+            SuperclockTime::new(0, 0),
+            SuperclockTime::new(0, SUPER_SAMPLE_TICKS_PER_SECOND / 2),
+            SuperclockTime::new(1, SUPER_SAMPLE_TICKS_PER_SECOND / 2),
+            SuperclockTime::new(10, SUPER_SAMPLE_TICKS_PER_SECOND / 300),
+            SuperclockTime::new(3_600, SUPER_SAMPLE_TICKS_PER_SECOND / 300),
+        ];
+
+        let mut frames_44100 = Vec::new();
+        SuperclockTime::materialize_all(&events, SampleRate(44_100.0), &mut frames_44100);
+        assert_eq!(
+            frames_44100,
+            vec![
+                FrameTime(0),
+                FrameTime(22_050),
+                FrameTime(66_150),
+                FrameTime(441_147),
+                FrameTime(158_760_147),
+            ]
+        );
+
+        let mut frames_48000 = Vec::new();
+        SuperclockTime::materialize_all(&events, SampleRate(48_000.0), &mut frames_48000);
+        assert_eq!(
+            frames_48000,
+            vec![
+                FrameTime(0),
+                FrameTime(24_000),
+                FrameTime(72_000),
+                FrameTime(480_160),
+                FrameTime(172_800_160),
+            ]
+        );
+
+        let mut recorded = Vec::new();
+        SuperclockTime::from_frames_all(&frames_48000, SampleRate(48_000.0), &mut recorded);
+        assert_eq!(&recorded[..], &events[..]);
+
+        // Events are rounded to exactly the nearest frame, even where `f64` doesn't have
+        // enough precision. A frame at 48 kHz is 5,880 ticks long.
+        let events = [
+            SuperclockTime::new(u32::MAX, 2_939),
+            SuperclockTime::new(u32::MAX, 2_940),
+        ];
+        let mut frames = Vec::new();
+        SuperclockTime::materialize_all(&events, SampleRate(48_000.0), &mut frames);
+        let last_second = u64::from(u32::MAX) * 48_000;
+        assert_eq!(
+            frames,
+            vec![FrameTime(last_second), FrameTime(last_second + 1)]
+        );
+
+        let mut recorded = Vec::new();
+        let frames = [FrameTime(last_second + 47_999)];
+        SuperclockTime::from_frames_all(&frames, SampleRate(48_000.0), &mut recorded);
+        frames_48000.clear();
+        SuperclockTime::materialize_all(&recorded, SampleRate(48_000.0), &mut frames_48000);
+        assert_eq!(&frames_48000[..], &frames[..]);
+    }
+}
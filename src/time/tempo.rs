@@ -0,0 +1,374 @@
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-derive")]
+use std::convert::TryFrom;
+
+use super::{MusicalTime, MusicalTimeRange, SecondsF64};
+
+/// A tempo expressed as the length of one beat in seconds (the reciprocal of beats per
+/// second).
+///
+/// Precomputing this lets bulk conversions from musical time to seconds use a multiply
+/// instead of a division for every value.
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct SecondsPerBeat(pub f64);
+
+impl SecondsPerBeat {
+    /// The length of one beat at the given tempo in beats per minute.
+    pub fn from_bpm(bpm: f64) -> Self {
+        Self(60.0 / bpm)
+    }
+
+    /// The tempo in beats per minute.
+    pub fn to_bpm(&self) -> f64 {
+        60.0 / self.0
+    }
+}
+
+/// A smooth (linear-in-beats) tempo change over a region of musical time, such as an
+/// accelerando or a ritardando.
+///
+/// Before the region the tempo is constant at `start_bpm`, and after the region the tempo
+/// is constant at `end_bpm`.
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TempoRamp {
+    pub start_bpm: f64,
+    pub end_bpm: f64,
+    pub region: MusicalTimeRange,
+}
+
+impl TempoRamp {
+    pub fn new(start_bpm: f64, end_bpm: f64, region: MusicalTimeRange) -> Self {
+        Self {
+            start_bpm,
+            end_bpm,
+            region,
+        }
+    }
+
+    /// The tempo in beats per minute at the musical time `t`.
+    pub fn bpm_at(&self, t: MusicalTime) -> f64 {
+        if t <= self.region.start {
+            return self.start_bpm;
+        }
+        if t >= self.region.end {
+            return self.end_bpm;
+        }
+
+        let length = self.region.length().as_beats_f64();
+        let beats = t.as_beats_f64() - self.region.start.as_beats_f64();
+
+        self.start_bpm + ((self.end_bpm - self.start_bpm) * beats / length)
+    }
+
+    /// The time in seconds from the start of the region to the musical time `t`, taking the
+    /// tempo ramp into account.
+    ///
+    /// This integrates `60 / bpm` over beats, so it will exactly match the constant-tempo
+    /// conversion when `start_bpm == end_bpm`.
+    ///
+    /// If `t` lies before the start of the region, then the returned value will be negative.
+    ///
+    /// Note that this conversion is *NOT* lossless.
+    pub fn musical_to_seconds(&self, t: MusicalTime) -> SecondsF64 {
+        if t <= self.region.start {
+            let beats = self.region.start.as_beats_f64() - t.as_beats_f64();
+            return SecondsF64(-beats * 60.0 / self.start_bpm);
+        }
+
+        let length = self.region.length().as_beats_f64();
+        let beats = t.as_beats_f64() - self.region.start.as_beats_f64();
+
+        if beats <= length {
+            SecondsF64(self.ramp_seconds(beats, length))
+        } else {
+            SecondsF64(self.ramp_seconds(length, length) + ((beats - length) * 60.0 / self.end_bpm))
+        }
+    }
+
+    /// The integral of `60 / bpm` from the start of the ramp to `beats` into the ramp.
+    fn ramp_seconds(&self, beats: f64, length: f64) -> f64 {
+        let delta_bpm = self.end_bpm - self.start_bpm;
+
+        if delta_bpm.abs() < 1.0e-9 || length <= 0.0 {
+            beats * 60.0 / self.start_bpm
+        } else {
+            let bpm = self.start_bpm + (delta_bpm * beats / length);
+            60.0 * length / delta_bpm * (bpm / self.start_bpm).ln()
+        }
+    }
+}
+
+/// A map of (instantaneous) tempo changes throughout a song, for converting between musical
+/// time and seconds in projects where the tempo is not constant.
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-derive", serde(try_from = "TempoMapData"))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TempoMap {
+    /// Sorted by time, with the first change always lying at time `0`. The tempo is given
+    /// in beats per minute.
+    changes: Vec<(MusicalTime, f64)>,
+}
+
+impl TempoMap {
+    /// Create a new map with the given tempo (in beats per minute) at the start of the song.
+    pub fn new(initial_bpm: f64) -> Self {
+        Self {
+            changes: vec![(MusicalTime::default(), initial_bpm)],
+        }
+    }
+
+    /// Insert a tempo change (in beats per minute) at time `t`. This replaces any existing
+    /// change at the same time.
+    pub fn insert(&mut self, t: MusicalTime, bpm: f64) {
+        match self
+            .changes
+            .binary_search_by(|(change_t, _)| change_t.cmp(&t))
+        {
+            Ok(i) => self.changes[i].1 = bpm,
+            Err(i) => self.changes.insert(i, (t, bpm)),
+        }
+    }
+
+    /// Remove the tempo change at time `t`. Returns `true` if a change was removed.
+    ///
+    /// The initial tempo at time `0` cannot be removed (use `insert()` to replace it
+    /// instead).
+    pub fn remove(&mut self, t: MusicalTime) -> bool {
+        if t == MusicalTime::default() {
+            return false;
+        }
+
+        if let Ok(i) = self
+            .changes
+            .binary_search_by(|(change_t, _)| change_t.cmp(&t))
+        {
+            self.changes.remove(i);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// All tempo changes (in beats per minute), sorted by time.
+    pub fn changes(&self) -> &[(MusicalTime, f64)] {
+        &self.changes
+    }
+
+    /// The tempo in beats per minute at time `t`.
+    pub fn bpm_at(&self, t: MusicalTime) -> f64 {
+        let i = self.changes.partition_point(|(change_t, _)| *change_t <= t);
+        self.changes[i.saturating_sub(1)].1
+    }
+
+    /// Convert the musical time `t` to the corresponding time in seconds from the start of
+    /// the song, taking every tempo change before `t` into account.
+    ///
+    /// With a single tempo, this gives exactly the same result as
+    /// `MusicalTime::to_seconds_f64()`.
+    ///
+    /// Note that this conversion is *NOT* lossless.
+    pub fn musical_to_seconds(&self, t: MusicalTime) -> SecondsF64 {
+        let mut seconds = 0.0;
+
+        for (i, (start, bpm)) in self.changes.iter().enumerate() {
+            match self.changes.get(i + 1) {
+                Some((next, _)) if *next <= t => {
+                    let beats = next.checked_sub(*start).unwrap_or_default().as_beats_f64();
+                    seconds += beats * 60.0 / bpm;
+                }
+                _ => {
+                    let beats = t.checked_sub(*start).unwrap_or_default().as_beats_f64();
+                    return SecondsF64(seconds + (beats * 60.0 / bpm));
+                }
+            }
+        }
+
+        unreachable!()
+    }
+
+    /// Convert the time in seconds from the start of the song to the corresponding musical
+    /// time, taking every tempo change before that time into account. This is the inverse of
+    /// `TempoMap::musical_to_seconds()`.
+    ///
+    /// With a single tempo, this gives exactly the same result as
+    /// `SecondsF64::to_musical()`.
+    ///
+    /// Note that this conversion is *NOT* lossless.
+    ///
+    /// If `seconds` is negative, then a musical time of `0` will be returned.
+    pub fn seconds_to_musical(&self, seconds: SecondsF64) -> MusicalTime {
+        let mut start_seconds = 0.0;
+
+        for (i, (start, bpm)) in self.changes.iter().enumerate() {
+            if let Some((next, _)) = self.changes.get(i + 1) {
+                let beats = next.checked_sub(*start).unwrap_or_default().as_beats_f64();
+                let end_seconds = start_seconds + (beats * 60.0 / bpm);
+
+                if end_seconds <= seconds.0 {
+                    start_seconds = end_seconds;
+                    continue;
+                }
+            }
+
+            return *start
+                + MusicalTime::from_beats_f64((seconds.0 - start_seconds) * (bpm / 60.0));
+        }
+
+        unreachable!()
+    }
+}
+
+impl Default for TempoMap {
+    fn default() -> Self {
+        Self::new(120.0)
+    }
+}
+
+/// The serialized form of a [`TempoMap`], which is checked before it is converted.
+///
+/// [`TempoMap`]: struct.TempoMap.html
+#[cfg(feature = "serde-derive")]
+#[derive(Deserialize)]
+struct TempoMapData {
+    changes: Vec<(MusicalTime, f64)>,
+}
+
+#[cfg(feature = "serde-derive")]
+impl TryFrom<TempoMapData> for TempoMap {
+    type Error = &'static str;
+
+    fn try_from(data: TempoMapData) -> Result<Self, Self::Error> {
+        if !super::time_signature::is_valid_change_list(&data.changes) {
+            return Err("tempo changes must be sorted by time and start at time 0");
+        }
+
+        Ok(Self {
+            changes: data.changes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_tempo_ramp() {
+        let ramp = TempoRamp::new(
+            130.0,
+            130.0,
+            MusicalTimeRange::new(MusicalTime::from_beats(0), MusicalTime::from_beats(8)),
+        );
+
+        for t in [
+            MusicalTime::from_beats(0),
+            MusicalTime::from_quarter_beats(3, 1),
+            MusicalTime::from_beats(8),
+            MusicalTime::from_third_beats(12, 2),
+        ] {
+            assert!((ramp.musical_to_seconds(t).0 - t.to_seconds_f64(130.0).0).abs() < 1.0e-9);
+        }
+    }
+
+    #[test]
+    fn test_tempo_ramp() {
+        let ramp = TempoRamp::new(
+            120.0,
+            240.0,
+            MusicalTimeRange::new(MusicalTime::from_beats(4), MusicalTime::from_beats(8)),
+        );
+
+        assert_eq!(ramp.bpm_at(MusicalTime::from_beats(2)), 120.0);
+        assert_eq!(ramp.bpm_at(MusicalTime::from_beats(6)), 180.0);
+        assert_eq!(ramp.bpm_at(MusicalTime::from_beats(10)), 240.0);
+
+        // 60 * 4 / (240 - 120) * ln(180 / 120)
+        let expected = 2.0 * 1.5f64.ln();
+        assert!((ramp.musical_to_seconds(MusicalTime::from_beats(6)).0 - expected).abs() < 1.0e-9);
+
+        // 60 * 4 / (240 - 120) * ln(240 / 120) + (one beat at 240 BPM)
+        let expected = (2.0 * 2.0f64.ln()) + 0.25;
+        assert!((ramp.musical_to_seconds(MusicalTime::from_beats(9)).0 - expected).abs() < 1.0e-9);
+
+        // One beat at 120 BPM before the region.
+        assert!((ramp.musical_to_seconds(MusicalTime::from_beats(3)).0 + 0.5).abs() < 1.0e-9);
+    }
+
+    #[cfg(feature = "serde-derive")]
+    #[test]
+    fn test_tempo_map_serde_rejects_invalid_data() {
+        let mut map = TempoMap::new(100.0);
+        map.insert(MusicalTime::from_beats(8), 140.0);
+
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(serde_json::from_str::<TempoMap>(&json).unwrap(), map);
+
+        let t = |beats| format!(r#"{{"beats":{},"ticks":0}}"#, beats);
+        for changes in [
+            String::new(),
+            format!("[{},120.0]", t(4)),
+            format!("[{},120.0],[{},90.0],[{},100.0]", t(0), t(8), t(4)),
+            format!("[{},120.0],[{},90.0]", t(0), t(0)),
+        ] {
+            let json = format!(r#"{{"changes":[{}]}}"#, changes);
+            assert!(serde_json::from_str::<TempoMap>(&json).is_err());
+        }
+    }
+
+    #[test]
+    fn test_constant_tempo_map() {
+        let map = TempoMap::new(137.0);
+
+        for t in [
+            MusicalTime::from_beats(0),
+            MusicalTime::from_quarter_beats(3, 1),
+            MusicalTime::from_third_beats(1_000, 2),
+        ] {
+            assert_eq!(map.musical_to_seconds(t), t.to_seconds_f64(137.0));
+
+            let seconds = t.to_seconds_f64(137.0);
+            assert_eq!(map.seconds_to_musical(seconds), seconds.to_musical(137.0));
+        }
+    }
+
+    #[test]
+    fn test_tempo_map() {
+        let mut map = TempoMap::new(120.0);
+        map.insert(MusicalTime::from_beats(8), 60.0);
+
+        assert_eq!(map.bpm_at(MusicalTime::from_beats(7)), 120.0);
+        assert_eq!(map.bpm_at(MusicalTime::from_beats(8)), 60.0);
+
+        // 8 beats at 120 BPM = 4 seconds, then 2 beats at 60 BPM = 2 seconds.
+        let t = MusicalTime::from_beats(10);
+        assert_eq!(map.musical_to_seconds(t), SecondsF64(6.0));
+        assert_eq!(map.seconds_to_musical(SecondsF64(6.0)), t);
+
+        // The change itself.
+        assert_eq!(
+            map.musical_to_seconds(MusicalTime::from_beats(8)),
+            SecondsF64(4.0)
+        );
+        assert_eq!(
+            map.seconds_to_musical(SecondsF64(4.0)),
+            MusicalTime::from_beats(8)
+        );
+
+        // Before the change.
+        assert_eq!(
+            map.seconds_to_musical(SecondsF64(1.25)),
+            MusicalTime::from_half_beats(2, 1)
+        );
+        assert_eq!(
+            map.seconds_to_musical(SecondsF64(-1.0)),
+            MusicalTime::default()
+        );
+
+        assert!(!map.remove(MusicalTime::default()));
+        assert!(map.remove(MusicalTime::from_beats(8)));
+        assert_eq!(map.musical_to_seconds(t), SecondsF64(5.0));
+    }
+}
@@ -0,0 +1,325 @@
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+
+use std::convert::TryFrom;
+
+use super::{MusicalTime, SUPER_BEAT_TICKS_PER_BEAT};
+
+/// A musical time signature (i.e. `4/4`, `6/8`).
+///
+/// Note that a "beat" in [`MusicalTime`] is always a quarter note, regardless of the
+/// denominator of the time signature.
+///
+/// [`MusicalTime`]: struct.MusicalTime.html
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-derive", serde(try_from = "TimeSignatureData"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeSignature {
+    /// The number of notes in a bar.
+    pub numerator: u32,
+    /// The note value of each note in a bar (i.e. `4` for quarter notes, `8` for eighth notes).
+    pub denominator: u32,
+}
+
+impl TimeSignature {
+    /// * `numerator` - The number of notes in a bar.
+    /// * `denominator` - The note value of each note in a bar (i.e. `4` for quarter notes,
+    ///   `8` for eighth notes). This must be greater than `0`.
+    pub const fn new(numerator: u32, denominator: u32) -> Self {
+        assert!(denominator > 0);
+
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// The length of a single bar in this time signature.
+    pub fn bar_length(&self) -> MusicalTime {
+        MusicalTime::from_total_ticks(self.bar_length_ticks())
+    }
+
+    /// The length of a single bar in this time signature in ticks.
+    ///
+    /// A "tick" is a unit of time equal to `1 / 1,241,856,000` of a beat.
+    ///
+    /// A denominator of `0` is treated as `1`.
+    pub fn bar_length_ticks(&self) -> u64 {
+        (u64::from(self.numerator) * u64::from(SUPER_BEAT_TICKS_PER_BEAT) * 4)
+            / u64::from(self.denominator.max(1))
+    }
+}
+
+/// The serialized form of a [`TimeSignature`], which is checked before it is converted.
+///
+/// [`TimeSignature`]: struct.TimeSignature.html
+#[cfg(feature = "serde-derive")]
+#[derive(Deserialize)]
+struct TimeSignatureData {
+    numerator: u32,
+    denominator: u32,
+}
+
+#[cfg(feature = "serde-derive")]
+impl TryFrom<TimeSignatureData> for TimeSignature {
+    type Error = &'static str;
+
+    fn try_from(data: TimeSignatureData) -> Result<Self, Self::Error> {
+        if data.denominator == 0 {
+            return Err("the denominator of a time signature must be greater than 0");
+        }
+
+        Ok(Self {
+            numerator: data.numerator,
+            denominator: data.denominator,
+        })
+    }
+}
+
+impl Default for TimeSignature {
+    fn default() -> Self {
+        Self {
+            numerator: 4,
+            denominator: 4,
+        }
+    }
+}
+
+/// A map of time signature changes throughout a song.
+///
+/// A time signature change always starts a new bar. If a change does not lie on a bar line of
+/// the previous time signature, then the last (partial) bar of the previous time signature is
+/// still counted as a whole bar.
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-derive", serde(try_from = "TimeSignatureMapData"))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TimeSignatureMap {
+    /// Sorted by time, with the first change always lying at time `0`.
+    changes: Vec<(MusicalTime, TimeSignature)>,
+}
+
+impl TimeSignatureMap {
+    /// Create a new map with the given time signature at the start of the song.
+    pub fn new(initial: TimeSignature) -> Self {
+        Self {
+            changes: vec![(MusicalTime::default(), initial)],
+        }
+    }
+
+    /// Insert a time signature change at time `t`. This replaces any existing change at the
+    /// same time.
+    pub fn insert(&mut self, t: MusicalTime, time_signature: TimeSignature) {
+        match self
+            .changes
+            .binary_search_by(|(change_t, _)| change_t.cmp(&t))
+        {
+            Ok(i) => self.changes[i].1 = time_signature,
+            Err(i) => self.changes.insert(i, (t, time_signature)),
+        }
+    }
+
+    /// Remove the time signature change at time `t`. Returns `true` if a change was removed.
+    ///
+    /// The initial time signature at time `0` cannot be removed (use `insert()` to replace it
+    /// instead).
+    pub fn remove(&mut self, t: MusicalTime) -> bool {
+        if t == MusicalTime::default() {
+            return false;
+        }
+
+        if let Ok(i) = self
+            .changes
+            .binary_search_by(|(change_t, _)| change_t.cmp(&t))
+        {
+            self.changes.remove(i);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// All time signature changes, sorted by time.
+    pub fn changes(&self) -> &[(MusicalTime, TimeSignature)] {
+        &self.changes
+    }
+
+    /// The time signature at time `t`.
+    pub fn time_signature_at(&self, t: MusicalTime) -> TimeSignature {
+        let i = self.changes.partition_point(|(change_t, _)| *change_t <= t);
+        self.changes[i.saturating_sub(1)].1
+    }
+
+    /// Returns the index of the bar (starting from `0`) containing time `t`, along with the
+    /// time at which that bar starts.
+    ///
+    /// The index is signed so that it can be shifted for bars before the start of the song
+    /// (i.e. subtracting `1` when the song starts with a pickup bar).
+    pub fn bar_at(&self, t: MusicalTime) -> (i64, MusicalTime) {
+        let t_ticks = t.total_ticks();
+        let mut bars = 0;
+
+        for (i, (start, time_signature)) in self.changes.iter().enumerate() {
+            let start = start.total_ticks();
+            let bar_len = time_signature.bar_length_ticks().max(1);
+
+            match self.changes.get(i + 1) {
+                Some((next, _)) if next.total_ticks() <= t_ticks => {
+                    bars += (next.total_ticks() - start).div_ceil(bar_len);
+                }
+                _ => {
+                    let bars_in_section = (t_ticks - start) / bar_len;
+                    return (
+                        i64::try_from(bars + bars_in_section).unwrap_or(i64::MAX),
+                        MusicalTime::from_total_ticks(start + (bars_in_section * bar_len)),
+                    );
+                }
+            }
+        }
+
+        unreachable!()
+    }
+}
+
+impl Default for TimeSignatureMap {
+    fn default() -> Self {
+        Self::new(TimeSignature::default())
+    }
+}
+
+/// The serialized form of a [`TimeSignatureMap`], which is checked before it is converted.
+///
+/// [`TimeSignatureMap`]: struct.TimeSignatureMap.html
+#[cfg(feature = "serde-derive")]
+#[derive(Deserialize)]
+struct TimeSignatureMapData {
+    changes: Vec<(MusicalTime, TimeSignature)>,
+}
+
+#[cfg(feature = "serde-derive")]
+impl TryFrom<TimeSignatureMapData> for TimeSignatureMap {
+    type Error = &'static str;
+
+    fn try_from(data: TimeSignatureMapData) -> Result<Self, Self::Error> {
+        if !is_valid_change_list(&data.changes) {
+            return Err("time signature changes must be sorted by time and start at time 0");
+        }
+
+        Ok(Self {
+            changes: data.changes,
+        })
+    }
+}
+
+/// Returns `true` if the list of changes is not empty, starts at time `0`, and is sorted by
+/// time with no duplicates.
+#[cfg(feature = "serde-derive")]
+pub(super) fn is_valid_change_list<T>(changes: &[(MusicalTime, T)]) -> bool {
+    match changes.first() {
+        Some((first, _)) if *first == MusicalTime::default() => {
+            changes.windows(2).all(|w| w[0].0 < w[1].0)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_at_across_meter_change() {
+        let mut map = TimeSignatureMap::default();
+        // Two bars of 4/4, then 3/4.
+        map.insert(MusicalTime::from_beats(8), TimeSignature::new(3, 4));
+
+        assert_eq!(
+            map.bar_at(MusicalTime::from_beats(0)),
+            (0, MusicalTime::from_beats(0))
+        );
+        assert_eq!(
+            map.bar_at(MusicalTime::from_half_beats(7, 1)),
+            (1, MusicalTime::from_beats(4))
+        );
+        assert_eq!(
+            map.bar_at(MusicalTime::from_beats(8)),
+            (2, MusicalTime::from_beats(8))
+        );
+        assert_eq!(
+            map.bar_at(MusicalTime::from_beats(10)),
+            (2, MusicalTime::from_beats(8))
+        );
+        assert_eq!(
+            map.bar_at(MusicalTime::from_beats(11)),
+            (3, MusicalTime::from_beats(11))
+        );
+        assert_eq!(
+            map.bar_at(MusicalTime::from_beats(20)),
+            (6, MusicalTime::from_beats(20))
+        );
+
+        assert_eq!(
+            map.time_signature_at(MusicalTime::from_beats(7)),
+            TimeSignature::new(4, 4)
+        );
+        assert_eq!(
+            map.time_signature_at(MusicalTime::from_beats(8)),
+            TimeSignature::new(3, 4)
+        );
+    }
+
+    #[cfg(feature = "serde-derive")]
+    #[test]
+    fn test_serde_rejects_invalid_data() {
+        let mut map = TimeSignatureMap::default();
+        map.insert(MusicalTime::from_beats(8), TimeSignature::new(3, 4));
+
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TimeSignatureMap>(&json).unwrap(),
+            map
+        );
+
+        let t = |beats| format!(r#"{{"beats":{},"ticks":0}}"#, beats);
+        let ts = r#"{"numerator":4,"denominator":4}"#;
+        for changes in [
+            String::new(),
+            format!("[{},{}]", t(4), ts),
+            format!("[{},{}],[{},{}],[{},{}]", t(0), ts, t(8), ts, t(4), ts),
+            format!("[{},{}],[{},{}]", t(0), ts, t(0), ts),
+        ] {
+            let json = format!(r#"{{"changes":[{}]}}"#, changes);
+            assert!(serde_json::from_str::<TimeSignatureMap>(&json).is_err());
+        }
+
+        assert!(
+            serde_json::from_str::<TimeSignature>(r#"{"numerator":4,"denominator":0}"#).is_err()
+        );
+        assert_eq!(
+            serde_json::from_str::<TimeSignature>(r#"{"numerator":6,"denominator":8}"#).unwrap(),
+            TimeSignature::new(6, 8)
+        );
+    }
+
+    #[test]
+    fn test_partial_bar_before_meter_change() {
+        let mut map = TimeSignatureMap::default();
+        // The change lies in the middle of the second 4/4 bar.
+        map.insert(MusicalTime::from_beats(6), TimeSignature::new(3, 4));
+
+        assert_eq!(
+            map.bar_at(MusicalTime::from_beats(5)),
+            (1, MusicalTime::from_beats(4))
+        );
+        assert_eq!(
+            map.bar_at(MusicalTime::from_beats(6)),
+            (2, MusicalTime::from_beats(6))
+        );
+
+        assert!(map.remove(MusicalTime::from_beats(6)));
+        assert!(!map.remove(MusicalTime::from_beats(0)));
+        assert_eq!(
+            map.bar_at(MusicalTime::from_beats(6)),
+            (1, MusicalTime::from_beats(4))
+        );
+    }
+}
@@ -0,0 +1,132 @@
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+
+use super::{FrameTime, MusicalTime, MusicalTimeRange, SampleRate, SecondsF64};
+
+/// The state of a simple transport with a constant tempo: the playhead, the tempo, the loop
+/// region, and whether it is playing.
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transport {
+    /// The current position of the playhead.
+    pub playhead: MusicalTime,
+    /// The tempo in beats per minute.
+    pub bpm: f64,
+    /// The loop region, or `None` if looping is disabled.
+    pub loop_range: Option<MusicalTimeRange>,
+    /// Whether the transport is currently playing. The playhead only advances while playing.
+    pub is_playing: bool,
+}
+
+impl Transport {
+    /// Create a new stopped transport at time `0` with looping disabled.
+    ///
+    /// * `bpm` - The tempo in beats per minute.
+    pub fn new(bpm: f64) -> Self {
+        Self {
+            playhead: MusicalTime::default(),
+            bpm,
+            loop_range: None,
+            is_playing: false,
+        }
+    }
+
+    /// Advance the playhead by the given number of frames (i.e. once per process block). This
+    /// does nothing if the transport is not playing.
+    ///
+    /// If looping is enabled and the playhead crosses the end of the loop region, then it
+    /// wraps back around to the start of the loop (see `MusicalTimeRange::map_position()`).
+    /// If the playhead was already at or after the end of the loop region, then it simply
+    /// advances without ever wrapping.
+    ///
+    /// Use `LoopingTransportIter` instead if the block needs to be split at the loop
+    /// boundary.
+    ///
+    /// * `frames` - The number of frames to advance by.
+    /// * `sample_rate` - The sample rate of the stream.
+    pub fn advance(&mut self, frames: FrameTime, sample_rate: SampleRate) {
+        if !self.is_playing {
+            return;
+        }
+
+        let before = self.playhead;
+        self.playhead += frames.to_musical(self.bpm, sample_rate);
+
+        if let Some(loop_range) = self.loop_range {
+            if before < loop_range.end {
+                self.playhead = loop_range.map_position(self.playhead);
+            }
+        }
+    }
+
+    /// The position of the playhead in seconds.
+    ///
+    /// Note that this conversion is *NOT* lossless.
+    pub fn seconds_position(&self) -> SecondsF64 {
+        self.playhead.to_seconds_f64(self.bpm)
+    }
+
+    /// The position of the playhead in frames, rounded to the nearest frame.
+    ///
+    /// Note that this conversion is *NOT* lossless.
+    ///
+    /// * `sample_rate` - The sample rate of the stream.
+    pub fn sample_position(&self, sample_rate: SampleRate) -> FrameTime {
+        self.playhead.to_nearest_frame_round(self.bpm, sample_rate)
+    }
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Self::new(120.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: SampleRate = SampleRate(48_000.0);
+
+    #[test]
+    fn test_advance() {
+        let mut transport = Transport::new(120.0);
+
+        // Stopped transports don't move.
+        transport.advance(FrameTime(48_000), SAMPLE_RATE);
+        assert_eq!(transport.playhead, MusicalTime::default());
+
+        // At 120 BPM, one second is two beats.
+        transport.is_playing = true;
+        transport.advance(FrameTime(48_000), SAMPLE_RATE);
+        assert_eq!(transport.playhead, MusicalTime::from_beats(2));
+        assert_eq!(transport.seconds_position(), SecondsF64(1.0));
+        assert_eq!(transport.sample_position(SAMPLE_RATE), FrameTime(48_000));
+
+        for _ in 0..375 {
+            transport.advance(FrameTime(128), SAMPLE_RATE);
+        }
+        assert_eq!(transport.playhead, MusicalTime::from_beats(4));
+        assert_eq!(transport.sample_position(SAMPLE_RATE), FrameTime(96_000));
+    }
+
+    #[test]
+    fn test_advance_loop_wrap() {
+        let mut transport = Transport::new(120.0);
+        transport.is_playing = true;
+        transport.loop_range = Some(MusicalTimeRange::new(
+            MusicalTime::from_beats(1),
+            MusicalTime::from_beats(5),
+        ));
+        transport.playhead = MusicalTime::from_half_beats(4, 1);
+
+        // Two beats from 4.5 crosses the loop end halfway through the block.
+        transport.advance(FrameTime(48_000), SAMPLE_RATE);
+        assert_eq!(transport.playhead, MusicalTime::from_half_beats(2, 1));
+
+        // A playhead past the end of the loop never wraps.
+        transport.playhead = MusicalTime::from_beats(6);
+        transport.advance(FrameTime(48_000), SAMPLE_RATE);
+        assert_eq!(transport.playhead, MusicalTime::from_beats(8));
+    }
+}
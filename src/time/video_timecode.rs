@@ -1,5 +1,8 @@
 // TODO: Flesh this out once I have a better idea how this should work.
 
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+
 /// The different framerate formats used with video encoding.
 ///
 /// Useful when editing the sound of video with the timeline.
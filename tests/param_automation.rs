@@ -0,0 +1,125 @@
+//! Simulates a host automation session, with a UI thread writing to a [`ParamF32Handle`]
+//! while an audio thread processes blocks with the [`ParamF32`].
+//!
+//! This also serves as documentation of the intended threading usage: the handle is moved
+//! to (or cloned into) the UI thread, while the parameter itself is owned by the audio
+//! thread. The two only communicate through the shared atomics, so neither side ever
+//! blocks the other.
+
+use std::sync::mpsc;
+use std::thread;
+
+use meadowlark_core_types::parameter::{Gradient, ParamF32, ParamF32Handle, Unit};
+use meadowlark_core_types::smooth::SmoothMode;
+use meadowlark_core_types::time::{SampleRate, SecondsF64};
+
+const SAMPLE_RATE: SampleRate = SampleRate(48_000.0);
+const SMOOTH_SECS: SecondsF64 = SecondsF64(0.005);
+const BLOCK_SIZE: usize = 64;
+
+fn new_param() -> (ParamF32, ParamF32Handle) {
+    let (mut param, handle) = ParamF32::from_value(
+        0.0,
+        0.0,
+        0.0,
+        1.0,
+        Gradient::Linear,
+        Unit::Generic,
+        SMOOTH_SECS,
+        SAMPLE_RATE,
+        BLOCK_SIZE,
+    );
+    // With linear smoothing, every change reaches its target in exactly the declick time.
+    param.set_smooth_mode(SmoothMode::Linear);
+
+    (param, handle)
+}
+
+/// Process blocks until the parameter has settled, returning the number of frames processed
+/// and the last smoothed value.
+fn process_until_settled(param: &mut ParamF32) -> (usize, f32) {
+    let mut frames = 0;
+    let mut last = param.smoothed(BLOCK_SIZE).values[BLOCK_SIZE - 1];
+    frames += BLOCK_SIZE;
+
+    while param.is_smoothing() && !param.reset_if_settled() {
+        last = param.smoothed(BLOCK_SIZE).values[BLOCK_SIZE - 1];
+        frames += BLOCK_SIZE;
+
+        assert!(frames < 100 * BLOCK_SIZE, "parameter never settled");
+    }
+
+    (frames, last)
+}
+
+#[test]
+fn test_smoothed_output_tracks_handle_within_declick_time() {
+    let (mut param, handle) = new_param();
+    let declick_frames = SMOOTH_SECS.to_nearest_frame_round(SAMPLE_RATE).0 as usize;
+
+    let (to_audio, from_ui) = mpsc::channel::<f32>();
+    let (to_ui, from_audio) = mpsc::channel::<()>();
+
+    let ui = thread::spawn(move || {
+        for value in [1.0, 0.25, 0.75, 0.0, 0.5] {
+            handle.set_value(value);
+
+            // Let the audio thread know a change was made, and wait until it has caught up
+            // before making the next one.
+            to_audio.send(value).unwrap();
+            from_audio.recv().unwrap();
+        }
+    });
+
+    while let Ok(value) = from_ui.recv() {
+        let (frames, last) = process_until_settled(&mut param);
+
+        // The change is picked up at the start of the first block, so it must have settled
+        // within the declick time (rounded up to the next block).
+        assert!(
+            frames <= declick_frames + BLOCK_SIZE,
+            "took {} frames to reach {}",
+            frames,
+            value
+        );
+        assert!((last - value).abs() < 1.0e-4, "{} != {}", last, value);
+        assert!(!param.is_smoothing());
+
+        to_ui.send(()).unwrap();
+    }
+
+    ui.join().unwrap();
+}
+
+#[test]
+fn test_free_running_automation_settles_on_last_value() {
+    let (mut param, handle) = new_param();
+    let ui_handle = handle.clone();
+
+    let (to_audio, from_ui) = mpsc::channel::<()>();
+    let (to_ui, from_audio) = mpsc::channel::<()>();
+
+    let ui = thread::spawn(move || {
+        for i in 0..=1_000 {
+            ui_handle.set_value(i as f32 / 1_000.0);
+
+            // Let the audio thread process a single block after each change, without waiting
+            // for the parameter to settle.
+            to_audio.send(()).unwrap();
+            from_audio.recv().unwrap();
+        }
+    });
+
+    // Keep processing while the UI thread is writing, as a host would.
+    while from_ui.recv().is_ok() {
+        let out = param.smoothed(BLOCK_SIZE);
+        assert!(out.values.iter().all(|v| (0.0..=1.0).contains(v)));
+
+        to_ui.send(()).unwrap();
+    }
+    ui.join().unwrap();
+
+    let (_, last) = process_until_settled(&mut param);
+    assert!((last - handle.value()).abs() < 1.0e-4);
+    assert_eq!(handle.value(), 1.0);
+}